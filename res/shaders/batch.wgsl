@@ -0,0 +1,64 @@
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) texcoord: vec2<f32>,
+    @location(3) texture_index: u32,
+    @location(4) flags: u32,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) texcoord: vec2<f32>,
+    @location(2) texture_index: u32,
+    @location(3) flags: u32,
+};
+
+struct Camera {
+    view_proj: mat4x4<f32>,
+};
+@group(0) @binding(0) var<uniform> camera: Camera;
+
+@vertex
+fn vs_main(
+    in: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    out.clip_position = camera.view_proj * vec4<f32>(in.position, 0.0, 1.0);
+    out.color = in.color;
+    out.texcoord = in.texcoord;
+    out.texture_index = in.texture_index;
+    out.flags = in.flags;
+
+    return out;
+}
+
+@group(1) @binding(0) var textures: binding_array<texture_2d<f32>, #capacity>;
+@group(1) @binding(1) var samplers: binding_array<sampler, #capacity>;
+
+// flags of the vertex being shaded, for fragment functions that treat some quads differently
+var<private> vertex_flags: u32;
+
+#fragment
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    vertex_flags = in.flags;
+
+    var texel = textureSample(textures[in.texture_index], samplers[in.texture_index], in.texcoord);
+
+    // single channel textures are coverage, white with the channel as alpha
+    if ((in.flags & 1u) != 0u) {
+        texel = vec4<f32>(1.0, 1.0, 1.0, texel.r);
+    }
+
+    let output = fragment(texel, in.color, in.texcoord);
+
+    // blend modes weighing the source by alpha themselves take colors multiplied by it
+    if (#premultiply) {
+        return vec4<f32>(output.rgb * output.a, output.a);
+    }
+
+    return output;
+}
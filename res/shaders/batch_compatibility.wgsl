@@ -3,6 +3,7 @@ struct VertexInput {
     @location(1) color: vec4<f32>,
     @location(2) texcoord: vec2<f32>,
     @location(3) texture_index: u32,
+    @location(4) flags: u32,
 };
 
 struct VertexOutput {
@@ -10,6 +11,7 @@ struct VertexOutput {
     @location(0) color: vec4<f32>,
     @location(1) texcoord: vec2<f32>,
     @location(2) texture_index: u32,
+    @location(3) flags: u32,
 };
 
 struct Camera {
@@ -27,17 +29,37 @@ fn vs_main(
     out.color = in.color;
     out.texcoord = in.texcoord;
     out.texture_index = in.texture_index;
+    out.flags = in.flags;
 
     return out;
 }
 
 #include_bind_groups
 
+// flags of the vertex being shaded, for fragment functions that treat some quads differently
+var<private> vertex_flags: u32;
+
+#fragment
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    vertex_flags = in.flags;
+
     var out: vec4<f32>;
     
     #include_body
 
-    return out;
+    // single channel textures are coverage, white with the channel as alpha
+    if ((in.flags & 1u) != 0u) {
+        out = vec4<f32>(1.0, 1.0, 1.0, out.r);
+    }
+
+    let output = fragment(out, in.color, in.texcoord);
+
+    // blend modes weighing the source by alpha themselves take colors multiplied by it
+    if (#premultiply) {
+        return vec4<f32>(output.rgb * output.a, output.a);
+    }
+
+    return output;
 }
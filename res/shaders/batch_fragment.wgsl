@@ -0,0 +1,3 @@
+fn fragment(texel: vec4<f32>, color: vec4<f32>, texcoord: vec2<f32>) -> vec4<f32> {
+    return texel * color;
+}
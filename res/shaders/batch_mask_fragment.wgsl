@@ -0,0 +1,8 @@
+// masks only write the stencil, mostly transparent texels are left out of them
+fn fragment(texel: vec4<f32>, color: vec4<f32>, texcoord: vec2<f32>) -> vec4<f32> {
+    if (texel.a * color.a < 0.5) {
+        discard;
+    }
+
+    return texel * color;
+}
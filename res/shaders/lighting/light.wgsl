@@ -0,0 +1,98 @@
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) world: vec2<f32>,
+};
+
+struct Camera {
+    view_proj: mat4x4<f32>,
+};
+@group(0) @binding(0) var<uniform> camera: Camera;
+
+struct Light {
+    // x, y, height, radius
+    position: vec4<f32>,
+    // rgb, intensity
+    color: vec4<f32>,
+    // x, y, cosine of the inner and the outer cone
+    direction: vec4<f32>,
+    // kind, falloff, shadow softness, shadows
+    params: vec4<f32>,
+    resolution: vec4<f32>,
+};
+@group(1) @binding(0) var<uniform> light: Light;
+
+@group(2) @binding(0) var normal_texture: texture_2d<f32>;
+@group(2) @binding(1) var normal_sampler: sampler;
+@group(3) @binding(0) var shadow_texture: texture_2d<f32>;
+@group(3) @binding(1) var shadow_sampler: sampler;
+
+const POINT: u32 = 0u;
+const SPOT: u32 = 1u;
+const DIRECTIONAL: u32 = 2u;
+
+@vertex
+fn vs_main(
+    in: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    out.clip_position = camera.view_proj * vec4<f32>(in.position, 0.0, 1.0);
+    out.world = in.position;
+
+    return out;
+}
+
+// 1 where lit, soft shadows average a 5x5 grid spread over the softness
+fn shadow(texcoord: vec2<f32>) -> f32 {
+    if (light.params.w == 0.0) {
+        return 1.0;
+    }
+
+    if (light.params.z <= 0.0) {
+        return textureSampleLevel(shadow_texture, shadow_sampler, texcoord, 0.0).r;
+    }
+
+    let step = light.params.z / 4.0 / light.resolution.xy;
+    var lit = 0.0;
+
+    for (var x = -2; x <= 2; x++) {
+        for (var y = -2; y <= 2; y++) {
+            lit += textureSampleLevel(shadow_texture, shadow_sampler, texcoord + vec2<f32>(f32(x), f32(y)) * step, 0.0).r;
+        }
+    }
+
+    return lit / 25.0;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let texcoord = in.clip_position.xy / light.resolution.xy;
+    let kind = u32(light.params.x);
+
+    var to_light = normalize(vec3<f32>(-light.direction.xy, 1.0));
+    var attenuation = 1.0;
+
+    if (kind != DIRECTIONAL) {
+        let offset = light.position.xy - in.world;
+        let distance = length(offset);
+
+        attenuation = pow(clamp(1.0 - distance / light.position.w, 0.0, 1.0), light.params.y);
+
+        if (kind == SPOT && distance > 0.0) {
+            attenuation *= smoothstep(light.direction.w, light.direction.z, dot(-offset / distance, light.direction.xy));
+        }
+
+        to_light = normalize(vec3<f32>(offset, light.position.z));
+    }
+
+    let normal = normalize(textureSampleLevel(normal_texture, normal_sampler, texcoord, 0.0).rgb * 2.0 - 1.0);
+
+    // relative to a flat surface, so flat parts of the scene only see the falloff
+    let diffuse = clamp(dot(normal, to_light) / max(to_light.z, 0.001), 0.0, 2.0);
+
+    return vec4<f32>(light.color.rgb * light.color.a * attenuation * diffuse * shadow(texcoord), 1.0);
+}
@@ -0,0 +1,14 @@
+// flat normal, encoded like a normal map
+const FLAT: vec3<f32> = vec3<f32>(0.5, 0.5, 1.0);
+
+// vertex flag the batch sets on the normal maps of sprites
+const NORMAL_MAP: u32 = 2u;
+
+// normal maps are kept as they are, everything else gets a flat normal cut out by its own alpha
+fn fragment(texel: vec4<f32>, color: vec4<f32>, texcoord: vec2<f32>) -> vec4<f32> {
+    if ((vertex_flags & NORMAL_MAP) != 0u) {
+        return vec4<f32>(texel.rgb, texel.a * color.a);
+    }
+
+    return vec4<f32>(FLAT, texel.a * color.a);
+}
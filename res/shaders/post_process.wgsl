@@ -0,0 +1,57 @@
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) texcoord: vec2<f32>,
+};
+
+struct Globals {
+    resolution: vec2<f32>,
+    time: f32,
+    pass_index: u32,
+};
+
+@group(0) @binding(0) var source_texture: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+@group(0) @binding(2) var input_texture: texture_2d<f32>;
+@group(0) @binding(3) var input_sampler: sampler;
+@group(0) @binding(4) var extra_texture: texture_2d<f32>;
+@group(0) @binding(5) var extra_sampler: sampler;
+@group(0) @binding(6) var<uniform> globals: Globals;
+
+// one triangle covering the screen, texcoords start at the top left like textures do
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+    var out: VertexOutput;
+
+    let corner = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+
+    out.clip_position = vec4<f32>(corner * 2.0 - 1.0, 0.0, 1.0);
+    out.texcoord = vec2<f32>(corner.x, 1.0 - corner.y);
+
+    return out;
+}
+
+/// Output of the previous pass, the effect input for the first pass.
+fn source(texcoord: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(source_texture, source_sampler, texcoord, 0.0);
+}
+
+/// What the effect started from, the same for every pass of a multi pass effect.
+fn input(texcoord: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(input_texture, input_sampler, texcoord, 0.0);
+}
+
+/// Texture given with `PostEffect::set_texture`, white if there is none.
+fn extra(texcoord: vec2<f32>) -> vec4<f32> {
+    return textureSampleLevel(extra_texture, extra_sampler, texcoord, 0.0);
+}
+
+fn texel_size() -> vec2<f32> {
+    return 1.0 / globals.resolution;
+}
+
+#effect
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return effect(in.texcoord);
+}
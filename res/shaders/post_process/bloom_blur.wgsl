@@ -0,0 +1,24 @@
+struct Params {
+    // threshold, intensity, radius
+    values: vec4<f32>,
+};
+@group(1) @binding(0) var<uniform> params: Params;
+
+// pass 1 blurs horizontally, pass 2 vertically
+fn effect(texcoord: vec2<f32>) -> vec4<f32> {
+    let radius = max(params.values.z, 0.0);
+    let sigma = max(radius / 2.0, 0.001);
+    let direction = select(vec2<f32>(0.0, 1.0), vec2<f32>(1.0, 0.0), globals.pass_index == 1u) * texel_size();
+
+    var sum = vec4<f32>(0.0);
+    var weights = 0.0;
+
+    for (var i = -i32(ceil(radius)); i <= i32(ceil(radius)); i++) {
+        let weight = exp(-f32(i * i) / (2.0 * sigma * sigma));
+
+        sum += source(texcoord + direction * f32(i)) * weight;
+        weights += weight;
+    }
+
+    return sum / weights;
+}
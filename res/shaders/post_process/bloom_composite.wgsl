@@ -0,0 +1,11 @@
+struct Params {
+    // threshold, intensity, radius
+    values: vec4<f32>,
+};
+@group(1) @binding(0) var<uniform> params: Params;
+
+fn effect(texcoord: vec2<f32>) -> vec4<f32> {
+    let color = input(texcoord);
+
+    return vec4<f32>(color.rgb + source(texcoord).rgb * params.values.y, color.a);
+}
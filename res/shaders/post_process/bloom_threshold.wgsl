@@ -0,0 +1,15 @@
+struct Params {
+    // threshold, intensity, radius
+    values: vec4<f32>,
+};
+@group(1) @binding(0) var<uniform> params: Params;
+
+fn effect(texcoord: vec2<f32>) -> vec4<f32> {
+    let color = source(texcoord);
+    let brightness = max(color.r, max(color.g, color.b));
+
+    // soft knee so pixels near the threshold fade in instead of popping
+    let contribution = clamp((brightness - params.values.x) / max(1.0 - params.values.x, 0.001), 0.0, 1.0);
+
+    return vec4<f32>(color.rgb * contribution, 1.0);
+}
@@ -0,0 +1,24 @@
+struct Params {
+    // radius in pixels
+    values: vec4<f32>,
+};
+@group(1) @binding(0) var<uniform> params: Params;
+
+// even passes blur horizontally, odd passes vertically
+fn effect(texcoord: vec2<f32>) -> vec4<f32> {
+    let radius = max(params.values.x, 0.0);
+    let sigma = max(radius / 2.0, 0.001);
+    let direction = select(vec2<f32>(1.0, 0.0), vec2<f32>(0.0, 1.0), (globals.pass_index & 1u) == 1u) * texel_size();
+
+    var sum = vec4<f32>(0.0);
+    var weights = 0.0;
+
+    for (var i = -i32(ceil(radius)); i <= i32(ceil(radius)); i++) {
+        let weight = exp(-f32(i * i) / (2.0 * sigma * sigma));
+
+        sum += source(texcoord + direction * f32(i)) * weight;
+        weights += weight;
+    }
+
+    return sum / weights;
+}
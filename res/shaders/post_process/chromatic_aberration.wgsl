@@ -0,0 +1,16 @@
+struct Params {
+    // offset in pixels at the edges
+    values: vec4<f32>,
+};
+@group(1) @binding(0) var<uniform> params: Params;
+
+fn effect(texcoord: vec2<f32>) -> vec4<f32> {
+    // channels drift apart towards the edges
+    let offset = (texcoord - 0.5) * 2.0 * params.values.x * texel_size();
+
+    let r = source(texcoord + offset).r;
+    let ga = source(texcoord).ga;
+    let b = source(texcoord - offset).b;
+
+    return vec4<f32>(r, ga.x, b, ga.y);
+}
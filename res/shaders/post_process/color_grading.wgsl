@@ -0,0 +1,33 @@
+struct Params {
+    // intensity
+    values: vec4<f32>,
+};
+@group(1) @binding(0) var<uniform> params: Params;
+
+fn to_srgb(linear: vec3<f32>) -> vec3<f32> {
+    let low = linear * 12.92;
+    let high = 1.055 * pow(linear, vec3<f32>(1.0 / 2.4)) - 0.055;
+
+    return select(high, low, linear <= vec3<f32>(0.0031308));
+}
+
+// the LUT is a strip of square slices, blue picks the slice, red and green the texel inside it
+fn grade(color: vec3<f32>) -> vec3<f32> {
+    let size = f32(textureDimensions(extra_texture).y);
+    let scaled = clamp(to_srgb(color), vec3<f32>(0.0), vec3<f32>(1.0)) * (size - 1.0);
+
+    let slice = floor(scaled.b);
+    let next = min(slice + 1.0, size - 1.0);
+    let inner = vec2<f32>(scaled.r + 0.5, scaled.g + 0.5);
+
+    let first = extra(vec2<f32>((slice * size + inner.x) / (size * size), inner.y / size)).rgb;
+    let second = extra(vec2<f32>((next * size + inner.x) / (size * size), inner.y / size)).rgb;
+
+    return mix(first, second, scaled.b - slice);
+}
+
+fn effect(texcoord: vec2<f32>) -> vec4<f32> {
+    let color = source(texcoord);
+
+    return vec4<f32>(mix(color.rgb, grade(color.rgb), params.values.x), color.a);
+}
@@ -0,0 +1,23 @@
+struct Params {
+    // scanline intensity, curvature, scanline count
+    values: vec4<f32>,
+};
+@group(1) @binding(0) var<uniform> params: Params;
+
+fn effect(texcoord: vec2<f32>) -> vec4<f32> {
+    // bulge the picture like the glass of a tube
+    var uv = texcoord * 2.0 - 1.0;
+    uv *= 1.0 + params.values.y * dot(uv.yx, uv.yx);
+    uv = uv * 0.5 + 0.5;
+
+    if any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0)) {
+        return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    }
+
+    let color = source(uv);
+
+    let lines = select(globals.resolution.y * 0.5, params.values.z, params.values.z > 0.0);
+    let scanline = 0.5 + 0.5 * cos(uv.y * lines * 6.2831853);
+
+    return vec4<f32>(color.rgb * mix(1.0, scanline, params.values.x), color.a);
+}
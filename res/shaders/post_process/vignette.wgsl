@@ -0,0 +1,18 @@
+struct Params {
+    // intensity, radius, softness
+    values: vec4<f32>,
+};
+@group(1) @binding(0) var<uniform> params: Params;
+
+fn effect(texcoord: vec2<f32>) -> vec4<f32> {
+    let color = source(texcoord);
+
+    // distance from the center, corrected for the aspect ratio
+    let aspect = globals.resolution.x / globals.resolution.y;
+    let offset = (texcoord - 0.5) * vec2<f32>(aspect, 1.0);
+    let distance = length(offset) / length(vec2<f32>(aspect, 1.0) * 0.5);
+
+    let shade = smoothstep(params.values.y, params.values.y - params.values.z, distance);
+
+    return vec4<f32>(color.rgb * mix(1.0, shade, params.values.x), color.a);
+}
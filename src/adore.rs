@@ -1,4 +1,5 @@
 use crate::{
+    gfx::raw::VirtualResolutionConfig,
     time::GameTime,
     traits::Game,
     types::Size,
@@ -10,9 +11,64 @@ use crate::{
 
 //
 
+#[derive(Debug, Clone, Copy)]
+pub struct HeadlessConfig {
+    pub width: u32,
+    pub height: u32,
+    /// Number of frames to run, `None` runs until `abort()` is called.
+    pub frames: Option<u64>,
+    /// Fixed delta in seconds reported to `Game::update` and `Game::draw`.
+    pub delta: f32,
+    /// Prefer a software (CPU) adapter, e.g. llvmpipe or WARP.
+    pub force_software: bool,
+}
+
+impl Default for HeadlessConfig {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            frames: None,
+            delta: 1.0 / 60.0,
+            force_software: false,
+        }
+    }
+}
+
+//
+
+#[derive(Debug, Clone, Copy)]
+pub struct FixedTimeStepConfig {
+    /// Must be above 0, `Adore::new` panics otherwise.
+    pub updates_per_second: f32,
+    /// Upper bound of `Game::update` calls per frame, time beyond it is dropped.
+    pub max_steps: u32,
+}
+
+impl Default for FixedTimeStepConfig {
+    fn default() -> Self {
+        Self {
+            updates_per_second: 60.0,
+            max_steps: 5,
+        }
+    }
+}
+
+//
+
 #[derive(Debug, Clone, Copy)]
 pub struct AdoreConfig {
     pub window_config: WindowConfig,
+    pub headless: Option<HeadlessConfig>,
+    pub fixed_time_step: Option<FixedTimeStepConfig>,
+    /// Size the game draws at regardless of the window, `Game::resize` then reports it instead of the window size.
+    pub virtual_resolution: Option<VirtualResolutionConfig>,
+    /// Samples per pixel for anti-aliasing the frame and render targets, 1, 2, 4 or 8. Counts the adapter does not
+    /// support fall back to the highest one below them.
+    pub sample_count: u32,
+    /// Binds the textures of a draw call as one texture array where the device supports it, `false` binds every
+    /// texture on its own like devices without texture arrays do.
+    pub texture_arrays: bool,
 }
 
 #[allow(clippy::all)]
@@ -20,6 +76,11 @@ impl Default for AdoreConfig {
     fn default() -> Self {
         Self {
             window_config: WindowConfig::default(),
+            headless: None,
+            fixed_time_step: None,
+            virtual_resolution: None,
+            sample_count: 1,
+            texture_arrays: true,
         }
     }
 }
@@ -28,54 +89,174 @@ impl Default for AdoreConfig {
 
 #[derive(Debug)]
 pub struct Adore {
-    window: Window,
+    window: Option<Window>,
+    headless: Option<HeadlessConfig>,
+    fixed_time_step: Option<FixedTimeStepConfig>,
+    virtual_resolution: Option<VirtualResolutionConfig>,
 
     game_time: GameTime,
 }
 
 impl Adore {
     pub fn new(config: AdoreConfig) -> Self {
-        let window = Window::new(config.window_config);
+        if let Some(fixed_time_step) = config.fixed_time_step {
+            // steps of infinite or negative length, the game would never update
+            assert!(
+                fixed_time_step.updates_per_second > 0.0 && fixed_time_step.updates_per_second.is_finite(),
+                "FixedTimeStepConfig::updates_per_second must be above 0, got {}",
+                fixed_time_step.updates_per_second
+            );
+        }
+
+        let window = match config.headless {
+            Some(headless) => {
+                crate::window::init_input();
+                crate::gfx::raw::init_headless(
+                    (headless.width, headless.height),
+                    headless.force_software,
+                    config.sample_count,
+                    config.texture_arrays,
+                );
 
-        crate::gfx::raw::init(&window, window.size());
+                None
+            },
+            None => {
+                let window = Window::new(config.window_config);
+
+                crate::gfx::raw::init(&window, window.size(), config.sample_count, config.texture_arrays);
+
+                Some(window)
+            },
+        };
 
         Self {
             window,
+            headless: config.headless,
+            fixed_time_step: config.fixed_time_step,
+            virtual_resolution: config.virtual_resolution,
 
             game_time: GameTime::new(),
         }
     }
 
     pub fn run(mut self, mut game: impl Game + 'static) {
+        let Some(window) = self.window.take() else {
+            return self.run_headless(game);
+        };
+
         let mut old_size = Size::default();
 
-        self.window.run(move |size| {
+        window.run(move |size| {
             if size != old_size {
                 old_size = size;
 
-                crate::gfx::raw::reset(crate::gfx::raw::ContextConfig {
-                    width: size.width,
-                    height: size.height,
-                    vsync: false,
-                });
-
-                game.resize(size);
+                Self::resize(&mut game, size, self.virtual_resolution);
             }
 
-            game.update(self.game_time);
+            Self::frame(&mut game, &mut self.game_time, self.fixed_time_step);
 
-            crate::gfx::raw::render(|| {
-                {
-                    // dummy render pass
-                    if let Some(frame) = crate::gfx::raw::frame() {
-                        _ = frame.create_render_pass_with_load_op(false, crate::gfx::raw::LoadOp::Clear(crate::gfx::raw::Color::default()));
-                    }
+            self.game_time.update();
+        });
+    }
+
+    fn run_headless(mut self, mut game: impl Game) {
+        let headless = self.headless.unwrap_or_default();
+
+        Self::resize(
+            &mut game,
+            Size {
+                width: headless.width,
+                height: headless.height,
+            },
+            self.virtual_resolution,
+        );
+
+        let mut frames = 0;
+
+        while headless.frames.is_none_or(|max| frames < max) && !crate::window::aborted() {
+            Self::frame(&mut game, &mut self.game_time, self.fixed_time_step);
+
+            self.game_time.step(headless.delta);
+            frames += 1;
+        }
+
+        // headless runs can follow each other, e.g. one per test, the game's resources go before their device
+        drop(game);
+        crate::gfx::raw::release();
+        crate::window::reset_abort();
+    }
+
+    fn resize(game: &mut impl Game, size: Size<u32>, virtual_resolution: Option<VirtualResolutionConfig>) {
+        crate::gfx::raw::reset(crate::gfx::raw::ContextConfig {
+            width: size.width,
+            height: size.height,
+            vsync: false,
+            virtual_resolution,
+        });
+
+        match virtual_resolution {
+            Some(virtual_resolution) => game.resize(Size {
+                width: virtual_resolution.width,
+                height: virtual_resolution.height,
+            }),
+            None => game.resize(size),
+        }
+    }
+
+    fn frame(game: &mut impl Game, game_time: &mut GameTime, fixed_time_step: Option<FixedTimeStepConfig>) {
+        match fixed_time_step {
+            Some(fixed_time_step) => {
+                let step = 1.0 / fixed_time_step.updates_per_second;
+
+                // input is only consumed by updates, frames without one keep it for the next frame
+                for _ in 0..game_time.accumulate(step, fixed_time_step.max_steps) {
+                    game.update(game_time.fixed(step));
+
+                    crate::window::input_mut().reset();
                 }
 
-                game.draw(self.game_time);
-            });
+                Self::draw(game, *game_time);
+            },
+            None => {
+                game.update(*game_time);
 
-            self.game_time.update();
+                Self::draw(game, *game_time);
+
+                crate::window::input_mut().reset();
+            },
+        }
+    }
+
+    fn draw(game: &mut impl Game, game_time: GameTime) {
+        crate::gfx::raw::post_process().set_time(game_time.total() as f32);
+
+        crate::gfx::raw::render(|| {
+            {
+                // dummy render pass
+                if let Some(frame) = crate::gfx::raw::frame() {
+                    _ = frame.create_render_pass_with_load_op(false, crate::gfx::raw::LoadOp::Clear(crate::gfx::raw::Color::default()));
+                }
+            }
+
+            game.draw(game_time);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "updates_per_second must be above 0")]
+    fn zero_updates_per_second() {
+        Adore::new(AdoreConfig {
+            headless: Some(HeadlessConfig::default()),
+            fixed_time_step: Some(FixedTimeStepConfig {
+                updates_per_second: 0.0,
+                ..Default::default()
+            }),
+            ..Default::default()
         });
     }
 }
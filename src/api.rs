@@ -1,3 +1,12 @@
+use std::path::Path;
+
+use crate::{
+    errors::ReadbackError,
+    raw,
+};
+
+//
+
 pub fn abort() {
     crate::window::abort();
 }
@@ -11,5 +20,36 @@ pub fn input() -> &'static crate::window::Input {
 }
 
 pub fn set_title(title: impl Into<String>) {
-    crate::window::raw().set_title(title.into().as_str());
+    if let Some(raw) = crate::window::try_raw() {
+        raw.set_title(title.into().as_str());
+    }
+}
+
+/// Effects run over every frame before it is presented.
+pub fn post_process() -> &'static raw::PostProcess {
+    raw::post_process()
+}
+
+/// Adds, removes and tunes the effects run over every frame.
+pub fn post_process_mut() -> &'static mut raw::PostProcess {
+    raw::post_process()
+}
+
+/// Saves the frame currently being drawn as a PNG, after post processing. It is written once the frame has been read
+/// back, failures are logged. `raw::screenshot` hands out the pending copy instead.
+pub fn screenshot(path: impl AsRef<Path>) {
+    raw::save_screenshot(path.as_ref().to_path_buf());
+}
+
+/// Records a copy of the frame currently being drawn, call from `Game::draw`. Post processing has not run yet.
+pub fn read_frame() -> anyhow::Result<raw::Readback> {
+    match raw::frame() {
+        Some(frame) => frame.read_pixels(),
+        None => Err(anyhow::anyhow!(ReadbackError::FrameIsNone)),
+    }
+}
+
+/// Samples per pixel the frame is drawn with, `AdoreConfig::sample_count` or the closest count supported below it.
+pub fn sample_count() -> u32 {
+    raw::sample_count()
 }
@@ -6,15 +6,33 @@ use std::{
 use crate::raw;
 
 pub fn load_texture_from_bytes(bytes: &[u8]) -> anyhow::Result<raw::Texture> {
+    load_texture_from_bytes_with(bytes, raw::TextureConfig::default())
+}
+
+/// Decodes `bytes` into a texture of `config.format`. Images without alpha fill `R8Unorm` textures with their
+/// luminance, images with it with their alpha.
+pub fn load_texture_from_bytes_with(bytes: &[u8], config: raw::TextureConfig) -> anyhow::Result<raw::Texture> {
     let image = image::load_from_memory(bytes)?;
-    let rgba = image.to_rgba8();
 
     use image::GenericImageView;
     let dimensions = image.dimensions();
 
-    Ok(raw::Texture::new(&rgba, dimensions, raw::TextureConfig {
-        ..Default::default()
-    }))
+    Ok(raw::Texture::new(&texture_bytes(&image, config.format), dimensions, config))
+}
+
+/// Pixels of `image` laid out as `format`, see `load_texture_from_bytes_with`.
+pub(crate) fn texture_bytes(image: &image::DynamicImage, format: raw::TextureFormat) -> Vec<u8> {
+    match format {
+        raw::TextureFormat::Rgba8UnormSrgb | raw::TextureFormat::Rgba8Unorm => image.to_rgba8().into_raw(),
+        raw::TextureFormat::R8Unorm if image.color().has_alpha() => image.to_rgba8().pixels().map(|pixel| pixel.0[3]).collect(),
+        raw::TextureFormat::R8Unorm => image.to_luma8().into_raw(),
+        raw::TextureFormat::Rgba16Float => image
+            .to_rgba32f()
+            .into_raw()
+            .into_iter()
+            .flat_map(|channel| half::f16::from_f32(channel).to_le_bytes())
+            .collect(),
+    }
 }
 
 pub async fn load_texture_from_path_async(path: &Path) -> anyhow::Result<raw::Texture> {
@@ -22,14 +40,9 @@ pub async fn load_texture_from_path_async(path: &Path) -> anyhow::Result<raw::Te
 }
 
 pub fn load_texture_from_path(path: &Path) -> anyhow::Result<raw::Texture> {
-    let bytes = fs::read(path)?;
-    let image = image::load_from_memory(&bytes)?;
-    let rgba = image.to_rgba8();
-
-    use image::GenericImageView;
-    let dimensions = image.dimensions();
+    load_texture_from_path_with(path, raw::TextureConfig::default())
+}
 
-    Ok(raw::Texture::new(&rgba, dimensions, raw::TextureConfig {
-        ..Default::default()
-    }))
+pub fn load_texture_from_path_with(path: &Path, config: raw::TextureConfig) -> anyhow::Result<raw::Texture> {
+    load_texture_from_bytes_with(&fs::read(path)?, config)
 }
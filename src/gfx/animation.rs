@@ -0,0 +1,535 @@
+use std::{
+    fmt,
+    fs,
+    path::Path,
+    rc::Rc,
+};
+
+use hashbrown::HashMap;
+
+use crate::{
+    assets,
+    errors::AnimationError,
+    gfx::atlas::Sheet,
+    raw,
+    GameTime,
+    Sprite,
+    TextureRegion,
+};
+
+//
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlayMode {
+    #[default]
+    Loop,
+    /// Plays to the last frame and stays there.
+    Once,
+    /// Plays forward then backward, repeating.
+    PingPong,
+    /// Loops from the last frame to the first.
+    Reverse,
+    /// Plays backward from the last frame then forward, repeating.
+    PingPongReverse,
+}
+
+impl PlayMode {
+    /// Mode of an Aseprite tag direction, unknown directions play forward.
+    fn from_direction(direction: &str) -> Self {
+        match direction {
+            "reverse" => Self::Reverse,
+            "pingpong" => Self::PingPong,
+            "pingpong_reverse" => Self::PingPongReverse,
+            _ => Self::Loop,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AnimationFrame {
+    pub region: TextureRegion,
+    /// Seconds the frame stays visible.
+    pub duration: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnimationEvent {
+    /// Event attached to a frame with `add_event`, fired whenever the frame is entered.
+    Frame { frame: usize, name: String },
+    /// The animation wrapped around to its start again.
+    Looped,
+    /// A `PlayMode::Once` animation reached its last frame.
+    Finished,
+}
+
+#[derive(Clone)]
+struct Callback(Rc<dyn Fn(&AnimationEvent)>);
+
+impl fmt::Debug for Callback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Callback")
+    }
+}
+
+//
+
+/// Frame based animation over texture regions, advanced by `GameTime::delta`.
+///
+/// Cloning is cheap, clones share textures and callbacks but play independently.
+#[derive(Debug, Clone)]
+pub struct Animation {
+    frames: Vec<AnimationFrame>,
+    playback: Playback,
+}
+
+impl Animation {
+    pub fn new(frames: Vec<AnimationFrame>, mode: PlayMode) -> anyhow::Result<Self> {
+        if frames.is_empty() {
+            return Err(anyhow::anyhow!(AnimationError::NoFrames));
+        }
+
+        let playback = Playback::new(frames.iter().map(|frame| frame.duration).collect(), mode);
+
+        Ok(Self {
+            frames,
+            playback,
+        })
+    }
+
+    /// Every region shown for the same `frame_duration`, in seconds.
+    pub fn from_regions(regions: &[TextureRegion], frame_duration: f32, mode: PlayMode) -> anyhow::Result<Self> {
+        let frames = regions
+            .iter()
+            .map(|region| AnimationFrame {
+                region: region.clone(),
+                duration: frame_duration,
+            })
+            .collect();
+
+        Self::new(frames, mode)
+    }
+
+    /// Loads every frame tag of an Aseprite JSON export (hash or array) as a named animation, using the frame
+    /// durations and tag directions of the file. Without tags, all frames form a single `"default"` animation.
+    pub fn from_aseprite_bytes(json: &[u8], texture: raw::Texture) -> anyhow::Result<HashMap<String, Self>> {
+        Self::from_sheet(&Sheet::parse(json)?, texture)
+    }
+
+    /// Loads an Aseprite JSON export and the sheet image it names, relative to the export's directory.
+    pub fn from_aseprite_path(path: &Path) -> anyhow::Result<HashMap<String, Self>> {
+        let sheet = Sheet::parse(&fs::read(path)?)?;
+
+        let directory = path.parent().unwrap_or(Path::new(""));
+        let texture = assets::load_texture_from_bytes(&fs::read(directory.join(&sheet.meta.image))?)?;
+
+        Self::from_sheet(&sheet, texture)
+    }
+
+    fn from_sheet(sheet: &Sheet, texture: raw::Texture) -> anyhow::Result<HashMap<String, Self>> {
+        let texture = Rc::new(texture);
+
+        let frames = sheet
+            .frames()?
+            .into_iter()
+            .map(|(_, frame)| AnimationFrame {
+                region: TextureRegion::from_rc(Rc::clone(&texture), frame.frame.rectangle()).with_trim(frame.trim()),
+                duration: frame.duration.unwrap_or(100) as f32 / 1000.0,
+            })
+            .collect::<Vec<_>>();
+
+        if sheet.meta.frame_tags.is_empty() {
+            return Ok(HashMap::from([("default".to_string(), Self::new(frames, PlayMode::Loop)?)]));
+        }
+
+        sheet
+            .meta
+            .frame_tags
+            .iter()
+            .map(|tag| {
+                let clip = frames
+                    .get(tag.from..=tag.to)
+                    .ok_or_else(|| anyhow::anyhow!(AnimationError::TagOutOfRange(tag.name.clone())))?;
+
+                Ok((
+                    tag.name.clone(),
+                    Self::new(clip.to_vec(), PlayMode::from_direction(&tag.direction))?,
+                ))
+            })
+            .collect()
+    }
+
+    //
+
+    /// Queues `name` as an `AnimationEvent::Frame` every time `frame` is entered.
+    pub fn add_event(&mut self, frame: usize, name: impl Into<String>) {
+        self.playback.frame_events.entry(frame).or_default().push(name.into());
+    }
+
+    /// Called for every event as it fires, in addition to queueing it.
+    pub fn on_event(&mut self, callback: impl Fn(&AnimationEvent) + 'static) {
+        self.playback.callback = Some(Callback(Rc::new(callback)));
+    }
+
+    /// Takes the events fired since the last call.
+    pub fn drain_events(&mut self) -> impl Iterator<Item = AnimationEvent> + '_ {
+        self.playback.events.drain(..)
+    }
+
+    //
+
+    pub fn update(&mut self, game_time: &GameTime) {
+        self.advance(game_time.delta());
+    }
+
+    pub fn advance(&mut self, delta: f32) {
+        self.playback.advance(delta);
+    }
+
+    pub fn play(&mut self) {
+        self.playback.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playback.playing = false;
+    }
+
+    /// Starts over from the first frame, the last one for `PlayMode::Reverse` and `PlayMode::PingPongReverse`.
+    pub fn restart(&mut self) {
+        self.playback.restart();
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playback.playing && !self.playback.finished
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.playback.finished
+    }
+
+    pub fn mode(&self) -> PlayMode {
+        self.playback.mode
+    }
+
+    pub fn set_mode(&mut self, mode: PlayMode) {
+        self.playback.mode = mode;
+        self.playback.restart();
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.playback.speed
+    }
+
+    /// Playback rate, `2.0` plays twice as fast.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.playback.speed = speed;
+    }
+
+    pub fn frames(&self) -> &[AnimationFrame] {
+        &self.frames
+    }
+
+    pub fn current_frame(&self) -> usize {
+        self.playback.current
+    }
+
+    pub fn set_current_frame(&mut self, frame: usize) {
+        self.playback.current = frame.min(self.frames.len() - 1);
+        self.playback.elapsed = 0.0;
+    }
+
+    pub fn region(&self) -> &TextureRegion {
+        &self.frames[self.playback.current].region
+    }
+
+    /// Points `sprite` at the current frame, keeping its target and transform.
+    pub fn apply(&self, sprite: &mut Sprite) {
+        sprite.set_region(self.region());
+    }
+
+    pub fn sprite(&self) -> Sprite {
+        Sprite::from_region(self.region())
+    }
+}
+
+//
+
+/// Frame stepping and events of an `Animation`, apart from its regions.
+#[derive(Debug, Clone)]
+struct Playback {
+    durations: Vec<f32>,
+    mode: PlayMode,
+    speed: f32,
+
+    frame_events: HashMap<usize, Vec<String>>,
+    events: Vec<AnimationEvent>,
+    callback: Option<Callback>,
+
+    current: usize,
+    elapsed: f32,
+    forward: bool,
+    playing: bool,
+    finished: bool,
+    entered: bool,
+}
+
+impl Playback {
+    fn new(durations: Vec<f32>, mode: PlayMode) -> Self {
+        let mut playback = Self {
+            // negative and NaN durations count as zero length
+            durations: durations.into_iter().map(|duration| duration.max(0.0)).collect(),
+            mode,
+            speed: 1.0,
+
+            frame_events: HashMap::new(),
+            events: vec![],
+            callback: None,
+
+            current: 0,
+            elapsed: 0.0,
+            forward: true,
+            playing: true,
+            finished: false,
+            entered: false,
+        };
+
+        playback.restart();
+
+        playback
+    }
+
+    fn restart(&mut self) {
+        let last = self.durations.len() - 1;
+
+        (self.current, self.forward) = match self.mode {
+            PlayMode::Reverse => (last, true),
+            PlayMode::PingPongReverse => (last, false),
+            _ => (0, true),
+        };
+        self.elapsed = 0.0;
+        self.finished = false;
+        self.entered = false;
+        self.playing = true;
+    }
+
+    fn advance(&mut self, delta: f32) {
+        if !self.playing {
+            return;
+        }
+
+        if !self.entered {
+            self.entered = true;
+            self.enter();
+        }
+
+        // without any frame that takes time there is nothing to play through
+        if self.finished || self.durations.iter().all(|&duration| duration == 0.0) {
+            return;
+        }
+
+        self.elapsed += delta * self.speed;
+
+        // zero length frames are skipped without taking time, every cycle takes some so the loop ends
+        while self.elapsed >= self.durations[self.current] {
+            self.elapsed -= self.durations[self.current];
+            self.step();
+
+            if self.finished {
+                self.elapsed = 0.0;
+                break;
+            }
+        }
+    }
+
+    fn step(&mut self) {
+        let last = self.durations.len() - 1;
+
+        match self.mode {
+            PlayMode::Loop if self.current == last => {
+                self.current = 0;
+                self.fire(AnimationEvent::Looped);
+            },
+            PlayMode::Loop => self.current += 1,
+            PlayMode::Reverse if self.current == 0 => {
+                self.current = last;
+                self.fire(AnimationEvent::Looped);
+            },
+            PlayMode::Reverse => self.current -= 1,
+            PlayMode::Once if self.current == last => {
+                self.finished = true;
+                self.fire(AnimationEvent::Finished);
+                return;
+            },
+            PlayMode::Once => self.current += 1,
+            PlayMode::PingPong | PlayMode::PingPongReverse if last == 0 => return,
+            PlayMode::PingPong | PlayMode::PingPongReverse => {
+                if (self.forward && self.current == last) || (!self.forward && self.current == 0) {
+                    self.forward = !self.forward;
+                }
+
+                if self.forward {
+                    self.current += 1;
+                } else {
+                    self.current -= 1;
+                }
+
+                let start = match self.mode {
+                    PlayMode::PingPongReverse => last,
+                    _ => 0,
+                };
+
+                if self.current == start {
+                    self.fire(AnimationEvent::Looped);
+                }
+            },
+        }
+
+        self.enter();
+    }
+
+    fn enter(&mut self) {
+        let names = self.frame_events.get(&self.current).cloned().unwrap_or_default();
+
+        for name in names {
+            self.fire(AnimationEvent::Frame {
+                frame: self.current,
+                name,
+            });
+        }
+    }
+
+    fn fire(&mut self, event: AnimationEvent) {
+        if let Some(callback) = &self.callback {
+            (callback.0)(&event);
+        }
+
+        self.events.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    fn frames(playback: &mut Playback, deltas: &[f32]) -> Vec<usize> {
+        deltas
+            .iter()
+            .map(|&delta| {
+                playback.advance(delta);
+                playback.current
+            })
+            .collect()
+    }
+
+    #[test]
+    fn aseprite_tags() {
+        let sheet = Sheet::parse(include_bytes!("../../tests/atlas/walk.json")).unwrap();
+
+        let durations = sheet
+            .frames()
+            .unwrap()
+            .into_iter()
+            .map(|(_, frame)| frame.duration)
+            .collect::<Vec<_>>();
+        assert_eq!(durations, [Some(125), Some(250), Some(125), Some(125)]);
+
+        let modes = sheet
+            .meta
+            .frame_tags
+            .iter()
+            .map(|tag| PlayMode::from_direction(&tag.direction))
+            .collect::<Vec<_>>();
+        assert_eq!(modes, [PlayMode::Loop, PlayMode::PingPong]);
+
+        assert_eq!(PlayMode::from_direction("reverse"), PlayMode::Reverse);
+        assert_eq!(PlayMode::from_direction("pingpong_reverse"), PlayMode::PingPongReverse);
+    }
+
+    #[test]
+    fn frame_events() {
+        let mut walk = Playback::new(vec![0.125, 0.25, 0.125, 0.125], PlayMode::Loop);
+
+        let steps = Rc::new(Cell::new(0));
+        let counter = Rc::clone(&steps);
+        walk.frame_events.entry(2).or_default().push("step".to_string());
+        walk.callback = Some(Callback(Rc::new(move |event| {
+            if matches!(event, AnimationEvent::Frame { .. }) {
+                counter.set(counter.get() + 1);
+            }
+        })));
+
+        assert_eq!(frames(&mut walk, &[0.125, 0.375, 0.125]), [1, 3, 0]);
+        assert_eq!(walk.events.drain(..).collect::<Vec<_>>(), [
+            AnimationEvent::Frame {
+                frame: 2,
+                name: "step".to_string()
+            },
+            AnimationEvent::Looped
+        ]);
+        assert_eq!(steps.get(), 1);
+    }
+
+    #[test]
+    fn ping_pong() {
+        // 0 1 2 1 0, the first frame lasts twice as long
+        let mut bounce = Playback::new(vec![0.25, 0.125, 0.125], PlayMode::PingPong);
+        assert_eq!(frames(&mut bounce, &[0.25, 0.125, 0.125, 0.125, 0.25]), [1, 2, 1, 0, 1]);
+        assert_eq!(bounce.events, [AnimationEvent::Looped]);
+
+        let mut reverse = Playback::new(vec![0.125; 3], PlayMode::PingPongReverse);
+        assert_eq!(reverse.current, 2);
+        assert_eq!(frames(&mut reverse, &[0.125; 5]), [1, 0, 1, 2, 1]);
+        assert_eq!(reverse.events, [AnimationEvent::Looped]);
+
+        // nowhere to go with a single frame
+        let mut still = Playback::new(vec![0.125], PlayMode::PingPong);
+        assert_eq!(frames(&mut still, &[1.0]), [0]);
+        assert!(still.events.is_empty());
+    }
+
+    #[test]
+    fn once_and_reverse() {
+        let mut once = Playback::new(vec![0.125; 4], PlayMode::Once);
+        once.advance(10.0);
+        assert!(once.finished);
+        assert_eq!(once.current, 3);
+        assert_eq!(once.events, [AnimationEvent::Finished]);
+
+        // finished animations stay on their last frame
+        once.advance(10.0);
+        assert_eq!(once.current, 3);
+        assert_eq!(once.events.len(), 1);
+
+        let mut reverse = Playback::new(vec![0.125; 4], PlayMode::Reverse);
+        assert_eq!(reverse.current, 3);
+        assert_eq!(frames(&mut reverse, &[0.25, 0.25]), [1, 3]);
+        assert_eq!(reverse.events, [AnimationEvent::Looped]);
+    }
+
+    #[test]
+    fn zero_length_frames() {
+        // passed through without being shown, their events still fire
+        let mut skipping = Playback::new(vec![0.125, 0.0, 0.125], PlayMode::Loop);
+        skipping.frame_events.entry(1).or_default().push("skipped".to_string());
+        assert_eq!(frames(&mut skipping, &[0.125, 0.125]), [2, 0]);
+        assert_eq!(skipping.events.len(), 2);
+
+        // nothing to play through, the first frame stays without any events
+        let mut empty = Playback::new(vec![0.0, 0.0, -1.0, f32::NAN], PlayMode::Loop);
+        assert_eq!(frames(&mut empty, &[0.016, 100.0]), [0, 0]);
+        assert!(empty.events.is_empty());
+    }
+
+    #[test]
+    fn paused_and_speed() {
+        let mut playback = Playback::new(vec![0.125; 4], PlayMode::Loop);
+
+        playback.playing = false;
+        assert_eq!(frames(&mut playback, &[1.0]), [0]);
+
+        playback.playing = true;
+        playback.speed = 2.0;
+        assert_eq!(frames(&mut playback, &[0.125]), [2]);
+    }
+}
@@ -0,0 +1,233 @@
+mod packer;
+mod texture_packer;
+
+use std::{
+    fs,
+    path::Path,
+    rc::Rc,
+};
+
+use hashbrown::HashMap;
+use packer::Skyline;
+pub(crate) use texture_packer::Sheet;
+
+use crate::{
+    assets,
+    errors::AtlasError,
+    raw,
+    Rectangle,
+    Sprite,
+    TextureRegion,
+};
+
+//
+
+#[derive(Debug, Clone)]
+pub struct AtlasConfig {
+    /// Largest width and height of a page, images that do not fit open another page.
+    pub max_size: u32,
+    /// Empty pixels around every image to keep filtering from bleeding neighbours in.
+    pub padding: u32,
+    pub texture_config: raw::TextureConfig,
+}
+
+impl Default for AtlasConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 2048,
+            padding: 1,
+            texture_config: raw::TextureConfig::default(),
+        }
+    }
+}
+
+//
+
+#[derive(Debug, Default)]
+pub struct AtlasBuilder {
+    config: AtlasConfig,
+    images: Vec<(String, image::RgbaImage)>,
+}
+
+impl AtlasBuilder {
+    pub fn new(config: AtlasConfig) -> Self {
+        Self {
+            config,
+            images: vec![],
+        }
+    }
+
+    pub fn add_bytes(&mut self, name: impl Into<String>, bytes: &[u8]) -> anyhow::Result<()> {
+        self.add_image(name.into(), image::load_from_memory(bytes)?.to_rgba8())
+    }
+
+    pub fn add_path(&mut self, name: impl Into<String>, path: &Path) -> anyhow::Result<()> {
+        self.add_bytes(name, &fs::read(path)?)
+    }
+
+    /// Adds tightly packed RGBA pixels, rows from top to bottom.
+    pub fn add_rgba(&mut self, name: impl Into<String>, dimensions: (u32, u32), rgba: Vec<u8>) -> anyhow::Result<()> {
+        let name = name.into();
+
+        let image = image::RgbaImage::from_raw(dimensions.0, dimensions.1, rgba)
+            .ok_or_else(|| anyhow::anyhow!(AtlasError::Parse(format!("`{name}` does not match its dimensions"))))?;
+
+        self.add_image(name, image)
+    }
+
+    fn add_image(&mut self, name: String, image: image::RgbaImage) -> anyhow::Result<()> {
+        let padding = self.config.padding;
+
+        if image.width() + padding > self.config.max_size || image.height() + padding > self.config.max_size {
+            return Err(anyhow::anyhow!(AtlasError::ImageTooLarge {
+                name,
+                dimensions: image.dimensions(),
+                max_size: self.config.max_size,
+            }));
+        }
+
+        self.images.push((name, image));
+
+        Ok(())
+    }
+
+    /// Packs every added image, tallest first, into as few pages as possible.
+    pub fn build(mut self) -> anyhow::Result<TextureAtlas> {
+        let padding = self.config.padding;
+        let max_size = self.config.max_size;
+
+        self.images
+            .sort_by_key(|(_, image)| std::cmp::Reverse((image.height(), image.width())));
+
+        let mut pages: Vec<(Skyline, image::RgbaImage, (u32, u32))> = vec![];
+        let mut placements = vec![];
+
+        for (name, image) in &self.images {
+            let size = image.dimensions();
+
+            let placed = pages
+                .iter_mut()
+                .enumerate()
+                .find_map(|(index, (skyline, ..))| skyline.insert(size.0, size.1).map(|origin| (index, origin)));
+
+            let (page, origin) = match placed {
+                Some(placed) => placed,
+                None => {
+                    let mut skyline = Skyline::new(max_size, max_size, padding);
+                    let origin = skyline.insert(size.0, size.1).expect("image is checked against max_size");
+
+                    pages.push((skyline, image::RgbaImage::new(max_size, max_size), (0, 0)));
+                    (pages.len() - 1, origin)
+                },
+            };
+
+            let (_, canvas, used) = &mut pages[page];
+
+            image::imageops::replace(canvas, image, origin.0 as i64, origin.1 as i64);
+            *used = (used.0.max(origin.0 + image.width()), used.1.max(origin.1 + image.height()));
+
+            placements.push((
+                name.clone(),
+                page,
+                Rectangle::new(origin.0, origin.1, image.width(), image.height()),
+            ));
+        }
+
+        // crop pages down to the area actually used
+        let pages = pages
+            .into_iter()
+            .map(|(_, canvas, used)| {
+                let page = image::DynamicImage::ImageRgba8(image::imageops::crop_imm(&canvas, 0, 0, used.0, used.1).to_image());
+                let bytes = assets::texture_bytes(&page, self.config.texture_config.format);
+
+                Rc::new(raw::Texture::new(&bytes, used, self.config.texture_config))
+            })
+            .collect::<Vec<_>>();
+
+        let regions = placements
+            .into_iter()
+            .map(|(name, page, rect)| {
+                let source = Rectangle::new(rect.x as f32, rect.y as f32, rect.width as f32, rect.height as f32);
+
+                (name, TextureRegion::from_rc(Rc::clone(&pages[page]), source))
+            })
+            .collect();
+
+        Ok(TextureAtlas {
+            pages,
+            regions,
+        })
+    }
+}
+
+//
+
+/// Named regions of one or more shared page textures.
+#[derive(Debug, Default)]
+pub struct TextureAtlas {
+    pages: Vec<Rc<raw::Texture>>,
+    regions: HashMap<String, TextureRegion>,
+}
+
+impl TextureAtlas {
+    /// Loads a TexturePacker "JSON (Hash)" or "JSON (Array)" sheet for an already loaded page.
+    pub fn from_texture_packer_bytes(json: &[u8], page: raw::Texture) -> anyhow::Result<Self> {
+        Self::from_sheet(&Sheet::parse(json)?, page)
+    }
+
+    /// Loads a TexturePacker sheet and the page image it names, relative to the sheet's directory.
+    pub fn from_texture_packer_path(path: &Path) -> anyhow::Result<Self> {
+        let sheet = Sheet::parse(&fs::read(path)?)?;
+
+        let directory = path.parent().unwrap_or(Path::new(""));
+        let page = assets::load_texture_from_bytes(&fs::read(directory.join(&sheet.meta.image))?)?;
+
+        Self::from_sheet(&sheet, page)
+    }
+
+    fn from_sheet(sheet: &Sheet, page: raw::Texture) -> anyhow::Result<Self> {
+        let page = Rc::new(page);
+
+        let regions = sheet
+            .frames()?
+            .into_iter()
+            .map(|(name, frame)| {
+                let source = frame.frame.rectangle();
+
+                (
+                    name.to_string(),
+                    TextureRegion::from_rc(Rc::clone(&page), source).with_trim(frame.trim()),
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            pages: vec![page],
+            regions,
+        })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TextureRegion> {
+        self.regions.get(name)
+    }
+
+    pub fn sprite(&self, name: &str) -> Option<Sprite> {
+        self.get(name).map(Sprite::from_region)
+    }
+
+    pub fn regions(&self) -> impl Iterator<Item = (&str, &TextureRegion)> {
+        self.regions.iter().map(|(name, region)| (name.as_str(), region))
+    }
+
+    pub fn pages(&self) -> impl Iterator<Item = &raw::Texture> {
+        self.pages.iter().map(|page| page.as_ref())
+    }
+
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
@@ -0,0 +1,145 @@
+/// Skyline bottom-left rectangle packer, places every rectangle as low as possible, then as far left as possible.
+#[derive(Debug)]
+pub(crate) struct Skyline {
+    width: u32,
+    height: u32,
+    padding: u32,
+
+    // (x, y, width) segments of the skyline, sorted by x and covering the full width
+    nodes: Vec<(u32, u32, u32)>,
+}
+
+impl Skyline {
+    /// Every rectangle reserves `padding` more pixels to its right and bottom.
+    pub fn new(width: u32, height: u32, padding: u32) -> Self {
+        Self {
+            width,
+            height,
+            padding,
+
+            nodes: vec![(0, 0, width)],
+        }
+    }
+
+    pub fn insert(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let (width, height) = (width + self.padding, height + self.padding);
+
+        // lowest position wins, ties go to the narrower segment to keep wide gaps open
+        let (index, y) = (0..self.nodes.len())
+            .filter_map(|index| self.fit(index, width, height).map(|y| (index, y)))
+            .min_by_key(|&(index, y)| (y, self.nodes[index].2))?;
+
+        let x = self.nodes[index].0;
+        self.place(index, x, y + height, width);
+
+        Some((x, y))
+    }
+
+    /// Lowest y a rectangle starting at node `index` can sit at, if it fits at all.
+    fn fit(&self, index: usize, width: u32, height: u32) -> Option<u32> {
+        let x = self.nodes[index].0;
+
+        if x + width > self.width {
+            return None;
+        }
+
+        let mut y = 0;
+        let mut remaining = width as i64;
+
+        for &(_, node_y, node_width) in &self.nodes[index..] {
+            if remaining <= 0 {
+                break;
+            }
+
+            y = y.max(node_y);
+            remaining -= node_width as i64;
+        }
+
+        (y + height <= self.height).then_some(y)
+    }
+
+    fn place(&mut self, index: usize, x: u32, top: u32, width: u32) {
+        self.nodes.insert(index, (x, top, width));
+
+        // shrink or remove the segments now covered by the new one
+        let right = x + width;
+
+        while index + 1 < self.nodes.len() {
+            let (node_x, node_y, node_width) = self.nodes[index + 1];
+
+            if node_x >= right {
+                break;
+            }
+
+            let overlap = right - node_x;
+
+            if overlap < node_width {
+                self.nodes[index + 1] = (right, node_y, node_width - overlap);
+                break;
+            }
+
+            self.nodes.remove(index + 1);
+        }
+
+        // merge neighbours at the same height
+        let mut i = 0;
+
+        while i + 1 < self.nodes.len() {
+            if self.nodes[i].1 == self.nodes[i + 1].1 {
+                self.nodes[i].2 += self.nodes[i + 1].2;
+                self.nodes.remove(i + 1);
+            } else {
+                i += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert_all(skyline: &mut Skyline, sizes: &[(u32, u32)]) -> Vec<Option<(u32, u32)>> {
+        sizes.iter().map(|&(width, height)| skyline.insert(width, height)).collect()
+    }
+
+    #[test]
+    fn placement() {
+        let mut skyline = Skyline::new(64, 64, 0);
+
+        // the tall image leaves a step that the short ones fill before the row above starts
+        assert_eq!(insert_all(&mut skyline, &[(40, 30), (24, 10), (24, 10), (24, 10), (24, 10)]), [
+            Some((0, 0)),
+            Some((40, 0)),
+            Some((40, 10)),
+            Some((40, 20)),
+            Some((0, 30)),
+        ]);
+    }
+
+    #[test]
+    fn overflow() {
+        let mut skyline = Skyline::new(64, 64, 0);
+
+        assert_eq!(skyline.insert(65, 1), None);
+        assert_eq!(skyline.insert(1, 65), None);
+        assert_eq!(skyline.insert(64, 64), Some((0, 0)));
+        assert_eq!(skyline.insert(1, 1), None);
+    }
+
+    #[test]
+    fn padding() {
+        let mut skyline = Skyline::new(16, 16, 1);
+
+        assert_eq!(insert_all(&mut skyline, &[(7, 7), (7, 7), (8, 7), (7, 7)]), [
+            Some((0, 0)),
+            Some((8, 0)),
+            Some((0, 8)),
+            None,
+        ]);
+
+        // the padding has to fit as well
+        assert_eq!(Skyline::new(16, 16, 1).insert(16, 16), None);
+        assert_eq!(Skyline::new(16, 16, 1).insert(15, 15), Some((0, 0)));
+    }
+}
@@ -0,0 +1,175 @@
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::{
+    errors::AtlasError,
+    Position,
+    Rectangle,
+    Size,
+};
+
+//
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Sheet {
+    pub frames: Frames,
+    pub meta: Meta,
+}
+
+/// TexturePacker and Aseprite write frames either keyed by name ("JSON (Hash)") or as a list ("JSON (Array)").
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Frames {
+    Hash(IndexMap<String, Frame>),
+    Array(Vec<NamedFrame>),
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct NamedFrame {
+    pub filename: String,
+    #[serde(flatten)]
+    pub frame: Frame,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Frame {
+    pub frame: Rect,
+    #[serde(default)]
+    pub rotated: bool,
+    /// Transparent borders were cut off, `sprite_source_size` places the frame within `source_size`.
+    #[serde(default)]
+    pub trimmed: bool,
+    #[serde(rename = "spriteSourceSize")]
+    pub sprite_source_size: Option<Rect>,
+    #[serde(rename = "sourceSize")]
+    pub source_size: Option<Dimensions>,
+    /// Aseprite only, in milliseconds.
+    pub duration: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub(crate) struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub(crate) struct Dimensions {
+    pub w: u32,
+    pub h: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Meta {
+    pub image: String,
+    /// Aseprite only.
+    #[serde(default, rename = "frameTags")]
+    pub frame_tags: Vec<FrameTag>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FrameTag {
+    pub name: String,
+    pub from: usize,
+    pub to: usize,
+    #[serde(default)]
+    pub direction: String,
+}
+
+impl Rect {
+    pub fn rectangle(&self) -> Rectangle<f32> {
+        Rectangle::new(self.x as f32, self.y as f32, self.w as f32, self.h as f32)
+    }
+}
+
+impl Frame {
+    /// Offset of the frame within the untrimmed image and the untrimmed size, if the frame was trimmed.
+    pub fn trim(&self) -> Option<(Position<f32>, Size<f32>)> {
+        let (true, Some(offset), Some(size)) = (self.trimmed, self.sprite_source_size, self.source_size) else {
+            return None;
+        };
+
+        Some((
+            Position {
+                x: offset.x as f32,
+                y: offset.y as f32,
+            },
+            Size {
+                width: size.w as f32,
+                height: size.h as f32,
+            },
+        ))
+    }
+}
+
+impl Sheet {
+    pub fn parse(json: &[u8]) -> anyhow::Result<Self> {
+        serde_json::from_slice(json).map_err(|err| anyhow::anyhow!(AtlasError::Parse(err.to_string())))
+    }
+
+    /// Frames in file order, rotated frames are rejected.
+    pub fn frames(&self) -> anyhow::Result<Vec<(&str, &Frame)>> {
+        let frames = match &self.frames {
+            Frames::Hash(frames) => frames.iter().map(|(name, frame)| (name.as_str(), frame)).collect::<Vec<_>>(),
+            Frames::Array(frames) => frames.iter().map(|frame| (frame.filename.as_str(), &frame.frame)).collect(),
+        };
+
+        match frames.iter().find(|(_, frame)| frame.rotated) {
+            Some((name, _)) => Err(anyhow::anyhow!(AtlasError::RotatedFrame(name.to_string()))),
+            None => Ok(frames),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHEET: &str = r#"{
+        "frames": {
+            "trimmed": { "frame": { "x": 0, "y": 0, "w": 12, "h": 10 }, "rotated": false, "trimmed": true, "spriteSourceSize": { "x": 3, "y": 4, "w": 12, "h": 10 }, "sourceSize": { "w": 16, "h": 16 } },
+            "full": { "frame": { "x": 12, "y": 0, "w": 16, "h": 16 }, "rotated": false, "trimmed": false, "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 }, "sourceSize": { "w": 16, "h": 16 } },
+            "bare": { "frame": { "x": 28, "y": 0, "w": 4, "h": 4 } }
+        },
+        "meta": { "image": "sheet.png" }
+    }"#;
+
+    #[test]
+    fn trim() {
+        let sheet = Sheet::parse(SHEET.as_bytes()).unwrap();
+        let frames = sheet.frames().unwrap();
+
+        assert_eq!(frames.iter().map(|(name, _)| *name).collect::<Vec<_>>(), [
+            "trimmed", "full", "bare"
+        ]);
+        assert_eq!(
+            frames[0].1.trim(),
+            Some((
+                Position {
+                    x: 3.0,
+                    y: 4.0
+                },
+                Size {
+                    width: 16.0,
+                    height: 16.0
+                }
+            ))
+        );
+        assert_eq!(frames[1].1.trim(), None);
+        assert_eq!(frames[2].1.trim(), None);
+    }
+
+    #[test]
+    fn rotated() {
+        let sheet = Sheet::parse(
+            SHEET
+                .replace(r#""rotated": false, "trimmed": true"#, r#""rotated": true, "trimmed": true"#)
+                .as_bytes(),
+        )
+        .unwrap();
+
+        assert!(sheet.frames().is_err());
+    }
+}
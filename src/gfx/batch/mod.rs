@@ -0,0 +1,1159 @@
+mod shapes;
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::atomic::{
+        AtomicU64,
+        Ordering,
+    },
+};
+
+use hashbrown::HashMap;
+pub use shapes::{
+    LineCap,
+    LineJoin,
+    LineStyle,
+};
+
+use crate::{
+    errors::BatchError,
+    raw,
+    Camera2D,
+    Color,
+    Font,
+    NinePatch,
+    ParticleEmitter,
+    Position,
+    Rectangle,
+    Size,
+    Sprite,
+};
+
+/// Upper bound for the texture array path, device limits can be far higher.
+const MAX_ARRAY_TEXTURES: u32 = 64;
+
+const INITIAL_QUADS: u32 = 1024;
+
+static NEXT_SHADER_ID: AtomicU64 = AtomicU64::new(0);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, crate::Desc)]
+struct Vertex {
+    #[f32x2(0)]
+    position: [f32; 2],
+    #[f32x4(1)]
+    color: [f32; 4],
+    #[f32x2(2)]
+    texcoord: [f32; 2],
+    #[u32(3)]
+    texture_index: u32,
+    #[u32(4)]
+    flags: u32,
+}
+
+/// Vertex flag for single channel textures, the shaders expand their texels to white with the channel as alpha.
+const SINGLE_CHANNEL: u32 = 1;
+/// Vertex flag for normal maps drawn by the normal pass, everything else gets a flat normal.
+const NORMAL_MAP: u32 = 2;
+
+//
+
+/// Range of quads in the batch vertices sharing one set of textures.
+#[derive(Debug)]
+struct DrawCall {
+    pub textures: Vec<Rc<raw::Texture>>,
+
+    pub first_quad: u32,
+    pub quads: u32,
+
+    pub current_texture: *const raw::Texture,
+}
+
+impl DrawCall {
+    fn new(texture: &Rc<raw::Texture>, first_quad: u32) -> Self {
+        Self {
+            textures: vec![Rc::clone(texture)],
+
+            first_quad,
+            quads: 0,
+
+            current_texture: texture.as_ref() as _,
+        }
+    }
+}
+
+//
+
+/// Order in which the quads of a `begin`/`end` pair are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortMode {
+    /// Submission order, drawn at `end`.
+    #[default]
+    Deferred,
+    /// Submission order, every draw is flushed right away.
+    Immediate,
+    /// Grouped by texture to use as few draw calls as possible, drawn at `end`.
+    Texture,
+    /// Highest layer depth first, drawn at `end`.
+    BackToFront,
+    /// Lowest layer depth first, drawn at `end`.
+    FrontToBack,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BatchConfig {
+    /// Draws into the target instead of the frame, using a projection matching the target size.
+    pub target: Option<raw::RenderTarget>,
+    /// View and projection for the pass, replacing the one of `resize` or the target.
+    pub camera: Option<Camera2D>,
+    pub sort_mode: SortMode,
+    pub blend_mode: raw::BlendMode,
+    /// Samples every texture with this configuration instead of the one it was created with.
+    pub sampler: Option<raw::TextureConfig>,
+    pub shader: Option<Rc<BatchShader>>,
+    /// Draws the normal maps of sprites instead of their textures and flat normals for everything else, meant for the
+    /// config of `Lighting::normal_config`.
+    pub normals: bool,
+}
+
+/// Custom fragment stage for a `Batch`, replacing the default `texel * color`.
+///
+/// The source must define `fn fragment(texel: vec4<f32>, color: vec4<f32>, texcoord: vec2<f32>) -> vec4<f32>`, where
+/// `texel` is sampled from the quad's texture and `color` is its tint. The uniform block of `with_uniform` is bound at
+/// `@group(#uniform_group) @binding(0)`, the placeholder is replaced with the group the batch uses.
+#[derive(Debug)]
+pub struct BatchShader {
+    id: u64,
+    source: String,
+    uniform: Option<RefCell<raw::DynamicUniform>>,
+}
+
+impl BatchShader {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            id: NEXT_SHADER_ID.fetch_add(1, Ordering::Relaxed),
+            source: source.into(),
+            uniform: None,
+        }
+    }
+
+    pub fn with_uniform(source: impl Into<String>, data: &[u8]) -> Self {
+        Self {
+            uniform: Some(RefCell::new(raw::DynamicUniform::new(data, raw::ShaderStages::Fragment))),
+            ..Self::new(source)
+        }
+    }
+
+    /// Applies to the batches flushed after the call, so every `begin`/`end` pair in a frame can use its own values.
+    /// Does nothing for shaders without a uniform.
+    pub fn set_uniform(&self, data: &[u8]) {
+        if let Some(uniform) = &self.uniform {
+            uniform.borrow_mut().set(data);
+        }
+    }
+
+    pub fn has_uniform(&self) -> bool {
+        self.uniform.is_some()
+    }
+}
+
+/// Work done by a `Batch` during the last frame it drew in, summed over all of its `begin`/`end` pairs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BatchStats {
+    pub draw_calls: u32,
+    pub quads: u32,
+    pub vertices: u32,
+    pub bytes_uploaded: u64,
+    /// GPU buffers replaced by larger ones.
+    pub reallocations: u32,
+}
+
+/// Corners go bottom left, top left, top right, bottom right, each with its own texture coordinate and color.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Quad {
+    pub corners: [glam::Vec2; 4],
+    pub texcoords: [[f32; 2]; 4],
+    pub colors: [Color<f32>; 4],
+}
+
+impl Quad {
+    /// `uv` is mapped with its top edge onto the top corners.
+    pub fn textured(corners: [glam::Vec2; 4], uv: Rectangle<f32>, color: Color<f32>) -> Self {
+        Self {
+            corners,
+            texcoords: [[uv.x, uv.y + uv.height], [uv.x, uv.y], [uv.x + uv.width, uv.y], [
+                uv.x + uv.width,
+                uv.y + uv.height,
+            ]],
+            colors: [color; 4],
+        }
+    }
+}
+
+/// Textures of one draw call, bound the way the device and the pass configuration require.
+enum TextureBindings<'a> {
+    Array(Rc<raw::TextureArray>),
+    Bindings(Vec<Rc<raw::TextureBinding>>),
+    Textures(Vec<&'a raw::Texture>),
+}
+
+/// Blend mode, shader id, stencil mode, target format and sample count a pipeline was created for.
+type PipelineKey = (raw::BlendMode, Option<u64>, raw::StencilMode, raw::wgpu::TextureFormat, u32);
+
+/// Texture and sampler override a texture binding was created for.
+type BindingKey = (raw::wgpu::Id<raw::wgpu::BindGroup>, raw::wgpu::Id<raw::wgpu::Sampler>);
+
+enum Vertices<'a> {
+    /// Offset into the batch vertex buffer.
+    Dynamic(u64),
+    Static(&'a raw::VertexBuffer),
+}
+
+/// Quad held back until `end` by the sorting modes.
+#[derive(Debug)]
+struct QueuedQuad {
+    texture: Rc<raw::Texture>,
+    quad: Quad,
+    depth: f32,
+    flags: u32,
+}
+
+/// Quads recorded by `Batch::record`, kept in a vertex buffer of their own.
+#[derive(Debug)]
+pub struct StaticBatch {
+    vertex_buffer: Option<raw::VertexBuffer>,
+    draw_calls: Vec<DrawCall>,
+    quads: u32,
+}
+
+impl StaticBatch {
+    pub fn quads(&self) -> u32 {
+        self.quads
+    }
+
+    pub fn draw_calls(&self) -> usize {
+        self.draw_calls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quads == 0
+    }
+}
+
+//
+
+#[derive(Debug)]
+pub struct Batch {
+    pipelines: HashMap<PipelineKey, raw::Pipeline>,
+    samplers: Vec<(raw::TextureConfig, raw::Sampler)>,
+    texture_arrays: raw::TextureArrayCache,
+    /// Textures bound with a sampler override, for devices without texture arrays.
+    texture_bindings: raw::FrameCache<BindingKey, raw::TextureBinding>,
+    camera_uniform: raw::DynamicUniform,
+    projection: glam::Mat4,
+
+    vertex_buffer: raw::DynamicVertexBuffer,
+    quad_indices: raw::DynamicIndexBuffer,
+
+    config: BatchConfig,
+    draw_calls: Vec<DrawCall>,
+    vertices: Vec<Vertex>,
+    queued: Vec<QueuedQuad>,
+    clips: Vec<Rectangle<f32>>,
+    masks: Vec<StaticBatch>,
+
+    stats: BatchStats,
+    stats_frame: u64,
+
+    is_drawing: bool,
+    is_recording: bool,
+    bindless: bool,
+    capacity: u32,
+    blank_texture: Rc<raw::Texture>,
+}
+
+impl Default for Batch {
+    fn default() -> Self {
+        let bindless = raw::TextureArray::is_supported();
+
+        let capacity = if bindless {
+            raw::TextureArray::max_count().min(MAX_ARRAY_TEXTURES)
+        } else {
+            raw::device().limits().max_bind_groups - 1
+        };
+
+        log::debug!("Batch Texture Capacity: {:?}, Texture Arrays: {:?}", capacity, bindless);
+
+        //
+
+        let projection = Self::projection(1280.0, 720.0);
+
+        let camera_uniform = raw::DynamicUniform::new(crate::cast(&projection.to_cols_array()), raw::ShaderStages::Vertex);
+
+        //
+
+        // raw texels, a single opaque white one
+        let blank_texture = Rc::new(raw::Texture::new(&[255, 255, 255, 255], (1, 1), raw::TextureConfig::default()));
+
+        let vertex_buffer = raw::DynamicVertexBuffer::with_capacity((INITIAL_QUADS as usize * 4 * std::mem::size_of::<Vertex>()) as u64);
+        let quad_indices = raw::DynamicIndexBuffer::new(
+            crate::cast(&Self::quad_indices(INITIAL_QUADS)),
+            raw::IndexFormat::Uint32,
+            INITIAL_QUADS as usize * 6,
+        );
+
+        Self {
+            pipelines: HashMap::new(),
+            samplers: vec![],
+            texture_arrays: raw::TextureArrayCache::default(),
+            texture_bindings: raw::FrameCache::default(),
+            camera_uniform,
+            projection,
+
+            vertex_buffer,
+            quad_indices,
+
+            config: BatchConfig::default(),
+            draw_calls: vec![],
+            vertices: vec![],
+            queued: vec![],
+            clips: vec![],
+            masks: vec![],
+
+            stats: BatchStats::default(),
+            stats_frame: 0,
+
+            is_drawing: false,
+            is_recording: false,
+            bindless,
+            capacity,
+            blank_texture,
+        }
+    }
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One bind group per texture and a branch per texture index, for devices without texture arrays.
+    fn compatibility_shader(capacity: u32) -> String {
+        let mut bg = String::new();
+        for i in 0..capacity {
+            let index = i + 1;
+            bg += format!(
+                r#"
+@group({index}) @binding(0) var texture_{i}: texture_2d<f32>;
+@group({index}) @binding(1) var texture_sampler_{i}: sampler;
+                "#
+            )
+            .as_str();
+        }
+        bg += "\n";
+
+        let mut rets = String::new();
+        for i in 0..capacity {
+            rets += format!(
+                r#"
+if (in.texture_index == {i}) {{
+out = textureSample(texture_{i}, texture_sampler_{i}, in.texcoord);
+}}
+            "#
+            )
+            .as_str();
+        }
+        rets += "\n";
+
+        let shader_source = include_str_from_root!("res/shaders/batch_compatibility.wgsl").to_string();
+        let shader_source = shader_source.replace("#include_body", rets.as_str());
+        let shader_source = shader_source.replace("#include_bind_groups", bg.as_str());
+        shader_source
+    }
+
+    fn create_pipeline(&self, (blend_mode, _, stencil, format, sample_count): PipelineKey, shader: Option<&BatchShader>) -> raw::Pipeline {
+        // masks only write the stencil, with a fragment stage of their own
+        let shader = match stencil {
+            raw::StencilMode::Increment | raw::StencilMode::Decrement => None,
+            raw::StencilMode::Disabled | raw::StencilMode::Test => shader,
+        };
+        let capacity = self.capacity_for(shader);
+
+        let mut bind_group_layouts = vec![(0, raw::DynamicUniform::bind_group_layout(raw::ShaderStages::Vertex))];
+
+        let shader_source = if self.bindless {
+            bind_group_layouts.push((1, raw::TextureArray::bind_group_layout(capacity)));
+
+            include_str_from_root!("res/shaders/batch.wgsl").replace("#capacity", capacity.to_string().as_str())
+        } else {
+            for i in 1..capacity + 1 {
+                bind_group_layouts.push((i, raw::Texture::bind_group_layout()));
+            }
+
+            Self::compatibility_shader(capacity)
+        };
+
+        let fragment = match shader {
+            Some(shader) => {
+                if shader.has_uniform() {
+                    bind_group_layouts.push((
+                        self.uniform_group(capacity),
+                        raw::DynamicUniform::bind_group_layout(raw::ShaderStages::Fragment),
+                    ));
+                }
+
+                shader
+                    .source
+                    .replace("#uniform_group", self.uniform_group(capacity).to_string().as_str())
+            },
+            None => match stencil {
+                raw::StencilMode::Increment | raw::StencilMode::Decrement => {
+                    include_str_from_root!("res/shaders/batch_mask_fragment.wgsl").to_string()
+                },
+                raw::StencilMode::Disabled | raw::StencilMode::Test => {
+                    include_str_from_root!("res/shaders/batch_fragment.wgsl").to_string()
+                },
+            },
+        };
+
+        raw::Pipeline::new(raw::PipelineConfig {
+            shader_source: shader_source
+                .replace("#fragment", fragment.as_str())
+                .replace("#premultiply", (blend_mode == raw::BlendMode::Multiply).to_string().as_str())
+                .as_str(),
+            vertex_buffer_layouts: &[Vertex::desc()],
+            bind_group_layouts: bind_group_layouts.iter().map(|(order, layout)| (*order, layout)).collect(),
+            depth_stencil_write_enabled: false,
+            blend_mode,
+            stencil,
+            format: Some(format),
+            sample_count: Some(sample_count),
+        })
+    }
+
+    /// Without texture arrays every texture takes a bind group, so a shader uniform takes the place of one texture.
+    fn capacity_for(&self, shader: Option<&BatchShader>) -> u32 {
+        match shader {
+            Some(shader) if shader.has_uniform() && !self.bindless => self.capacity - 1,
+            _ => self.capacity,
+        }
+    }
+
+    fn uniform_group(&self, capacity: u32) -> u32 {
+        if self.bindless {
+            2
+        } else {
+            capacity + 1
+        }
+    }
+
+    fn pipeline_key(config: &BatchConfig, stencil: raw::StencilMode) -> PipelineKey {
+        let (format, sample_count) = match &config.target {
+            Some(target) => (target.format(), target.sample_count()),
+            None => (raw::format(), raw::sample_count()),
+        };
+
+        match stencil {
+            raw::StencilMode::Increment | raw::StencilMode::Decrement => (raw::BlendMode::Alpha, None, stencil, format, sample_count),
+            raw::StencilMode::Disabled | raw::StencilMode::Test => (
+                config.blend_mode,
+                config.shader.as_ref().map(|shader| shader.id),
+                stencil,
+                format,
+                sample_count,
+            ),
+        }
+    }
+
+    fn ensure_pipeline(&mut self, stencil: raw::StencilMode) {
+        let key = Self::pipeline_key(&self.config, stencil);
+
+        if !self.pipelines.contains_key(&key) {
+            let pipeline = self.create_pipeline(key, self.config.shader.as_deref());
+            self.pipelines.insert(key, pipeline);
+        }
+    }
+
+    fn quad_indices(quads: u32) -> Vec<u32> {
+        (0..quads)
+            .flat_map(|quad| [0, 2, 1, 0, 3, 2].map(|index| index + quad * 4))
+            .collect()
+    }
+
+    pub(crate) fn projection(width: f32, height: f32) -> glam::Mat4 {
+        let view = glam::Mat4::look_at_rh(glam::Vec3::new(0.0, 0.0, 1.0), glam::Vec3::new(0.0, 0.0, 0.0), glam::Vec3::Y);
+        let proj = glam::Mat4::orthographic_rh(0.0, width, 0.0, height, 0.0, 1.0);
+
+        proj * view
+    }
+
+    pub fn resize(&mut self, size: Size<u32>) {
+        self.projection = Self::projection(size.width as f32, size.height as f32);
+    }
+
+    pub fn begin(&mut self) -> anyhow::Result<()> {
+        self.begin_with(BatchConfig::default())
+    }
+
+    pub fn begin_with(&mut self, config: BatchConfig) -> anyhow::Result<()> {
+        if self.is_drawing {
+            return Err(anyhow::anyhow!(BatchError::BatchIsDrawing));
+        }
+
+        if let Some(sampler) = config.sampler {
+            if !self.samplers.iter().any(|(config, _)| *config == sampler) {
+                self.samplers.push((sampler, raw::Sampler::new(sampler)));
+            }
+        }
+
+        self.is_drawing = true;
+        self.config = config;
+        self.ensure_pipeline(raw::StencilMode::Disabled);
+
+        self.draw_calls.clear();
+        self.vertices.clear();
+        self.queued.clear();
+        self.clips.clear();
+        self.masks.clear();
+
+        Ok(())
+    }
+
+    pub fn end(&mut self) -> anyhow::Result<()> {
+        if !self.is_drawing {
+            return Err(anyhow::anyhow!(BatchError::BatchNotDrawing));
+        }
+
+        self.is_drawing = false;
+
+        let result = self.submit();
+
+        self.clips.clear();
+        self.masks.clear();
+
+        result
+    }
+
+    /// Records the quads drawn by `draw` into a `StaticBatch`, which keeps them on the GPU to be drawn by `draw_static`
+    /// without batching them again. Quads are recorded in submission order.
+    pub fn record(&mut self, draw: impl FnOnce(&mut Batch)) -> anyhow::Result<StaticBatch> {
+        if self.is_drawing {
+            return Err(anyhow::anyhow!(BatchError::BatchIsDrawing));
+        }
+
+        let config = std::mem::take(&mut self.config);
+
+        self.is_drawing = true;
+        let batch = self.collect(draw);
+        self.is_drawing = false;
+
+        self.config = config;
+
+        Ok(batch)
+    }
+
+    /// Quads drawn by `draw` in submission order, as a `StaticBatch` instead of batched for the current pass.
+    fn collect(&mut self, draw: impl FnOnce(&mut Batch)) -> StaticBatch {
+        let (sort_mode, normals) = (self.config.sort_mode, self.config.normals);
+
+        self.config.sort_mode = SortMode::Deferred;
+        self.config.normals = false;
+        self.is_recording = true;
+
+        self.draw_calls.clear();
+        self.vertices.clear();
+
+        draw(self);
+
+        self.is_recording = false;
+        self.config.sort_mode = sort_mode;
+        self.config.normals = normals;
+
+        let vertices = std::mem::take(&mut self.vertices);
+
+        StaticBatch {
+            vertex_buffer: (!vertices.is_empty()).then(|| raw::VertexBuffer::new(crate::cast(&vertices))),
+            draw_calls: std::mem::take(&mut self.draw_calls),
+            quads: vertices.len() as u32 / 4,
+        }
+    }
+
+    /// Limits the quads drawn after it to `clip` until the matching `pop_clip`, intersected with the clips pushed
+    /// before. `clip` is in the coordinates the quads are drawn in, rotated views clip to its bounding box on screen.
+    pub fn push_clip(&mut self, clip: Rectangle<f32>) -> anyhow::Result<()> {
+        self.flush_stack_change()?;
+        self.clips.push(clip);
+
+        Ok(())
+    }
+
+    /// Removes the clip pushed last, does nothing without one.
+    pub fn pop_clip(&mut self) -> anyhow::Result<()> {
+        self.flush_stack_change()?;
+        self.clips.pop();
+
+        Ok(())
+    }
+
+    /// Limits the quads drawn after it to the shapes and sprites drawn by `draw` until the matching `pop_mask`, inside
+    /// the masks pushed before. Texels less than half opaque are left out of the mask. Masks nest up to 255 deep.
+    ///
+    /// Masks use the stencil buffer, of the frame or of the target, which needs to be created with depth.
+    pub fn push_mask(&mut self, draw: impl FnOnce(&mut Batch)) -> anyhow::Result<()> {
+        self.flush_stack_change()?;
+
+        let Some(frame) = raw::frame() else {
+            return Err(anyhow::anyhow!(BatchError::FrameIsNone));
+        };
+
+        // a stale stencil from earlier passes would leak into the first mask
+        if self.masks.is_empty() && frame.create_stencil_render_pass(self.config.target.as_ref(), true).is_none() {
+            return Err(anyhow::anyhow!(BatchError::NoStencilBuffer));
+        }
+
+        let mask = self.collect(draw);
+        let level = self.masks.len() as u32;
+
+        if let Some(vertex_buffer) = &mask.vertex_buffer {
+            self.render(
+                &mask.draw_calls,
+                Vertices::Static(vertex_buffer),
+                (raw::StencilMode::Increment, level),
+            )?;
+        }
+
+        self.masks.push(mask);
+
+        Ok(())
+    }
+
+    /// Removes the mask pushed last, does nothing without one.
+    pub fn pop_mask(&mut self) -> anyhow::Result<()> {
+        self.flush_stack_change()?;
+
+        let level = self.masks.len() as u32;
+
+        let Some(mask) = self.masks.pop() else {
+            return Ok(());
+        };
+
+        match &mask.vertex_buffer {
+            Some(vertex_buffer) => self.render(
+                &mask.draw_calls,
+                Vertices::Static(vertex_buffer),
+                (raw::StencilMode::Decrement, level),
+            ),
+            None => Ok(()),
+        }
+    }
+
+    /// Clips and masks pushed and not popped yet.
+    pub fn clip_depth(&self) -> (usize, usize) {
+        (self.clips.len(), self.masks.len())
+    }
+
+    /// Draws what was batched under the clips and masks before they change.
+    fn flush_stack_change(&mut self) -> anyhow::Result<()> {
+        if !self.is_drawing {
+            return Err(anyhow::anyhow!(BatchError::BatchNotDrawing));
+        }
+
+        if self.is_recording {
+            return Err(anyhow::anyhow!(BatchError::BatchIsRecording));
+        }
+
+        self.submit()?;
+
+        self.draw_calls.clear();
+        self.vertices.clear();
+
+        Ok(())
+    }
+
+    /// Stencil mode and reference of the quads drawn under the current masks.
+    fn content_stencil(&self) -> (raw::StencilMode, u32) {
+        match self.masks.len() {
+            0 => (raw::StencilMode::Disabled, 0),
+            level => (raw::StencilMode::Test, level as u32),
+        }
+    }
+
+    /// Draws `batch` with the configuration of the current pass. Quads drawn before it are flushed first, so the
+    /// sorting modes only sort the quads between two static batches.
+    pub fn draw_static(&mut self, batch: &StaticBatch) -> anyhow::Result<()> {
+        if !self.is_drawing {
+            return Err(anyhow::anyhow!(BatchError::BatchNotDrawing));
+        }
+
+        if self.is_recording {
+            return Err(anyhow::anyhow!(BatchError::BatchIsRecording));
+        }
+
+        let required = batch.draw_calls.iter().map(|draw_call| draw_call.textures.len()).max().unwrap_or(0) as u32;
+
+        if required > self.texture_capacity() {
+            return Err(anyhow::anyhow!(BatchError::TextureCapacity {
+                required,
+                capacity: self.texture_capacity(),
+            }));
+        }
+
+        self.submit()?;
+
+        self.draw_calls.clear();
+        self.vertices.clear();
+
+        match &batch.vertex_buffer {
+            Some(vertex_buffer) => self.render(&batch.draw_calls, Vertices::Static(vertex_buffer), self.content_stencil()),
+            None => Ok(()),
+        }
+    }
+
+    /// Batches the quads held back by the sorting modes and flushes everything batched.
+    fn submit(&mut self) -> anyhow::Result<()> {
+        let mut queued = std::mem::take(&mut self.queued);
+
+        // stable sorts, equal keys keep their submission order
+        match self.config.sort_mode {
+            SortMode::Texture => queued.sort_by_key(|quad| quad.texture.id()),
+            SortMode::BackToFront => queued.sort_by(|a, b| b.depth.total_cmp(&a.depth)),
+            SortMode::FrontToBack => queued.sort_by(|a, b| a.depth.total_cmp(&b.depth)),
+            SortMode::Deferred | SortMode::Immediate => {},
+        }
+
+        for quad in queued.drain(..) {
+            self.add_texture(&quad.texture, quad.quad, quad.flags);
+        }
+
+        self.queued = queued;
+
+        self.flush()
+    }
+
+    fn flush(&mut self) -> anyhow::Result<()> {
+        if self.draw_calls.is_empty() {
+            return Ok(());
+        }
+
+        if raw::frame().is_none() {
+            return Err(anyhow::anyhow!(BatchError::FrameIsNone));
+        }
+
+        self.reset_stats();
+
+        let reallocations = self.vertex_buffer.reallocations();
+
+        let vertices = crate::cast(&self.vertices);
+        let offset = self.vertex_buffer.push(vertices);
+
+        self.stats.bytes_uploaded += vertices.len() as u64;
+        self.stats.reallocations += self.vertex_buffer.reallocations() - reallocations;
+
+        let draw_calls = std::mem::take(&mut self.draw_calls);
+        let result = self.render(&draw_calls, Vertices::Dynamic(offset), self.content_stencil());
+        self.draw_calls = draw_calls;
+
+        result
+    }
+
+    fn reset_stats(&mut self) {
+        if self.stats_frame != raw::frame_index() {
+            self.stats_frame = raw::frame_index();
+            self.stats = BatchStats::default();
+        }
+    }
+
+    /// Draws `draw_calls` in a render pass configured by the current `begin`, their quads start at the first vertex.
+    fn render(&mut self, draw_calls: &[DrawCall], vertices: Vertices, (stencil, reference): (raw::StencilMode, u32)) -> anyhow::Result<()> {
+        let Some(frame) = raw::frame() else {
+            return Err(anyhow::anyhow!(BatchError::FrameIsNone));
+        };
+
+        self.reset_stats();
+        self.ensure_pipeline(stencil);
+
+        let projection = self.view_projection();
+        let scissor = self.scissor(projection);
+
+        // clipped away entirely
+        if scissor.is_some_and(|(_, _, width, height)| width == 0 || height == 0) {
+            return Ok(());
+        }
+
+        let reallocations = self.quad_indices.reallocations();
+
+        // every draw call shares the same quad indices, offset by its first vertex
+        let quads = draw_calls.iter().map(|draw_call| draw_call.quads).max().unwrap_or(0);
+
+        if quads as usize * 6 > self.quad_indices.len() as usize {
+            let quads = quads.max(self.quad_indices.len() / 6 * 2);
+            self.quad_indices.set(crate::cast(&Self::quad_indices(quads)), quads as usize * 6);
+        }
+
+        let quads = draw_calls.iter().map(|draw_call| draw_call.quads).sum::<u32>();
+
+        self.stats.draw_calls += draw_calls.len() as u32;
+        self.stats.quads += quads;
+        self.stats.vertices += quads * 4;
+        self.stats.reallocations += self.quad_indices.reallocations() - reallocations;
+
+        self.camera_uniform.set(crate::cast(&projection.to_cols_array()));
+
+        let sampler = self
+            .samplers
+            .iter()
+            .find(|(config, _)| Some(*config) == self.config.sampler)
+            .map(|(_, sampler)| sampler);
+
+        let is_mask = matches!(stencil, raw::StencilMode::Increment | raw::StencilMode::Decrement);
+        let capacity = match is_mask {
+            true => self.capacity_for(None),
+            false => self.texture_capacity(),
+        };
+        let shader = self.config.shader.clone().filter(|_| !is_mask);
+        let uniform = shader
+            .as_ref()
+            .and_then(|shader| shader.uniform.as_ref())
+            .map(|uniform| uniform.borrow());
+
+        // bound before the pass starts, which borrows them until it ends
+        let bindings = draw_calls
+            .iter()
+            .map(|draw_call| {
+                let textures = (0..capacity as usize).map(|i| match draw_call.textures.get(i) {
+                    Some(texture) => texture.as_ref(),
+                    None => self.blank_texture.as_ref(),
+                });
+
+                match (self.bindless, sampler) {
+                    (true, _) => TextureBindings::Array(self.texture_arrays.get(&textures.collect::<Vec<_>>(), sampler)),
+                    (false, Some(sampler)) => TextureBindings::Bindings(
+                        textures
+                            .map(|texture| {
+                                self.texture_bindings
+                                    .get_or_insert_with((texture.id(), sampler.id()), || raw::TextureBinding::new(texture, sampler))
+                            })
+                            .collect(),
+                    ),
+                    (false, None) => TextureBindings::Textures(textures.collect()),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut rp = match (stencil, &self.config.target) {
+            (raw::StencilMode::Disabled, Some(target)) => frame.create_target_render_pass(target, false, raw::LoadOp::Load),
+            (raw::StencilMode::Disabled, None) => frame.create_render_pass(false),
+            (_, target) => match frame.create_stencil_render_pass(target.as_ref(), false) {
+                Some(rp) => rp,
+                None => return Err(anyhow::anyhow!(BatchError::NoStencilBuffer)),
+            },
+        };
+
+        rp.set_pipeline(&self.pipelines[&Self::pipeline_key(&self.config, stencil)]);
+        rp.set_dynamic_uniform(0, &self.camera_uniform);
+
+        if stencil != raw::StencilMode::Disabled {
+            rp.set_stencil_reference(reference);
+        }
+
+        if let Some((x, y, width, height)) = scissor {
+            rp.set_scissor_rect(x, y, width, height);
+        }
+
+        if let Some(uniform) = &uniform {
+            rp.set_dynamic_uniform(self.uniform_group(capacity), uniform);
+        }
+
+        match vertices {
+            Vertices::Dynamic(offset) => rp.set_dynamic_vertex_buffer_at(0, &self.vertex_buffer, offset),
+            Vertices::Static(vertex_buffer) => rp.set_vertex_buffer(0, vertex_buffer),
+        }
+        rp.set_dynamic_index_buffer(&self.quad_indices);
+
+        for (draw_call, bindings) in draw_calls.iter().zip(bindings.iter()) {
+            match bindings {
+                TextureBindings::Array(texture_array) => rp.set_texture_array(1, texture_array),
+                TextureBindings::Bindings(bindings) => {
+                    for (i, binding) in bindings.iter().enumerate() {
+                        rp.set_texture_binding(i as u32 + 1, binding);
+                    }
+                },
+                TextureBindings::Textures(textures) => {
+                    for (i, texture) in textures.iter().enumerate() {
+                        rp.set_texture(i as u32 + 1, texture);
+                    }
+                },
+            }
+
+            rp.draw_indexed(0..draw_call.quads * 6, (draw_call.first_quad * 4) as i32, 0..1);
+        }
+
+        Ok(())
+    }
+
+    /// Projection of the quads as they end up in the pass.
+    fn view_projection(&self) -> glam::Mat4 {
+        let projection = match (&self.config.camera, &self.config.target) {
+            (Some(camera), _) => camera.view_projection(),
+            (None, Some(target)) => Self::projection(target.width() as f32, target.height() as f32),
+            (None, None) => self.projection,
+        };
+
+        // the frame's render pass only covers the visible part of the virtual resolution
+        match (raw::viewport(), &self.config.target) {
+            (Some(viewport), None) => viewport.correction() * projection,
+            _ => projection,
+        }
+    }
+
+    /// Intersection of the clips in pixels of the attachment, from the top left.
+    fn scissor(&self, projection: glam::Mat4) -> Option<(u32, u32, u32, u32)> {
+        if self.clips.is_empty() {
+            return None;
+        }
+
+        let (width, height) = match &self.config.target {
+            Some(target) => (target.width(), target.height()),
+            None => {
+                let texture = raw::frame()?.texture();
+                (texture.width(), texture.height())
+            },
+        };
+
+        let area = match (raw::viewport(), &self.config.target) {
+            (Some(viewport), None) => viewport.visible_area(),
+            _ => Rectangle::new(0.0, 0.0, width as f32, height as f32),
+        };
+
+        let mut bounds = (0.0f32, 0.0f32, width as f32, height as f32);
+
+        for clip in &self.clips {
+            let pixels = Self::corners(*clip).map(|corner| {
+                let ndc = projection.project_point3(corner.extend(0.0));
+
+                glam::Vec2::new(
+                    area.x + (ndc.x + 1.0) * 0.5 * area.width,
+                    area.y + (1.0 - ndc.y) * 0.5 * area.height,
+                )
+            });
+
+            let min = pixels.iter().fold(glam::Vec2::MAX, |min, pixel| min.min(*pixel));
+            let max = pixels.iter().fold(glam::Vec2::MIN, |max, pixel| max.max(*pixel));
+
+            bounds = (bounds.0.max(min.x), bounds.1.max(min.y), bounds.2.min(max.x), bounds.3.min(max.y));
+        }
+
+        let (left, top) = (bounds.0.round().max(0.0) as u32, bounds.1.round().max(0.0) as u32);
+        let (right, bottom) = (bounds.2.round().max(0.0) as u32, bounds.3.round().max(0.0) as u32);
+
+        Some((
+            left.min(width),
+            top.min(height),
+            right.min(width).saturating_sub(left),
+            bottom.min(height).saturating_sub(top),
+        ))
+    }
+
+    pub(crate) fn corners(target: Rectangle<f32>) -> [glam::Vec2; 4] {
+        [
+            glam::Vec2::new(target.x, target.y),
+            glam::Vec2::new(target.x, target.y + target.height),
+            glam::Vec2::new(target.x + target.width, target.y + target.height),
+            glam::Vec2::new(target.x + target.width, target.y),
+        ]
+    }
+
+    fn add_quad(vertices: &mut Vec<Vertex>, draw_call: &mut DrawCall, quad: Quad, texture_index: u32, flags: u32) {
+        vertices.extend((0..4).map(|i| Vertex {
+            position: quad.corners[i].to_array(),
+            color: quad.colors[i].into(),
+            texcoord: quad.texcoords[i],
+            texture_index,
+            flags,
+        }));
+
+        draw_call.quads += 1;
+    }
+
+    pub(crate) fn draw_texture(&mut self, texture: &Rc<raw::Texture>, quad: Quad, depth: f32) {
+        self.queue_texture(texture, quad, depth, 0);
+    }
+
+    fn queue_texture(&mut self, texture: &Rc<raw::Texture>, quad: Quad, depth: f32, flags: u32) {
+        match self.config.sort_mode {
+            SortMode::Deferred => self.add_texture(texture, quad, flags),
+            SortMode::Immediate => {
+                self.add_texture(texture, quad, flags);
+
+                if let Err(err) = self.flush() {
+                    log::error!("Failed flushing immediate batch: {:?}", err);
+                }
+
+                self.draw_calls.clear();
+                self.vertices.clear();
+            },
+            SortMode::Texture | SortMode::BackToFront | SortMode::FrontToBack => self.queued.push(QueuedQuad {
+                texture: Rc::clone(texture),
+                quad,
+                depth,
+                flags,
+            }),
+        }
+    }
+
+    fn add_texture(&mut self, texture: &Rc<raw::Texture>, quad: Quad, flags: u32) {
+        let needs_new_draw_call = match self.draw_calls.last() {
+            None => true,
+            Some(dc) => {
+                !std::ptr::eq(dc.current_texture, texture.as_ref())
+                    && dc.textures.len() >= self.texture_capacity() as usize
+                    && !dc.textures.iter().any(|t| t.id() == texture.id())
+            },
+        };
+
+        if needs_new_draw_call {
+            self.draw_calls.push(DrawCall::new(texture, self.vertices.len() as u32 / 4));
+        }
+
+        let dc = self.draw_calls.last_mut().unwrap();
+
+        let index = if std::ptr::eq(dc.current_texture, texture.as_ref()) {
+            0
+        } else {
+            match dc.textures.iter().position(|t| t.id() == texture.id()) {
+                Some(index) => index,
+                None => {
+                    dc.textures.push(Rc::clone(texture));
+                    dc.textures.len() - 1
+                },
+            }
+        };
+
+        let flags = match texture.texture.format() {
+            wgpu::TextureFormat::R8Unorm => flags | SINGLE_CHANNEL,
+            _ => flags,
+        };
+
+        Self::add_quad(&mut self.vertices, dc, quad, index as u32, flags);
+    }
+
+    pub fn draw_sprite(&mut self, sprite: &Sprite) {
+        if let (true, Some(normal_map)) = (self.config.normals, sprite.normal_map_rc()) {
+            self.queue_texture(
+                normal_map,
+                Quad::textured(sprite.corners(), sprite.uv(), sprite.color()),
+                sprite.layer_depth(),
+                NORMAL_MAP,
+            );
+
+            return;
+        }
+
+        self.draw_texture(
+            sprite.texture_rc(),
+            Quad::textured(sprite.corners(), sprite.uv(), sprite.color()),
+            sprite.layer_depth(),
+        );
+    }
+
+    /// Draws the slices of `patch` over `target`.
+    pub fn draw_nine_patch(&mut self, patch: &NinePatch, target: Rectangle<f32>) {
+        for (target, uv) in patch.pieces(target) {
+            self.draw_texture(
+                patch.texture_rc(),
+                Quad::textured(Self::corners(target), uv, patch.color()),
+                patch.layer_depth(),
+            );
+        }
+    }
+
+    /// Draws the live particles of `emitter`. Blending comes from `begin_with`, pass the config's `blend_mode` there.
+    pub fn draw_particles(&mut self, emitter: &ParticleEmitter) {
+        let texture = match emitter.region() {
+            Some(region) => Rc::clone(region.texture_rc()),
+            None => Rc::clone(&self.blank_texture),
+        };
+        let uv = emitter.uv();
+
+        for (corners, color) in emitter.quads() {
+            self.draw_texture(&texture, Quad::textured(corners, uv, color), emitter.layer_depth());
+        }
+    }
+
+    /// Draws `text` with its top left corner at `position`, lines advance downwards. Glyphs sort at layer depth 0.
+    pub fn draw_text(&mut self, font: &Font, text: &str, position: Position<f32>, size: f32, color: Color<f32>) {
+        font.layout(text, size, |glyph, offset| {
+            if let Some(texture) = glyph.texture {
+                let target = Rectangle::new(
+                    (position.x + offset.x).round(),
+                    (position.y + offset.y).round(),
+                    glyph.size.width,
+                    glyph.size.height,
+                );
+
+                self.draw_texture(&texture, Quad::textured(Self::corners(target), glyph.uv, color), 0.0);
+            }
+        });
+    }
+
+    /// Draw calls batched since `begin`, the sorting modes only batch at `end`.
+    pub fn draw_calls(&self) -> usize {
+        self.draw_calls.len()
+    }
+
+    pub fn stats(&self) -> BatchStats {
+        self.stats
+    }
+
+    /// Distinct textures a single draw call can hold with the current configuration.
+    pub fn texture_capacity(&self) -> u32 {
+        self.capacity_for(self.config.shader.as_deref())
+    }
+
+    /// Whether textures are bound as one texture array per draw call instead of one bind group each.
+    pub fn uses_texture_arrays(&self) -> bool {
+        self.bindless
+    }
+}
+
+//                                ,;.
+//                              .;  `'-;.-
+//                            ,;`       `.,
+//                          ._;           ;`
+//                          `;            ;`
+//                           ;_ .-.-,`;   `;
+//                            ;`` '  o '`&`
+//                             ` o\      ;
+//                              `. `_   ;
+//                           *     .~_.'  *
+//                            )__.-\   |\ )
+//                          .' \   |   | `,.
+//                         ,'   `. `.  |  | `\
+//                        /      `. |  | ,'   `\
+//                      ,'    ,1  `.`. | |  ,   `\
+//                     /     / L   `.| |,' ;_     `.
+//                     \     \  \   `| |; ,' \    _)
+//                      `.    `)~\   |-|  |   `.-' ~-.
+//                        `._./   ;  | |  |  ,'      |
+//                         (    ,'`; |-|  |  \   _.--<
+//                          `-.'_,'  | |  |   `-(    7
+//                            ;      ; `. |      >-Y-')
+//                           /      ;   |  \     `-=p~
+//                         ,'      ;    :  `.      `|.
+//                        /       ;   |  `.  `.     ||
+//                      ,'       /    |   ;.  `.    ||
+//                     /       ,'    ,'  ' ;.   `.   ||
+//                   ,'       /     '|    ';`-.   `. ||
+//                  /        ;|`-._.,'-.__.'  `-.   `||
+//                ,'       ,' P     |     q     `-.  ||.
+//              ,'      ,-'  ;      ;     )        `-|| `-.
+//           ,-'      ,'     ;     ,^.    d         || `~-.`-.
+//         ,'      ,-'       `L   d  :    p         ||     `-.`.
+//       ,'     ,-'        ___|   q__:    b........||_        ~.\
+//     ,'   _,-'     __,--~   |    \ (_    \       |  `~~--._    `\
+//    /   ,'    _,--~         (_   |   `\.__|      ;         `~-.._|
+//   /  ,'   ,-~                \   \                              `
+//  / ,'  ,-~    kg              `-._>
+// | ; ,-~
+// |/_/
@@ -0,0 +1,438 @@
+use std::{
+    f32::consts::PI,
+    rc::Rc,
+};
+
+use super::{
+    Batch,
+    Quad,
+};
+use crate::{
+    Color,
+    Position,
+    Rectangle,
+    Size,
+};
+
+/// Miter length, in half line thicknesses, above which a miter join is drawn as a bevel.
+const MITER_LIMIT: f32 = 4.0;
+
+/// Angle covered by each triangle of round joins and caps.
+const ARC_STEP: f32 = PI / 16.0;
+
+/// How two segments of a thick line are connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineJoin {
+    /// Edges extended until they meet, drawn as a bevel for joins sharper than the miter limit.
+    #[default]
+    Miter,
+    Bevel,
+    Round,
+}
+
+/// How the ends of an open thick line are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineCap {
+    /// Ends exactly at the end points.
+    #[default]
+    Butt,
+    /// Extends half the thickness past the end points.
+    Square,
+    Round,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LineStyle {
+    pub thickness: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+}
+
+impl Default for LineStyle {
+    fn default() -> Self {
+        Self {
+            thickness: 1.0,
+            join: LineJoin::default(),
+            cap: LineCap::default(),
+        }
+    }
+}
+
+impl From<f32> for LineStyle {
+    fn from(thickness: f32) -> Self {
+        Self {
+            thickness,
+            ..Default::default()
+        }
+    }
+}
+
+//
+
+// shapes are drawn with the blank texture, so they batch with each other and sort at layer depth 0
+impl Batch {
+    pub fn fill_triangle(&mut self, points: [Position<f32>; 3], color: Color<f32>) {
+        self.fill_triangle_colors(points, [color; 3]);
+    }
+
+    pub fn fill_triangle_colors(&mut self, points: [Position<f32>; 3], colors: [Color<f32>; 3]) {
+        self.add_triangle(points.map(vec2), colors);
+    }
+
+    pub fn fill_rectangle(&mut self, rectangle: Rectangle<f32>, color: Color<f32>) {
+        self.fill_rectangle_colors(rectangle, [color; 4]);
+    }
+
+    /// `colors` go bottom left, top left, top right, bottom right.
+    pub fn fill_rectangle_colors(&mut self, rectangle: Rectangle<f32>, colors: [Color<f32>; 4]) {
+        let texture = Rc::clone(&self.blank_texture);
+
+        self.draw_texture(
+            &texture,
+            Quad {
+                corners: Self::corners(rectangle),
+                texcoords: [[0.5, 0.5]; 4],
+                colors,
+            },
+            0.0,
+        );
+    }
+
+    /// The outline lies inside `rectangle`, a thickness of half its smaller side or more fills it.
+    pub fn draw_rectangle(&mut self, rectangle: Rectangle<f32>, thickness: f32, color: Color<f32>) {
+        let Rectangle {
+            x,
+            y,
+            width,
+            height,
+        } = rectangle;
+
+        if thickness * 2.0 >= width.min(height) {
+            self.fill_rectangle(rectangle, color);
+            return;
+        }
+
+        self.fill_rectangle(Rectangle::new(x, y, width, thickness), color);
+        self.fill_rectangle(Rectangle::new(x, y + height - thickness, width, thickness), color);
+        self.fill_rectangle(Rectangle::new(x, y + thickness, thickness, height - thickness * 2.0), color);
+        self.fill_rectangle(
+            Rectangle::new(x + width - thickness, y + thickness, thickness, height - thickness * 2.0),
+            color,
+        );
+    }
+
+    pub fn fill_circle(&mut self, center: Position<f32>, radius: f32, segments: u32, color: Color<f32>) {
+        self.fill_ellipse(
+            center,
+            Size {
+                width: radius,
+                height: radius,
+            },
+            segments,
+            color,
+        );
+    }
+
+    /// See `draw_ellipse`.
+    pub fn draw_circle(&mut self, center: Position<f32>, radius: f32, segments: u32, thickness: f32, color: Color<f32>) {
+        self.draw_ellipse(
+            center,
+            Size {
+                width: radius,
+                height: radius,
+            },
+            segments,
+            thickness,
+            color,
+        );
+    }
+
+    /// `radius` holds the horizontal and vertical radius, `segments` is raised to at least 3.
+    pub fn fill_ellipse(&mut self, center: Position<f32>, radius: Size<f32>, segments: u32, color: Color<f32>) {
+        let center = vec2(center);
+        let points = ellipse_points(center, radius, segments);
+
+        for (i, point) in points.iter().enumerate() {
+            self.add_triangle([center, *point, points[(i + 1) % points.len()]], [color; 3]);
+        }
+    }
+
+    /// The outline lies inside the ellipse, a thickness of its smaller radius or more fills it.
+    pub fn draw_ellipse(&mut self, center: Position<f32>, radius: Size<f32>, segments: u32, thickness: f32, color: Color<f32>) {
+        if thickness >= radius.width.min(radius.height) {
+            self.fill_ellipse(center, radius, segments, color);
+            return;
+        }
+
+        let half = thickness / 2.0;
+        let points = ellipse_points(
+            vec2(center),
+            Size {
+                width: radius.width - half,
+                height: radius.height - half,
+            },
+            segments,
+        );
+        let colors = vec![color; points.len()];
+
+        self.stroke(&points, &colors, true, thickness.into());
+    }
+
+    /// Fills a simple polygon, convex or concave, in either winding. Self intersecting polygons are filled partially.
+    pub fn fill_polygon(&mut self, points: &[Position<f32>], color: Color<f32>) {
+        self.fill_polygon_colors(points, &vec![color; points.len()]);
+    }
+
+    /// Panics if `points` and `colors` differ in length.
+    pub fn fill_polygon_colors(&mut self, points: &[Position<f32>], colors: &[Color<f32>]) {
+        assert_eq!(points.len(), colors.len(), "every polygon point needs a color");
+
+        let points = points.iter().copied().map(vec2).collect::<Vec<_>>();
+
+        for [a, b, c] in triangulate(&points) {
+            self.add_triangle([points[a], points[b], points[c]], [colors[a], colors[b], colors[c]]);
+        }
+    }
+
+    /// Closed outline centered on the polygon edges, the style's cap is unused.
+    pub fn draw_polygon(&mut self, points: &[Position<f32>], style: impl Into<LineStyle>, color: Color<f32>) {
+        let points = points.iter().copied().map(vec2).collect::<Vec<_>>();
+
+        self.stroke(&points, &vec![color; points.len()], true, style.into());
+    }
+
+    pub fn draw_line(&mut self, start: Position<f32>, end: Position<f32>, style: impl Into<LineStyle>, color: Color<f32>) {
+        self.draw_polyline(&[start, end], style, color);
+    }
+
+    pub fn draw_polyline(&mut self, points: &[Position<f32>], style: impl Into<LineStyle>, color: Color<f32>) {
+        self.draw_polyline_colors(points, style, &vec![color; points.len()]);
+    }
+
+    /// Colors blend along each segment, joins take the color of their point. Panics if `points` and `colors` differ in length.
+    pub fn draw_polyline_colors(&mut self, points: &[Position<f32>], style: impl Into<LineStyle>, colors: &[Color<f32>]) {
+        assert_eq!(points.len(), colors.len(), "every polyline point needs a color");
+
+        let points = points.iter().copied().map(vec2).collect::<Vec<_>>();
+
+        self.stroke(&points, colors, false, style.into());
+    }
+
+    //
+
+    /// A triangle is a quad with its last two corners on the same point, turned clockwise like the corners of a quad.
+    fn add_triangle(&mut self, [a, b, c]: [glam::Vec2; 3], [ca, cb, cc]: [Color<f32>; 3]) {
+        let (corners, colors) = if (b - a).perp_dot(c - a) > 0.0 {
+            ([a, c, b, b], [ca, cc, cb, cb])
+        } else {
+            ([a, b, c, c], [ca, cb, cc, cc])
+        };
+
+        let texture = Rc::clone(&self.blank_texture);
+
+        self.draw_texture(
+            &texture,
+            Quad {
+                corners,
+                texcoords: [[0.5, 0.5]; 4],
+                colors,
+            },
+            0.0,
+        );
+    }
+
+    /// Fan of triangles around `center`, from the angle `start` over `sweep` radians.
+    fn add_arc(&mut self, center: glam::Vec2, radius: f32, start: f32, sweep: f32, color: Color<f32>) {
+        let steps = (sweep.abs() / ARC_STEP).ceil().max(1.0) as u32;
+        let point = |step: u32| center + glam::Vec2::from_angle(start + sweep * step as f32 / steps as f32) * radius;
+
+        for step in 0..steps {
+            self.add_triangle([center, point(step), point(step + 1)], [color; 3]);
+        }
+    }
+
+    fn stroke(&mut self, points: &[glam::Vec2], colors: &[Color<f32>], closed: bool, style: LineStyle) {
+        // repeated points have no direction to offset along
+        let mut path = points.iter().copied().zip(colors.iter().copied()).collect::<Vec<_>>();
+        path.dedup_by(|(a, _), (b, _)| a.distance_squared(*b) < f32::EPSILON);
+
+        if closed && path.len() > 2 && path[0].0.distance_squared(path[path.len() - 1].0) < f32::EPSILON {
+            path.pop();
+        }
+
+        if path.len() < 2 || style.thickness <= 0.0 {
+            return;
+        }
+
+        let half = style.thickness / 2.0;
+        let count = if closed {
+            path.len()
+        } else {
+            path.len() - 1
+        };
+
+        for i in 0..count {
+            let (mut a, color_a) = path[i];
+            let (mut b, color_b) = path[(i + 1) % path.len()];
+
+            let direction = (b - a).normalize();
+            let normal = direction.perp() * half;
+
+            if !closed {
+                if i == 0 {
+                    a = self.cap(a, -direction, half, style.cap, color_a);
+                }
+
+                if i == count - 1 {
+                    b = self.cap(b, direction, half, style.cap, color_b);
+                }
+            }
+
+            let texture = Rc::clone(&self.blank_texture);
+
+            self.draw_texture(
+                &texture,
+                Quad {
+                    corners: [a - normal, a + normal, b + normal, b - normal],
+                    texcoords: [[0.5, 0.5]; 4],
+                    colors: [color_a, color_a, color_b, color_b],
+                },
+                0.0,
+            );
+        }
+
+        let joins = if closed {
+            0..path.len()
+        } else {
+            1..path.len() - 1
+        };
+
+        for i in joins {
+            let previous = path[(i + path.len() - 1) % path.len()].0;
+            let (point, color) = path[i];
+            let next = path[(i + 1) % path.len()].0;
+
+            self.join(previous, point, next, half, style.join, color);
+        }
+    }
+
+    /// Draws the cap at `point` facing `outward` and returns where the segment has to start or end.
+    fn cap(&mut self, point: glam::Vec2, outward: glam::Vec2, half: f32, cap: LineCap, color: Color<f32>) -> glam::Vec2 {
+        match cap {
+            LineCap::Butt => point,
+            LineCap::Square => point + outward * half,
+            LineCap::Round => {
+                let start = outward.perp().to_angle();
+                self.add_arc(point, half, start, -PI, color);
+                point
+            },
+        }
+    }
+
+    /// Fills the gap segment quads leave on the outer side of a turn at `point`.
+    fn join(&mut self, previous: glam::Vec2, point: glam::Vec2, next: glam::Vec2, half: f32, join: LineJoin, color: Color<f32>) {
+        let incoming = (point - previous).normalize();
+        let outgoing = (next - point).normalize();
+
+        let turn = incoming.perp_dot(outgoing);
+        let cos = incoming.dot(outgoing);
+
+        if turn.abs() < f32::EPSILON && cos > 0.0 {
+            return;
+        }
+
+        // left turns open a gap on the right
+        let side = if turn > 0.0 {
+            -1.0
+        } else {
+            1.0
+        };
+        let outer_in = point + incoming.perp() * half * side;
+        let outer_out = point + outgoing.perp() * half * side;
+
+        match join {
+            LineJoin::Round => {
+                let start = (outer_in - point).to_angle();
+                let sweep = turn.atan2(cos);
+                self.add_arc(point, half, start, sweep, color);
+            },
+            LineJoin::Miter if (1.0 + cos) / 2.0 > 1.0 / (MITER_LIMIT * MITER_LIMIT) => {
+                // half the miter length over half the thickness is 1 / cos(half the angle between the segments)
+                let bisector = (incoming.perp() + outgoing.perp()).normalize() * side;
+                let tip = point + bisector * half / ((1.0 + cos) / 2.0).sqrt();
+
+                self.add_triangle([point, outer_in, tip], [color; 3]);
+                self.add_triangle([point, tip, outer_out], [color; 3]);
+            },
+            LineJoin::Miter | LineJoin::Bevel => self.add_triangle([point, outer_in, outer_out], [color; 3]),
+        }
+    }
+}
+
+fn vec2(position: Position<f32>) -> glam::Vec2 {
+    glam::Vec2::new(position.x, position.y)
+}
+
+fn ellipse_points(center: glam::Vec2, radius: Size<f32>, segments: u32) -> Vec<glam::Vec2> {
+    let segments = segments.max(3);
+
+    (0..segments)
+        .map(|i| {
+            let angle = i as f32 / segments as f32 * PI * 2.0;
+            center + glam::Vec2::new(angle.cos() * radius.width, angle.sin() * radius.height)
+        })
+        .collect()
+}
+
+/// Ear clipping, returns triangles as indices into `points`.
+fn triangulate(points: &[glam::Vec2]) -> Vec<[usize; 3]> {
+    if points.len() < 3 {
+        return vec![];
+    }
+
+    let mut remaining = (0..points.len()).collect::<Vec<_>>();
+
+    // ears are found on counter clockwise polygons
+    let area = (0..points.len())
+        .map(|i| points[i].perp_dot(points[(i + 1) % points.len()]))
+        .sum::<f32>();
+
+    if area < 0.0 {
+        remaining.reverse();
+    }
+
+    let mut triangles = Vec::with_capacity(points.len() - 2);
+
+    while remaining.len() > 3 {
+        let len = remaining.len();
+
+        let ear = (0..len).find(|&i| {
+            let [a, b, c] = [remaining[(i + len - 1) % len], remaining[i], remaining[(i + 1) % len]];
+            let [pa, pb, pc] = [points[a], points[b], points[c]];
+
+            (pb - pa).perp_dot(pc - pb) >= 0.0
+                && !remaining.iter().any(|&other| {
+                    let p = points[other];
+
+                    ![pa, pb, pc].contains(&p)
+                        && (pb - pa).perp_dot(p - pa) >= 0.0
+                        && (pc - pb).perp_dot(p - pb) >= 0.0
+                        && (pa - pc).perp_dot(p - pc) >= 0.0
+                })
+        });
+
+        // self intersecting polygons can run out of ears, the rest is dropped
+        let Some(i) = ear else {
+            break;
+        };
+
+        triangles.push([remaining[(i + len - 1) % len], remaining[i], remaining[(i + 1) % len]]);
+        remaining.remove(i);
+    }
+
+    if remaining.len() == 3 {
+        triangles.push([remaining[0], remaining[1], remaining[2]]);
+    }
+
+    triangles
+}
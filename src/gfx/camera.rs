@@ -0,0 +1,263 @@
+use crate::{
+    GameTime,
+    Position,
+    Rectangle,
+    Size,
+};
+
+/// 2D view into the world, centered on `position`, passed to `Batch::begin_with` through `BatchConfig::camera`.
+///
+/// Screen coordinates are pixels from the top left of the viewport, like `Input::mouse_position`. World coordinates
+/// grow up and to the right like those of a `Batch` without a camera.
+#[derive(Debug, Clone)]
+pub struct Camera2D {
+    position: glam::Vec2,
+    zoom: f32,
+    rotation: f32,
+    viewport: Size<f32>,
+    bounds: Option<Rectangle<f32>>,
+
+    target: Option<glam::Vec2>,
+    smoothing: f32,
+    deadzone: Option<Size<f32>>,
+
+    shake_magnitude: f32,
+    shake_duration: f32,
+    shake_remaining: f32,
+    shake_time: f32,
+}
+
+impl Camera2D {
+    /// Camera showing `viewport` pixels at zoom 1, centered so the world matches a `Batch` without a camera.
+    pub fn new(viewport: Size<u32>) -> Self {
+        let viewport = Size {
+            width: viewport.width as f32,
+            height: viewport.height as f32,
+        };
+
+        Self {
+            position: glam::Vec2::new(viewport.width / 2.0, viewport.height / 2.0),
+            zoom: 1.0,
+            rotation: 0.0,
+            viewport,
+            bounds: None,
+
+            target: None,
+            smoothing: 0.0,
+            deadzone: None,
+
+            shake_magnitude: 0.0,
+            shake_duration: 0.0,
+            shake_remaining: 0.0,
+            shake_time: 0.0,
+        }
+    }
+
+    /// World position at the center of the viewport.
+    pub fn position(&self) -> Position<f32> {
+        Position {
+            x: self.position.x,
+            y: self.position.y,
+        }
+    }
+
+    pub fn set_position(&mut self, position: Position<f32>) {
+        self.position = glam::Vec2::new(position.x, position.y);
+        self.clamp();
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Screen pixels per world unit, `2.0` shows everything twice as large.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom.max(f32::EPSILON);
+        self.clamp();
+    }
+
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    /// Counter-clockwise rotation of the view, in radians. The world appears to turn the other way.
+    pub fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+    }
+
+    pub fn viewport(&self) -> Size<f32> {
+        self.viewport
+    }
+
+    pub fn resize(&mut self, viewport: Size<u32>) {
+        self.viewport = Size {
+            width: viewport.width as f32,
+            height: viewport.height as f32,
+        };
+        self.clamp();
+    }
+
+    pub fn bounds(&self) -> Option<Rectangle<f32>> {
+        self.bounds
+    }
+
+    /// World area the view is kept inside of, ignoring rotation. Bounds smaller than the view center it instead.
+    pub fn set_bounds(&mut self, bounds: Option<Rectangle<f32>>) {
+        self.bounds = bounds;
+        self.clamp();
+    }
+
+    /// World area visible at the current position and zoom, ignoring rotation and shake.
+    pub fn visible_area(&self) -> Rectangle<f32> {
+        let half = self.half_extents();
+
+        Rectangle::new(self.position.x - half.x, self.position.y - half.y, half.x * 2.0, half.y * 2.0)
+    }
+
+    fn half_extents(&self) -> glam::Vec2 {
+        glam::Vec2::new(self.viewport.width, self.viewport.height) / (2.0 * self.zoom)
+    }
+
+    fn clamp(&mut self) {
+        let Some(bounds) = self.bounds else {
+            return;
+        };
+
+        let half = self.half_extents();
+
+        let clamp_axis = |value: f32, min: f32, size: f32, half: f32| {
+            if size <= half * 2.0 {
+                min + size / 2.0
+            } else {
+                value.clamp(min + half, min + size - half)
+            }
+        };
+
+        self.position.x = clamp_axis(self.position.x, bounds.x, bounds.width, half.x);
+        self.position.y = clamp_axis(self.position.y, bounds.y, bounds.height, half.y);
+    }
+
+    //
+
+    /// Moves towards `target` on every `update` until `stop_following` is called.
+    pub fn follow(&mut self, target: Position<f32>) {
+        self.target = Some(glam::Vec2::new(target.x, target.y));
+    }
+
+    pub fn stop_following(&mut self) {
+        self.target = None;
+    }
+
+    pub fn smoothing(&self) -> f32 {
+        self.smoothing
+    }
+
+    /// How quickly the camera catches up with its follow target, roughly the fraction of the distance covered per
+    /// second is `1 - e^-smoothing`. `0.0` snaps to the target right away.
+    pub fn set_smoothing(&mut self, smoothing: f32) {
+        self.smoothing = smoothing.max(0.0);
+    }
+
+    pub fn deadzone(&self) -> Option<Size<f32>> {
+        self.deadzone
+    }
+
+    /// World sized area around the center in which the follow target can move without moving the camera.
+    pub fn set_deadzone(&mut self, deadzone: Option<Size<f32>>) {
+        self.deadzone = deadzone;
+    }
+
+    /// Shakes the view by up to `magnitude` world units, fading out over `duration` seconds.
+    pub fn shake(&mut self, magnitude: f32, duration: f32) {
+        self.shake_magnitude = magnitude;
+        self.shake_duration = duration.max(f32::EPSILON);
+        self.shake_remaining = duration;
+    }
+
+    pub fn is_shaking(&self) -> bool {
+        self.shake_remaining > 0.0
+    }
+
+    pub fn update(&mut self, game_time: &GameTime) {
+        self.advance(game_time.delta());
+    }
+
+    pub fn advance(&mut self, delta: f32) {
+        if let Some(target) = self.target {
+            let mut goal = self.position;
+
+            match self.deadzone {
+                Some(deadzone) => {
+                    let half = glam::Vec2::new(deadzone.width, deadzone.height) / 2.0;
+                    let offset = target - self.position;
+
+                    // just far enough for the target to sit on the edge of the deadzone
+                    goal += offset - offset.clamp(-half, half);
+                },
+                None => goal = target,
+            }
+
+            self.position = if self.smoothing > 0.0 {
+                self.position.lerp(goal, 1.0 - (-self.smoothing * delta).exp())
+            } else {
+                goal
+            };
+
+            self.clamp();
+        }
+
+        if self.shake_remaining > 0.0 {
+            self.shake_remaining = (self.shake_remaining - delta).max(0.0);
+            self.shake_time += delta;
+        }
+    }
+
+    fn shake_offset(&self) -> glam::Vec2 {
+        if self.shake_remaining <= 0.0 {
+            return glam::Vec2::ZERO;
+        }
+
+        // incommensurate frequencies keep the motion from looking periodic
+        let t = self.shake_time;
+        let falloff = self.shake_remaining / self.shake_duration;
+
+        glam::Vec2::new((t * 47.0).sin() + (t * 83.0).sin(), (t * 59.0).cos() + (t * 97.0).sin()) * 0.5 * self.shake_magnitude * falloff
+    }
+
+    //
+
+    /// Maps world coordinates to clip space, shake included.
+    pub fn view_projection(&self) -> glam::Mat4 {
+        let (width, height) = (self.viewport.width / 2.0, self.viewport.height / 2.0);
+
+        let view = glam::Mat4::look_at_rh(glam::Vec3::new(0.0, 0.0, 1.0), glam::Vec3::new(0.0, 0.0, 0.0), glam::Vec3::Y);
+        let proj = glam::Mat4::orthographic_rh(-width, width, -height, height, 0.0, 1.0);
+
+        let camera = glam::Mat4::from_scale(glam::Vec3::new(self.zoom, self.zoom, 1.0))
+            * glam::Mat4::from_rotation_z(-self.rotation)
+            * glam::Mat4::from_translation((-(self.position + self.shake_offset())).extend(0.0));
+
+        proj * view * camera
+    }
+
+    pub fn screen_to_world(&self, screen: Position<f32>) -> Position<f32> {
+        let centered = glam::Vec2::new(screen.x - self.viewport.width / 2.0, self.viewport.height / 2.0 - screen.y);
+
+        let world = self.position + self.shake_offset() + glam::Vec2::from_angle(self.rotation).rotate(centered / self.zoom);
+
+        Position {
+            x: world.x,
+            y: world.y,
+        }
+    }
+
+    pub fn world_to_screen(&self, world: Position<f32>) -> Position<f32> {
+        let offset = glam::Vec2::new(world.x, world.y) - self.position - self.shake_offset();
+        let centered = glam::Vec2::from_angle(-self.rotation).rotate(offset) * self.zoom;
+
+        Position {
+            x: centered.x + self.viewport.width / 2.0,
+            y: self.viewport.height / 2.0 - centered.y,
+        }
+    }
+}
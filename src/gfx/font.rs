@@ -1,8 +0,0 @@
-#[derive(Debug)]
-pub struct Font {}
-
-impl Font {
-    pub fn new() -> Self {
-        Self {}
-    }
-}
@@ -0,0 +1,307 @@
+use std::rc::Rc;
+
+use hashbrown::HashMap;
+
+use super::Glyph;
+use crate::{
+    errors::FontError,
+    raw,
+    Position,
+    Rectangle,
+    Size,
+};
+
+//
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Descriptor {
+    pub size: f32,
+    pub line_height: f32,
+    pub base: f32,
+    pub scale: (f32, f32),
+    pub pages: Vec<String>,
+    pub chars: HashMap<char, Char>,
+    pub kernings: HashMap<(char, char), f32>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Char {
+    pub rect: Rectangle<f32>,
+    pub offset: Position<f32>,
+    pub advance: f32,
+    pub page: usize,
+}
+
+fn error(message: impl Into<String>) -> anyhow::Error {
+    anyhow::anyhow!(FontError::Parse(message.into()))
+}
+
+impl Descriptor {
+    /// Parses an AngelCode BMFont descriptor in either the text or the binary format.
+    pub fn parse(bytes: &[u8]) -> anyhow::Result<Self> {
+        if bytes.starts_with(b"BMF") {
+            Self::parse_binary(bytes)
+        } else {
+            Self::parse_text(std::str::from_utf8(bytes)?)
+        }
+    }
+
+    fn parse_text(text: &str) -> anyhow::Result<Self> {
+        let mut descriptor = Self::default();
+
+        for line in text.lines() {
+            let mut tokens = tokenize(line);
+
+            let Some(tag) = tokens.next() else {
+                continue;
+            };
+
+            let attributes = tokens
+                .filter_map(|token| token.split_once('='))
+                .map(|(key, value)| (key, value.trim_matches('"')))
+                .collect::<HashMap<_, _>>();
+
+            let number = |key: &str| -> anyhow::Result<f32> {
+                attributes
+                    .get(key)
+                    .ok_or_else(|| error(format!("`{tag}` is missing `{key}`")))?
+                    .parse::<f32>()
+                    .map_err(|_| error(format!("`{tag}` has an invalid `{key}`")))
+            };
+
+            match tag {
+                "info" => descriptor.size = number("size")?.abs(),
+                "common" => {
+                    descriptor.line_height = number("lineHeight")?;
+                    descriptor.base = number("base")?;
+                    descriptor.scale = (number("scaleW")?, number("scaleH")?);
+                },
+                "page" => {
+                    let id = number("id")? as usize;
+                    let file = attributes.get("file").ok_or_else(|| error("`page` is missing `file`"))?;
+
+                    if descriptor.pages.len() <= id {
+                        descriptor.pages.resize(id + 1, String::new());
+                    }
+                    descriptor.pages[id] = file.to_string();
+                },
+                "char" => {
+                    let Some(character) = char::from_u32(number("id")? as u32) else {
+                        continue;
+                    };
+
+                    descriptor.chars.insert(character, Char {
+                        rect: Rectangle::new(number("x")?, number("y")?, number("width")?, number("height")?),
+                        offset: Position {
+                            x: number("xoffset")?,
+                            y: number("yoffset")?,
+                        },
+                        advance: number("xadvance")?,
+                        page: number("page")? as usize,
+                    });
+                },
+                "kerning" => {
+                    let first = char::from_u32(number("first")? as u32);
+                    let second = char::from_u32(number("second")? as u32);
+
+                    if let (Some(first), Some(second)) = (first, second) {
+                        descriptor.kernings.insert((first, second), number("amount")?);
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        Ok(descriptor)
+    }
+
+    fn parse_binary(bytes: &[u8]) -> anyhow::Result<Self> {
+        if bytes.get(3) != Some(&3) {
+            return Err(error("only version 3 of the binary format is supported"));
+        }
+
+        let mut descriptor = Self::default();
+        let mut rest = &bytes[4..];
+
+        while !rest.is_empty() {
+            let (kind, block) = match rest {
+                [kind, a, b, c, d, tail @ ..] => {
+                    let size = u32::from_le_bytes([*a, *b, *c, *d]) as usize;
+                    let block = tail.get(..size).ok_or_else(|| error("block is truncated"))?;
+
+                    rest = &tail[size..];
+                    (*kind, block)
+                },
+                _ => return Err(error("block header is truncated")),
+            };
+
+            let u16_at = |offset: usize| block.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+            let i16_at = |offset: usize| block.get(offset..offset + 2).map(|b| i16::from_le_bytes([b[0], b[1]]));
+
+            match kind {
+                // info
+                1 => descriptor.size = i16_at(0).ok_or_else(|| error("info block is truncated"))?.unsigned_abs() as f32,
+                // common
+                2 => {
+                    let values = (0..4)
+                        .map(|index| u16_at(index * 2).map(|value| value as f32))
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| error("common block is truncated"))?;
+
+                    descriptor.line_height = values[0];
+                    descriptor.base = values[1];
+                    descriptor.scale = (values[2], values[3]);
+                },
+                // pages, null terminated file names
+                3 => {
+                    descriptor.pages = block
+                        .split(|byte| *byte == 0)
+                        .filter(|name| !name.is_empty())
+                        .map(|name| String::from_utf8_lossy(name).into_owned())
+                        .collect();
+                },
+                // chars, 20 bytes each
+                4 => {
+                    for entry in block.chunks_exact(20) {
+                        let u16_at = |offset: usize| u16::from_le_bytes([entry[offset], entry[offset + 1]]) as f32;
+                        let i16_at = |offset: usize| i16::from_le_bytes([entry[offset], entry[offset + 1]]) as f32;
+
+                        let Some(character) = char::from_u32(u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]])) else {
+                            continue;
+                        };
+
+                        descriptor.chars.insert(character, Char {
+                            rect: Rectangle::new(u16_at(4), u16_at(6), u16_at(8), u16_at(10)),
+                            offset: Position {
+                                x: i16_at(12),
+                                y: i16_at(14),
+                            },
+                            advance: i16_at(16),
+                            page: entry[18] as usize,
+                        });
+                    }
+                },
+                // kerning pairs, 10 bytes each
+                5 => {
+                    for entry in block.chunks_exact(10) {
+                        let first = char::from_u32(u32::from_le_bytes([entry[0], entry[1], entry[2], entry[3]]));
+                        let second = char::from_u32(u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]));
+                        let amount = i16::from_le_bytes([entry[8], entry[9]]) as f32;
+
+                        if let (Some(first), Some(second)) = (first, second) {
+                            descriptor.kernings.insert((first, second), amount);
+                        }
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        Ok(descriptor)
+    }
+}
+
+/// Splits a line of the text format on whitespace, keeping quoted values together.
+fn tokenize(line: &str) -> impl Iterator<Item = &str> {
+    let mut quoted = false;
+
+    line.split(move |character: char| {
+        if character == '"' {
+            quoted = !quoted;
+        }
+
+        !quoted && character.is_whitespace()
+    })
+    .filter(|token| !token.is_empty())
+}
+
+//
+
+#[derive(Debug)]
+pub(crate) struct BitmapFont {
+    descriptor: Descriptor,
+    pages: Vec<Rc<raw::Texture>>,
+}
+
+impl BitmapFont {
+    pub fn new(descriptor: Descriptor, pages: Vec<raw::Texture>) -> anyhow::Result<Self> {
+        if pages.len() < descriptor.pages.len() {
+            return Err(anyhow::anyhow!(FontError::MissingPages {
+                expected: descriptor.pages.len(),
+                found: pages.len(),
+            }));
+        }
+
+        if descriptor.size <= 0.0 {
+            return Err(error("font size must be positive"));
+        }
+
+        Ok(Self {
+            descriptor,
+            pages: pages.into_iter().map(Rc::new).collect(),
+        })
+    }
+
+    pub fn size(&self) -> f32 {
+        self.descriptor.size
+    }
+
+    fn scale(&self, size: f32) -> f32 {
+        size / self.descriptor.size
+    }
+
+    pub fn line_height(&self, size: f32) -> f32 {
+        self.descriptor.line_height * self.scale(size)
+    }
+
+    pub fn ascent(&self, size: f32) -> f32 {
+        self.descriptor.base * self.scale(size)
+    }
+
+    pub fn kerning(&self, left: char, right: char, size: f32) -> f32 {
+        self.descriptor.kernings.get(&(left, right)).copied().unwrap_or(0.0) * self.scale(size)
+    }
+
+    pub fn glyph(&self, character: char, size: f32) -> Glyph {
+        let scale = self.scale(size);
+
+        let Some(glyph) = self.descriptor.chars.get(&character) else {
+            return Glyph {
+                texture: None,
+                uv: Rectangle::default(),
+                offset: Position::default(),
+                size: Size::default(),
+                advance: 0.0,
+            };
+        };
+
+        let texture = self
+            .pages
+            .get(glyph.page)
+            .filter(|_| glyph.rect.width > 0.0 && glyph.rect.height > 0.0);
+        let page_size = texture
+            .map(|texture| (texture.dimensions.0 as f32, texture.dimensions.1 as f32))
+            .unwrap_or(self.descriptor.scale);
+
+        Glyph {
+            texture: texture.cloned(),
+            uv: Rectangle::new(
+                glyph.rect.x / page_size.0,
+                glyph.rect.y / page_size.1,
+                glyph.rect.width / page_size.0,
+                glyph.rect.height / page_size.1,
+            ),
+
+            // BMFont offsets are measured down from the top of the line
+            offset: Position {
+                x: glyph.offset.x * scale,
+                y: (self.descriptor.base - glyph.offset.y - glyph.rect.height) * scale,
+            },
+            size: Size {
+                width: glyph.rect.width * scale,
+                height: glyph.rect.height * scale,
+            },
+            advance: glyph.advance * scale,
+        }
+    }
+}
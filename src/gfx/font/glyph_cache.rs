@@ -0,0 +1,108 @@
+use std::rc::Rc;
+
+use hashbrown::HashMap;
+
+use crate::{
+    raw,
+    Rectangle,
+};
+
+//
+
+const PAGE_SIZE: u32 = 1024;
+const PADDING: u32 = 1;
+
+#[derive(Debug, Clone)]
+pub(crate) struct CachedGlyph {
+    pub page: usize,
+    pub rect: Rectangle<u32>,
+}
+
+/// Packs rasterized glyphs into shelves of `PAGE_SIZE` atlas pages, opening a new page when one is full.
+#[derive(Debug, Default)]
+pub(crate) struct GlyphCache {
+    pages: Vec<Rc<raw::Texture>>,
+
+    cursor: (u32, u32),
+    shelf_height: u32,
+
+    glyphs: HashMap<(char, u32), Option<CachedGlyph>>,
+}
+
+impl GlyphCache {
+    pub fn get(&self, key: (char, u32)) -> Option<&Option<CachedGlyph>> {
+        self.glyphs.get(&key)
+    }
+
+    pub fn page(&self, index: usize) -> &Rc<raw::Texture> {
+        &self.pages[index]
+    }
+
+    pub fn page_size() -> u32 {
+        PAGE_SIZE
+    }
+
+    /// Stores an alpha `coverage` bitmap, `None` marks glyphs without pixels (e.g. space) or too large to cache.
+    pub fn insert(&mut self, key: (char, u32), dimensions: (u32, u32), coverage: &[u8]) -> Option<CachedGlyph> {
+        let glyph = if dimensions.0 == 0 || dimensions.1 == 0 {
+            None
+        } else if dimensions.0 + PADDING > PAGE_SIZE || dimensions.1 + PADDING > PAGE_SIZE {
+            log::warn!("Glyph {:?} is too large for the glyph cache: {:?}", key.0, dimensions);
+            None
+        } else {
+            let (page, origin) = self.allocate(dimensions);
+
+            self.pages[page].write(origin, dimensions, coverage);
+
+            Some(CachedGlyph {
+                page,
+                rect: Rectangle::new(origin.0, origin.1, dimensions.0, dimensions.1),
+            })
+        };
+
+        self.glyphs.insert(key, glyph.clone());
+
+        glyph
+    }
+
+    fn allocate(&mut self, dimensions: (u32, u32)) -> (usize, (u32, u32)) {
+        if self.pages.is_empty() {
+            self.add_page();
+        }
+
+        if self.cursor.0 + dimensions.0 + PADDING > PAGE_SIZE {
+            self.cursor = (0, self.cursor.1 + self.shelf_height);
+            self.shelf_height = 0;
+        }
+
+        if self.cursor.1 + dimensions.1 + PADDING > PAGE_SIZE {
+            self.add_page();
+        }
+
+        let origin = self.cursor;
+
+        self.cursor.0 += dimensions.0 + PADDING;
+        self.shelf_height = self.shelf_height.max(dimensions.1 + PADDING);
+
+        (self.pages.len() - 1, origin)
+    }
+
+    /// Pages only hold coverage, the batch draws single channel textures white with it as alpha.
+    fn add_page(&mut self) {
+        let page = raw::Texture::new(
+            &vec![0; (PAGE_SIZE * PAGE_SIZE) as usize],
+            (PAGE_SIZE, PAGE_SIZE),
+            raw::TextureConfig {
+                mag_filter: raw::FilterMode::Linear,
+                min_filter: raw::FilterMode::Linear,
+                format: raw::TextureFormat::R8Unorm,
+                ..Default::default()
+            },
+        );
+
+        self.pages.push(Rc::new(page));
+
+        self.cursor = (0, 0);
+        self.shelf_height = 0;
+    }
+}
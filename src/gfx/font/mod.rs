@@ -0,0 +1,241 @@
+mod bitmap;
+mod glyph_cache;
+
+use std::{
+    cell::RefCell,
+    fs,
+    path::Path,
+    rc::Rc,
+};
+
+use bitmap::{
+    BitmapFont,
+    Descriptor,
+};
+use glyph_cache::GlyphCache;
+
+use crate::{
+    assets,
+    errors::FontError,
+    raw,
+    Position,
+    Rectangle,
+    Size,
+};
+
+//
+
+/// Glyph ready to be drawn, `offset` is the bottom left corner relative to the pen on the baseline.
+#[derive(Debug, Clone)]
+pub(crate) struct Glyph {
+    pub texture: Option<Rc<raw::Texture>>,
+    pub uv: Rectangle<f32>,
+
+    pub offset: Position<f32>,
+    pub size: Size<f32>,
+    pub advance: f32,
+}
+
+#[derive(Debug)]
+enum Source {
+    TrueType(Box<fontdue::Font>),
+    Bitmap(BitmapFont),
+}
+
+//
+
+#[derive(Debug)]
+pub struct Font {
+    source: Source,
+    cache: RefCell<GlyphCache>,
+}
+
+impl Font {
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let font = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default())
+            .map_err(|err| anyhow::anyhow!(FontError::Parse(err.to_string())))?;
+
+        Ok(Self {
+            source: Source::TrueType(Box::new(font)),
+            cache: RefCell::new(GlyphCache::default()),
+        })
+    }
+
+    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Loads an AngelCode BMFont from a text or binary `.fnt` descriptor and its page images, in page id order.
+    pub fn from_bmfont_bytes(descriptor: &[u8], pages: &[&[u8]]) -> anyhow::Result<Self> {
+        let descriptor = Descriptor::parse(descriptor)?;
+
+        let pages = pages
+            .iter()
+            .map(|bytes| assets::load_texture_from_bytes(bytes))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Self::from_bitmap(BitmapFont::new(descriptor, pages)?)
+    }
+
+    /// Loads an AngelCode BMFont descriptor, with its pages resolved relative to the descriptor's directory.
+    pub fn from_bmfont_path(path: &Path) -> anyhow::Result<Self> {
+        let descriptor = Descriptor::parse(&fs::read(path)?)?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+
+        let pages = descriptor
+            .pages
+            .iter()
+            .map(|file| assets::load_texture_from_bytes(&fs::read(directory.join(file))?))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Self::from_bitmap(BitmapFont::new(descriptor, pages)?)
+    }
+
+    fn from_bitmap(font: BitmapFont) -> anyhow::Result<Self> {
+        Ok(Self {
+            source: Source::Bitmap(font),
+            cache: RefCell::new(GlyphCache::default()),
+        })
+    }
+
+    /// Size the font was authored at, bitmap fonts stay crisp at integer multiples of it.
+    pub fn native_size(&self) -> Option<f32> {
+        match &self.source {
+            Source::TrueType(_) => None,
+            Source::Bitmap(font) => Some(font.size()),
+        }
+    }
+
+    /// Distance between the baselines of two lines.
+    pub fn line_height(&self, size: f32) -> f32 {
+        match &self.source {
+            Source::TrueType(font) => font
+                .horizontal_line_metrics(size)
+                .map(|metrics| metrics.new_line_size)
+                .unwrap_or(size),
+            Source::Bitmap(font) => font.line_height(size),
+        }
+    }
+
+    fn ascent(&self, size: f32) -> f32 {
+        match &self.source {
+            Source::TrueType(font) => font.horizontal_line_metrics(size).map(|metrics| metrics.ascent).unwrap_or(size),
+            Source::Bitmap(font) => font.ascent(size),
+        }
+    }
+
+    fn kerning(&self, left: char, right: char, size: f32) -> f32 {
+        match &self.source {
+            Source::TrueType(font) => font.horizontal_kern(left, right, size).unwrap_or(0.0),
+            Source::Bitmap(font) => font.kerning(left, right, size),
+        }
+    }
+
+    pub(crate) fn glyph(&self, character: char, size: f32) -> Glyph {
+        match &self.source {
+            Source::TrueType(font) => {
+                let key = (character, size.to_bits());
+
+                let cached = self.cache.borrow().get(key).cloned();
+
+                let (metrics, cached) = match cached {
+                    Some(cached) => (font.metrics(character, size), cached),
+                    None => {
+                        let (metrics, coverage) = font.rasterize(character, size);
+
+                        let cached = self
+                            .cache
+                            .borrow_mut()
+                            .insert(key, (metrics.width as u32, metrics.height as u32), &coverage);
+
+                        (metrics, cached)
+                    },
+                };
+
+                let page_size = GlyphCache::page_size() as f32;
+
+                Glyph {
+                    texture: cached.as_ref().map(|cached| Rc::clone(self.cache.borrow().page(cached.page))),
+                    uv: cached
+                        .map(|cached| {
+                            Rectangle::new(
+                                cached.rect.x as f32 / page_size,
+                                cached.rect.y as f32 / page_size,
+                                cached.rect.width as f32 / page_size,
+                                cached.rect.height as f32 / page_size,
+                            )
+                        })
+                        .unwrap_or_default(),
+
+                    offset: Position {
+                        x: metrics.xmin as f32,
+                        y: metrics.ymin as f32,
+                    },
+                    size: Size {
+                        width: metrics.width as f32,
+                        height: metrics.height as f32,
+                    },
+                    advance: metrics.advance_width,
+                }
+            },
+            Source::Bitmap(font) => font.glyph(character, size),
+        }
+    }
+
+    /// Lays out `text` with its top left corner at the origin, y pointing up, calling `f` with every glyph and
+    /// its bottom left corner.
+    pub(crate) fn layout(&self, text: &str, size: f32, mut f: impl FnMut(Glyph, Position<f32>)) -> Size<f32> {
+        let ascent = self.ascent(size);
+        let line_height = self.line_height(size);
+
+        let mut lines = 1;
+        let mut pen = Position {
+            x: 0.0,
+            y: -ascent,
+        };
+        let mut width: f32 = 0.0;
+        let mut previous = None;
+
+        for character in text.chars() {
+            match character {
+                '\n' => {
+                    width = width.max(pen.x);
+
+                    lines += 1;
+                    pen.x = 0.0;
+                    pen.y -= line_height;
+                    previous = None;
+
+                    continue;
+                },
+                '\r' => continue,
+                _ => (),
+            }
+
+            if let Some(previous) = previous {
+                pen.x += self.kerning(previous, character, size);
+            }
+
+            let glyph = self.glyph(character, size);
+
+            let position = Position {
+                x: pen.x + glyph.offset.x,
+                y: pen.y + glyph.offset.y,
+            };
+
+            pen.x += glyph.advance;
+            previous = Some(character);
+
+            f(glyph, position);
+        }
+
+        Size {
+            width: width.max(pen.x),
+            height: lines as f32 * line_height,
+        }
+    }
+
+    pub fn measure_text(&self, text: &str, size: f32) -> Size<f32> {
+        self.layout(text, size, |_, _| ())
+    }
+}
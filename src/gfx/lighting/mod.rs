@@ -0,0 +1,565 @@
+use std::rc::Rc;
+
+use crate::{
+    errors::LightingError,
+    raw,
+    Batch,
+    BatchConfig,
+    BatchShader,
+    Camera2D,
+    Color,
+    Position,
+    Rectangle,
+    Size,
+};
+
+/// Distance shadows are pushed away from point and spot lights, in multiples of the radius.
+const SHADOW_REACH: f32 = 4.0;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, crate::Desc)]
+struct Vertex {
+    #[f32x2(0)]
+    position: [f32; 2],
+}
+
+/// Kind of light, directions are in degrees counter-clockwise from the x axis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightKind {
+    /// Shines in every direction from its position.
+    Point,
+    /// Cone of `angle` degrees around `direction`, its outer fifth fading out.
+    Spot { direction: f32, angle: f32 },
+    /// Covers the whole view from far away, shining towards `direction`.
+    Directional { direction: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Shadows {
+    #[default]
+    None,
+    Hard,
+    /// Edges blurred over this many pixels of the light map.
+    Soft(f32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Light {
+    pub kind: LightKind,
+    /// Ignored by directional lights.
+    pub position: Position<f32>,
+    pub color: Color<f32>,
+    pub intensity: f32,
+    /// Pixels the light reaches, for directional lights how long their shadows are.
+    pub radius: f32,
+    /// Exponent of the falloff towards the radius, 1 is linear.
+    pub falloff: f32,
+    /// Pixels above the scene, low lights graze normal maps and bring out their bumps. Directional lights come in at
+    /// 45 degrees.
+    pub height: f32,
+    pub shadows: Shadows,
+    pub enabled: bool,
+}
+
+impl Light {
+    pub fn point(position: Position<f32>, radius: f32, color: Color<f32>) -> Self {
+        Self {
+            kind: LightKind::Point,
+            position,
+            color,
+            intensity: 1.0,
+            radius,
+            falloff: 2.0,
+            height: radius / 4.0,
+            shadows: Shadows::None,
+            enabled: true,
+        }
+    }
+
+    pub fn spot(position: Position<f32>, radius: f32, direction: f32, angle: f32, color: Color<f32>) -> Self {
+        Self {
+            kind: LightKind::Spot {
+                direction,
+                angle,
+            },
+            ..Self::point(position, radius, color)
+        }
+    }
+
+    pub fn directional(direction: f32, color: Color<f32>) -> Self {
+        Self {
+            kind: LightKind::Directional {
+                direction,
+            },
+            falloff: 1.0,
+            ..Self::point(Position::default(), 64.0, color)
+        }
+    }
+
+    /// Direction the light travels in, for spot and directional lights.
+    fn direction(&self) -> glam::Vec2 {
+        match self.kind {
+            LightKind::Spot {
+                direction, ..
+            }
+            | LightKind::Directional {
+                direction,
+            } => glam::Vec2::from_angle(direction.to_radians()),
+            LightKind::Point => glam::Vec2::ZERO,
+        }
+    }
+}
+
+/// Closed polygon blocking light, in either winding. The polygon itself stays lit, like a wall facing the light.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occluder {
+    pub points: Vec<Position<f32>>,
+    pub enabled: bool,
+}
+
+impl Occluder {
+    pub fn new(points: Vec<Position<f32>>) -> Self {
+        Self {
+            points,
+            enabled: true,
+        }
+    }
+
+    pub fn rectangle(rectangle: Rectangle<f32>) -> Self {
+        Self::new(
+            Batch::corners(rectangle)
+                .map(|corner| Position {
+                    x: corner.x,
+                    y: corner.y,
+                })
+                .to_vec(),
+        )
+    }
+
+    /// Edges turned away from `light`, each with its outward normal pointing along the light.
+    fn back_edges(&self, light: &Light) -> impl Iterator<Item = (glam::Vec2, glam::Vec2)> + '_ {
+        let points = self
+            .points
+            .iter()
+            .map(|point| glam::Vec2::new(point.x, point.y))
+            .collect::<Vec<_>>();
+
+        // positive for counter-clockwise polygons, whose outward normals are on the right of every edge
+        let area = (0..points.len())
+            .map(|i| points[i].perp_dot(points[(i + 1) % points.len()]))
+            .sum::<f32>();
+
+        let light = *light;
+
+        (0..points.len()).filter_map(move |i| {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            let normal = (b - a).perp() * -area.signum();
+
+            let away = match light.kind {
+                LightKind::Directional {
+                    ..
+                } => light.direction(),
+                LightKind::Point
+                | LightKind::Spot {
+                    ..
+                } => (a + b) / 2.0 - glam::Vec2::new(light.position.x, light.position.y),
+            };
+
+            (normal.dot(away) > 0.0).then_some((a, b))
+        })
+    }
+}
+
+//
+
+/// 2D lighting composited over the frame from a light accumulation target.
+///
+/// A frame draws the scene's normals with the config of `normal_config`, then the scene as usual, then calls `draw`,
+/// which starts from the ambient color, adds every light shaded by the normals and its shadows, and multiplies the
+/// result into the frame. Skipping the normal pass lights the scene as if it were flat.
+#[derive(Debug)]
+pub struct Lighting {
+    lights: Vec<Light>,
+    occluders: Vec<Occluder>,
+    ambient: Color<f32>,
+
+    size: Size<u32>,
+    normals: raw::RenderTarget,
+    shadows: raw::RenderTarget,
+    light_map: raw::RenderTarget,
+    /// Frame the normals were last cleared in.
+    normals_frame: Option<u64>,
+
+    pipeline: raw::Pipeline,
+    camera_uniform: raw::DynamicUniform,
+    light_uniform: raw::DynamicUniform,
+    vertex_buffer: raw::DynamicVertexBuffer,
+    indices: raw::IndexBuffer,
+
+    normal_shader: Rc<BatchShader>,
+    /// Draws the shadows and the light map.
+    batch: Batch,
+}
+
+impl Lighting {
+    /// Lighting for a view of `size` pixels, the size of the batches drawing the scene.
+    pub fn new(size: Size<u32>) -> Self {
+        let pipeline = raw::Pipeline::new(raw::PipelineConfig {
+            shader_source: include_str_from_root!("res/shaders/lighting/light.wgsl"),
+            vertex_buffer_layouts: &[Vertex::desc()],
+            bind_group_layouts: vec![
+                (0, &raw::DynamicUniform::bind_group_layout(raw::ShaderStages::Vertex)),
+                (1, &raw::DynamicUniform::bind_group_layout(raw::ShaderStages::Fragment)),
+                (2, &raw::Texture::bind_group_layout()),
+                (3, &raw::Texture::bind_group_layout()),
+            ],
+            depth_stencil_write_enabled: false,
+            blend_mode: raw::BlendMode::Additive,
+            stencil: raw::StencilMode::Disabled,
+            format: None,
+            sample_count: None,
+        });
+
+        let mut batch = Batch::new();
+        batch.resize(size);
+
+        Self {
+            lights: vec![],
+            occluders: vec![],
+            ambient: Color::new(0.1, 0.1, 0.1, 1.0),
+
+            size,
+            normals: Self::normals_target(size),
+            shadows: Self::target(size),
+            light_map: Self::target(size),
+            normals_frame: None,
+
+            pipeline,
+            camera_uniform: raw::DynamicUniform::new(crate::cast(&glam::Mat4::IDENTITY.to_cols_array()), raw::ShaderStages::Vertex),
+            light_uniform: raw::DynamicUniform::new(crate::cast(&[0.0f32; 20]), raw::ShaderStages::Fragment),
+            vertex_buffer: raw::DynamicVertexBuffer::with_capacity(64 * 4 * std::mem::size_of::<Vertex>() as u64),
+            indices: raw::IndexBuffer::new(crate::cast(&[0u32, 2, 1, 0, 3, 2]), raw::IndexFormat::Uint32, 6),
+
+            normal_shader: Rc::new(BatchShader::new(include_str_from_root!(
+                "res/shaders/lighting/normals_fragment.wgsl"
+            ))),
+            batch,
+        }
+    }
+
+    fn target(size: Size<u32>) -> raw::RenderTarget {
+        raw::RenderTarget::new((size.width.max(1), size.height.max(1)), false, raw::TextureConfig::default())
+    }
+
+    /// Linear, the normals are data and come back out exactly as the normal pass wrote them.
+    fn normals_target(size: Size<u32>) -> raw::RenderTarget {
+        raw::RenderTarget::with_config((size.width.max(1), size.height.max(1)), raw::RenderTargetConfig {
+            format: Some(raw::TextureFormat::Rgba8Unorm),
+            ..Default::default()
+        })
+    }
+
+    pub fn resize(&mut self, size: Size<u32>) {
+        if size == self.size {
+            return;
+        }
+
+        self.size = size;
+        self.normals = Self::normals_target(size);
+        self.shadows = Self::target(size);
+        self.light_map = Self::target(size);
+        self.normals_frame = None;
+
+        self.batch.resize(size);
+    }
+
+    pub fn lights(&self) -> &[Light] {
+        &self.lights
+    }
+
+    pub fn lights_mut(&mut self) -> &mut Vec<Light> {
+        &mut self.lights
+    }
+
+    /// Adds `light` and returns its index into `lights`.
+    pub fn add_light(&mut self, light: Light) -> usize {
+        self.lights.push(light);
+        self.lights.len() - 1
+    }
+
+    pub fn occluders(&self) -> &[Occluder] {
+        &self.occluders
+    }
+
+    pub fn occluders_mut(&mut self) -> &mut Vec<Occluder> {
+        &mut self.occluders
+    }
+
+    /// Adds `occluder` and returns its index into `occluders`.
+    pub fn add_occluder(&mut self, occluder: Occluder) -> usize {
+        self.occluders.push(occluder);
+        self.occluders.len() - 1
+    }
+
+    pub fn ambient(&self) -> Color<f32> {
+        self.ambient
+    }
+
+    /// Light everything gets without any light, white leaves the scene unlit.
+    pub fn set_ambient(&mut self, ambient: Color<f32>) {
+        self.ambient = ambient;
+    }
+
+    /// Normals drawn by the normal pass, encoded like a normal map.
+    pub fn normals(&self) -> &raw::RenderTarget {
+        &self.normals
+    }
+
+    /// Light accumulated by the last `draw`.
+    pub fn light_map(&self) -> &raw::RenderTarget {
+        &self.light_map
+    }
+
+    /// Config for the batch drawing the normal pass, with the camera of the scene. The first call in a frame clears
+    /// the normals to flat.
+    pub fn normal_config(&mut self, camera: Option<Camera2D>) -> BatchConfig {
+        self.clear_normals();
+
+        BatchConfig {
+            target: Some(self.normals.clone()),
+            camera,
+            shader: Some(Rc::clone(&self.normal_shader)),
+            normals: true,
+            ..Default::default()
+        }
+    }
+
+    fn clear_normals(&mut self) {
+        if self.normals_frame == Some(raw::frame_index()) || raw::frame().is_none() {
+            return;
+        }
+
+        self.normals_frame = Some(raw::frame_index());
+
+        // the flat normal of the normal shader
+        self.normals.clear(raw::Color {
+            r: 0.5,
+            g: 0.5,
+            b: 1.0,
+            a: 1.0,
+        });
+    }
+
+    //
+
+    /// Accumulates the lights into the light map and multiplies it into the frame, with the camera of the scene.
+    pub fn draw(&mut self, camera: Option<&Camera2D>) -> anyhow::Result<()> {
+        if raw::frame().is_none() {
+            return Err(anyhow::anyhow!(LightingError::FrameIsNone));
+        }
+
+        self.clear_normals();
+
+        self.light_map.clear(raw::Color {
+            r: self.ambient.r as f64,
+            g: self.ambient.g as f64,
+            b: self.ambient.b as f64,
+            a: 1.0,
+        });
+
+        let view_projection = match camera {
+            Some(camera) => camera.view_projection(),
+            None => Batch::projection(self.size.width as f32, self.size.height as f32),
+        };
+
+        self.camera_uniform.set(crate::cast(&view_projection.to_cols_array()));
+
+        for index in 0..self.lights.len() {
+            let light = self.lights[index];
+
+            if !light.enabled || light.intensity <= 0.0 {
+                continue;
+            }
+
+            let shadows = light.shadows != Shadows::None && self.occluders.iter().any(|occluder| occluder.enabled);
+
+            if shadows {
+                self.draw_shadows(&light, camera)?;
+            }
+
+            self.draw_light(&light, shadows, view_projection)?;
+        }
+
+        self.batch.begin_with(BatchConfig {
+            blend_mode: raw::BlendMode::Multiply,
+            ..Default::default()
+        })?;
+
+        let mut light_map = self.light_map.sprite();
+        light_map.set_target(Rectangle::new(0.0, 0.0, self.size.width as f32, self.size.height as f32));
+        self.batch.draw_sprite(&light_map);
+
+        self.batch.end()
+    }
+
+    /// Clears the shadow mask to white and draws what `light` can not reach in black.
+    fn draw_shadows(&mut self, light: &Light, camera: Option<&Camera2D>) -> anyhow::Result<()> {
+        self.shadows.clear(raw::Color {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+            a: 1.0,
+        });
+
+        self.batch.begin_with(BatchConfig {
+            target: Some(self.shadows.clone()),
+            camera: camera.cloned(),
+            blend_mode: raw::BlendMode::Opaque,
+            ..Default::default()
+        })?;
+
+        let origin = glam::Vec2::new(light.position.x, light.position.y);
+        let black = Color::new(0.0, 0.0, 0.0, 1.0);
+        let position = |point: glam::Vec2| Position {
+            x: point.x,
+            y: point.y,
+        };
+
+        for occluder in self.occluders.iter().filter(|occluder| occluder.enabled) {
+            for (a, b) in occluder.back_edges(light) {
+                let points = match light.kind {
+                    LightKind::Directional {
+                        ..
+                    } => {
+                        let offset = light.direction() * light.radius;
+
+                        vec![a, b, b + offset, a + offset]
+                    },
+                    LightKind::Point
+                    | LightKind::Spot {
+                        ..
+                    } => {
+                        let (to_a, to_b) = ((a - origin).normalize_or_zero(), (b - origin).normalize_or_zero());
+                        let middle = (to_a + to_b).normalize_or_zero();
+
+                        // edges through the light cast nothing
+                        if middle == glam::Vec2::ZERO {
+                            continue;
+                        }
+
+                        // pushed out along the edge's rays and between them, so the far side stays past the radius
+                        // however wide the edge looks from the light
+                        let reach = light.radius * SHADOW_REACH;
+
+                        vec![a, b, b + to_b * reach, origin + middle * reach, a + to_a * reach]
+                    },
+                };
+
+                self.batch
+                    .fill_polygon(&points.into_iter().map(position).collect::<Vec<_>>(), black);
+            }
+        }
+
+        self.batch.end()
+    }
+
+    fn draw_light(&mut self, light: &Light, shadows: bool, view_projection: glam::Mat4) -> anyhow::Result<()> {
+        let direction = light.direction();
+
+        let (inner, outer) = match light.kind {
+            LightKind::Spot {
+                angle, ..
+            } => {
+                let half = (angle / 2.0).to_radians();
+
+                ((half * 0.8).cos(), half.cos())
+            },
+            LightKind::Point
+            | LightKind::Directional {
+                ..
+            } => (1.0, 1.0),
+        };
+
+        let (kind, softness) = (
+            match light.kind {
+                LightKind::Point => 0.0,
+                LightKind::Spot {
+                    ..
+                } => 1.0,
+                LightKind::Directional {
+                    ..
+                } => 2.0,
+            },
+            match light.shadows {
+                Shadows::Soft(softness) => softness,
+                Shadows::None | Shadows::Hard => 0.0,
+            },
+        );
+
+        self.light_uniform.set(crate::cast(&[
+            light.position.x,
+            light.position.y,
+            light.height,
+            light.radius.max(f32::EPSILON),
+            light.color.r,
+            light.color.g,
+            light.color.b,
+            light.intensity,
+            direction.x,
+            direction.y,
+            inner,
+            outer,
+            kind,
+            light.falloff,
+            softness,
+            shadows as u32 as f32,
+            self.size.width as f32,
+            self.size.height as f32,
+            0.0,
+            0.0,
+        ]));
+
+        let corners = match light.kind {
+            // the whole view, unprojected onto the scene
+            LightKind::Directional {
+                ..
+            } => {
+                let inverse = view_projection.inverse();
+                let depth = view_projection.project_point3(glam::Vec3::ZERO).z;
+
+                [(-1.0, -1.0), (-1.0, 1.0), (1.0, 1.0), (1.0, -1.0)]
+                    .map(|(x, y)| inverse.project_point3(glam::Vec3::new(x, y, depth)).truncate())
+            },
+            LightKind::Point
+            | LightKind::Spot {
+                ..
+            } => Batch::corners(Rectangle::new(
+                light.position.x - light.radius,
+                light.position.y - light.radius,
+                light.radius * 2.0,
+                light.radius * 2.0,
+            )),
+        };
+
+        let offset = self.vertex_buffer.push(crate::cast(&corners.map(|corner| Vertex {
+            position: corner.to_array(),
+        })));
+
+        let Some(frame) = raw::frame() else {
+            return Err(anyhow::anyhow!(LightingError::FrameIsNone));
+        };
+
+        let mut rp = frame.create_target_render_pass(&self.light_map, false, raw::LoadOp::Load);
+
+        rp.set_pipeline(&self.pipeline);
+        rp.set_dynamic_uniform(0, &self.camera_uniform);
+        rp.set_dynamic_uniform(1, &self.light_uniform);
+        rp.set_texture(2, self.normals.texture());
+        rp.set_texture(3, self.shadows.texture());
+        rp.set_dynamic_vertex_buffer_at(0, &self.vertex_buffer, offset);
+        rp.set_index_buffer(&self.indices);
+        rp.draw_indexed(0..6, 0, 0..1);
+
+        Ok(())
+    }
+}
@@ -1,8 +1,73 @@
+mod animation;
+mod atlas;
 mod batch;
+mod camera;
 mod font;
-pub(crate) mod raw;
+mod lighting;
+mod nine_patch;
+mod particles;
+pub mod raw;
 mod sprite;
+mod texture_region;
+mod tilemap;
 
+pub use animation::{
+    Animation,
+    AnimationEvent,
+    AnimationFrame,
+    PlayMode,
+};
+pub use atlas::{
+    AtlasBuilder,
+    AtlasConfig,
+    TextureAtlas,
+};
 pub use batch::*;
+pub use camera::Camera2D;
 pub use font::Font;
+pub use lighting::{
+    Light,
+    LightKind,
+    Lighting,
+    Occluder,
+    Shadows,
+};
+pub use nine_patch::{
+    Margins,
+    NinePatch,
+    PatchMode,
+};
+pub use particles::{
+    Burst,
+    Curve,
+    EmitterConfig,
+    EmitterShape,
+    Gradient,
+    Modifier,
+    ParticleEmitter,
+    Range,
+};
+pub use raw::{
+    BlendMode,
+    Pixels,
+    PostEffect,
+    PostProcess,
+    Readback,
+    RenderTarget,
+    ScalingPolicy,
+    Viewport,
+    VirtualResolutionConfig,
+};
 pub use sprite::Sprite;
+pub use texture_region::TextureRegion;
+pub use tilemap::{
+    Layer,
+    MapObject,
+    ObjectLayer,
+    ObjectShape,
+    Orientation,
+    Properties,
+    PropertyValue,
+    TileLayer,
+    TileMap,
+};
@@ -0,0 +1,300 @@
+use std::{
+    fs,
+    path::Path,
+    rc::Rc,
+};
+
+use crate::{
+    errors::NinePatchError,
+    raw,
+    Color,
+    Rectangle,
+    TextureRegion,
+};
+
+/// Insets from the edges of a rectangle, in pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Margins {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl Margins {
+    pub fn new(left: f32, right: f32, top: f32, bottom: f32) -> Self {
+        Self {
+            left,
+            right,
+            top,
+            bottom,
+        }
+    }
+
+    pub fn uniform(margin: f32) -> Self {
+        Self::new(margin, margin, margin, margin)
+    }
+}
+
+/// How the edges and the centre of a `NinePatch` fill the space between the corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PatchMode {
+    #[default]
+    Stretch,
+    /// Repeats the source at its own size from the top left, cutting off the last tile.
+    Tile,
+}
+
+/// Texture region split into nine slices by four margins, the corners keep their size at any target size.
+#[derive(Debug, Clone)]
+pub struct NinePatch {
+    region: TextureRegion,
+    margins: Margins,
+    padding: Option<Margins>,
+
+    edge_mode: PatchMode,
+    center_mode: PatchMode,
+    color: Color<f32>,
+    layer_depth: f32,
+}
+
+impl NinePatch {
+    pub fn new(texture: raw::Texture, margins: Margins) -> Self {
+        let source = Rectangle::new(0.0, 0.0, texture.dimensions.0 as f32, texture.dimensions.1 as f32);
+
+        Self::from_region(&TextureRegion::new(texture, source), margins)
+    }
+
+    pub fn from_region(region: &TextureRegion, margins: Margins) -> Self {
+        Self {
+            region: region.clone(),
+            margins,
+            padding: None,
+
+            edge_mode: PatchMode::default(),
+            center_mode: PatchMode::default(),
+            color: Color::default(),
+            layer_depth: 0.0,
+        }
+    }
+
+    /// Loads an Android `.9.png`, whose one pixel border marks the stretchable area on the top and left and the content
+    /// area on the bottom and right with black pixels. Several stretchable runs on one side are merged into one.
+    pub fn from_nine_png_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        let image = image::load_from_memory(bytes)?.to_rgba8();
+        let (width, height) = image.dimensions();
+
+        if width < 3 || height < 3 {
+            return Err(anyhow::anyhow!(NinePatchError::TooSmall(width, height)));
+        }
+
+        let row = |y: u32| (1..width - 1).map(|x| image.get_pixel(x, y).0).collect::<Vec<_>>();
+        let column = |x: u32| (1..height - 1).map(|y| image.get_pixel(x, y).0).collect::<Vec<_>>();
+
+        let (left, right) = guide(&row(0), "top")?.ok_or(NinePatchError::MissingGuide("top"))?;
+        let (top, bottom) = guide(&column(0), "left")?.ok_or(NinePatchError::MissingGuide("left"))?;
+
+        let padding = match (guide(&row(height - 1), "bottom")?, guide(&column(width - 1), "right")?) {
+            (None, None) => None,
+            (horizontal, vertical) => {
+                let (left, right) = horizontal.unwrap_or((left, right));
+                let (top, bottom) = vertical.unwrap_or((top, bottom));
+
+                Some(Margins::new(left, right, top, bottom))
+            },
+        };
+
+        let inner = image::imageops::crop_imm(&image, 1, 1, width - 2, height - 2).to_image();
+
+        let mut patch = Self::new(
+            raw::Texture::new(&inner, inner.dimensions(), raw::TextureConfig::default()),
+            Margins::new(left, right, top, bottom),
+        );
+        patch.padding = padding;
+
+        Ok(patch)
+    }
+
+    pub fn from_nine_png_path(path: &Path) -> anyhow::Result<Self> {
+        Self::from_nine_png_bytes(&fs::read(path)?)
+    }
+
+    pub(crate) fn texture_rc(&self) -> &Rc<raw::Texture> {
+        self.region.texture_rc()
+    }
+
+    pub fn region(&self) -> &TextureRegion {
+        &self.region
+    }
+
+    pub fn margins(&self) -> Margins {
+        self.margins
+    }
+
+    pub fn set_margins(&mut self, margins: Margins) {
+        self.margins = margins;
+    }
+
+    /// Content insets of a `.9.png`, `None` if it has no content guides.
+    pub fn padding(&self) -> Option<Margins> {
+        self.padding
+    }
+
+    pub fn set_padding(&mut self, padding: Option<Margins>) {
+        self.padding = padding;
+    }
+
+    pub fn edge_mode(&self) -> PatchMode {
+        self.edge_mode
+    }
+
+    pub fn set_edge_mode(&mut self, edge_mode: PatchMode) {
+        self.edge_mode = edge_mode;
+    }
+
+    pub fn center_mode(&self) -> PatchMode {
+        self.center_mode
+    }
+
+    pub fn set_center_mode(&mut self, center_mode: PatchMode) {
+        self.center_mode = center_mode;
+    }
+
+    pub fn color(&self) -> Color<f32> {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: Color<f32>) {
+        self.color = color;
+    }
+
+    /// Sort key for `SortMode::BackToFront` and `SortMode::FrontToBack`, 0 is the front and 1 the back.
+    pub fn layer_depth(&self) -> f32 {
+        self.layer_depth
+    }
+
+    pub fn set_layer_depth(&mut self, layer_depth: f32) {
+        self.layer_depth = layer_depth;
+    }
+
+    /// Area of `target` left for content, inside the padding or the margins if there is none.
+    pub fn content(&self, target: Rectangle<f32>) -> Rectangle<f32> {
+        let padding = self.padding.unwrap_or(self.margins);
+
+        Rectangle::new(
+            target.x + padding.left,
+            target.y + padding.bottom,
+            (target.width - padding.left - padding.right).max(0.0),
+            (target.height - padding.top - padding.bottom).max(0.0),
+        )
+    }
+
+    /// Target rectangles and normalized texture coordinates of every piece covering `target`.
+    ///
+    /// Margins larger than the target shrink to fit it.
+    pub(crate) fn pieces(&self, target: Rectangle<f32>) -> Vec<(Rectangle<f32>, Rectangle<f32>)> {
+        let source = self.region.source();
+        let Margins {
+            left,
+            right,
+            top,
+            bottom,
+        } = self.margins;
+
+        let horizontal = (target.width / (left + right)).min(1.0);
+        let vertical = (target.height / (top + bottom)).min(1.0);
+
+        // offsets and lengths of the three slices, in the source and in the target from its top left corner
+        let columns = [
+            (0.0, left, 0.0, left * horizontal),
+            (
+                left,
+                source.width - left - right,
+                left * horizontal,
+                target.width - (left + right) * horizontal,
+            ),
+            (source.width - right, right, target.width - right * horizontal, right * horizontal),
+        ];
+        let rows = [
+            (0.0, top, 0.0, top * vertical),
+            (
+                top,
+                source.height - top - bottom,
+                top * vertical,
+                target.height - (top + bottom) * vertical,
+            ),
+            (source.height - bottom, bottom, target.height - bottom * vertical, bottom * vertical),
+        ];
+
+        let (width, height) = (self.texture_rc().dimensions.0 as f32, self.texture_rc().dimensions.1 as f32);
+
+        let mut pieces = vec![];
+
+        for (row, &slice_y) in rows.iter().enumerate() {
+            for (column, &slice_x) in columns.iter().enumerate() {
+                let mode = match (column, row) {
+                    (1, 1) => self.center_mode,
+                    (1, _) | (_, 1) => self.edge_mode,
+                    _ => PatchMode::Stretch,
+                };
+
+                for (source_x, source_width, target_x, target_width) in tiles(slice_x, mode == PatchMode::Tile && column == 1) {
+                    for (source_y, source_height, target_y, target_height) in tiles(slice_y, mode == PatchMode::Tile && row == 1) {
+                        pieces.push((
+                            Rectangle::new(
+                                target.x + target_x,
+                                target.y + target.height - target_y - target_height,
+                                target_width,
+                                target_height,
+                            ),
+                            Rectangle::new(
+                                (source.x + source_x) / width,
+                                (source.y + source_y) / height,
+                                source_width / width,
+                                source_height / height,
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+
+        pieces
+    }
+}
+
+/// Splits a slice into tiles of its source length, or keeps it whole. Empty slices have no tiles.
+fn tiles((source, source_length, target, target_length): (f32, f32, f32, f32), tile: bool) -> Vec<(f32, f32, f32, f32)> {
+    if source_length <= 0.0 || target_length <= 0.0 {
+        return vec![];
+    }
+
+    if !tile {
+        return vec![(source, source_length, target, target_length)];
+    }
+
+    let mut tiles = vec![];
+    let mut offset = 0.0;
+
+    while offset < target_length {
+        let length = source_length.min(target_length - offset);
+        tiles.push((source, length, target + offset, length));
+        offset += source_length;
+    }
+
+    tiles
+}
+
+/// First and last black pixel of a `.9.png` border, as distances from both ends of the image inside the border.
+fn guide(pixels: &[[u8; 4]], side: &'static str) -> Result<Option<(f32, f32)>, NinePatchError> {
+    if let Some(pixel) = pixels.iter().find(|pixel| pixel[3] != 0 && **pixel != [0, 0, 0, 255]) {
+        return Err(NinePatchError::InvalidGuide(side, *pixel));
+    }
+
+    let first = pixels.iter().position(|pixel| pixel[3] != 0);
+    let last = pixels.iter().rposition(|pixel| pixel[3] != 0);
+
+    Ok(first
+        .zip(last)
+        .map(|(first, last)| (first as f32, (pixels.len() - 1 - last) as f32)))
+}
@@ -0,0 +1,363 @@
+use std::{
+    fs,
+    path::Path,
+};
+
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    raw::BlendMode,
+    Color,
+};
+
+/// Area new particles appear in, centered on the emitter position.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmitterShape {
+    #[default]
+    Point,
+    /// Segment of `length` pixels, rotated counter-clockwise by `angle` degrees.
+    Line {
+        length: f32,
+        angle: f32,
+    },
+    /// Disc of `radius` pixels, or only its outline if `edge` is set.
+    Circle {
+        radius: f32,
+        #[serde(default)]
+        edge: bool,
+    },
+    Rectangle {
+        width: f32,
+        height: f32,
+    },
+}
+
+/// Uniformly distributed value between `min` and `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Range {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Range {
+    pub fn new(min: f32, max: f32) -> Self {
+        Self {
+            min,
+            max,
+        }
+    }
+
+    pub fn constant(value: f32) -> Self {
+        Self::new(value, value)
+    }
+
+    /// Value at `t` between `min` and `max`.
+    pub(crate) fn at(&self, t: f32) -> f32 {
+        self.min + (self.max - self.min) * t
+    }
+}
+
+/// Particles emitted at once, `time` seconds after the emitter started and again every `interval` seconds if set.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Burst {
+    pub time: f32,
+    pub count: u32,
+    #[serde(default)]
+    pub interval: Option<f32>,
+}
+
+/// Piecewise linear curve over a particle's lifetime, keys are `(t, value)` pairs with `t` rising from 0 to 1.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Curve(pub Vec<(f32, f32)>);
+
+impl Curve {
+    pub fn constant(value: f32) -> Self {
+        Self(vec![(0.0, value)])
+    }
+
+    /// Linear from `start` at birth to `end` at death.
+    pub fn linear(start: f32, end: f32) -> Self {
+        Self(vec![(0.0, start), (1.0, end)])
+    }
+
+    /// Value at `t`, held flat before the first and after the last key. 1 without keys.
+    pub fn sample(&self, t: f32) -> f32 {
+        sample(&self.0, t, |a, b, t| a + (b - a) * t).unwrap_or(1.0)
+    }
+}
+
+/// Color keys over a particle's lifetime, as `(t, color)` pairs with `t` rising from 0 to 1.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Gradient(pub Vec<(f32, Color<f32>)>);
+
+impl Gradient {
+    /// Linear from `start` at birth to `end` at death.
+    pub fn linear(start: Color<f32>, end: Color<f32>) -> Self {
+        Self(vec![(0.0, start), (1.0, end)])
+    }
+
+    /// Color at `t`, held flat before the first and after the last key. White without keys.
+    pub fn sample(&self, t: f32) -> Color<f32> {
+        sample(&self.0, t, |a, b, t| {
+            Color::new(
+                a.r + (b.r - a.r) * t,
+                a.g + (b.g - a.g) * t,
+                a.b + (b.b - a.b) * t,
+                a.a + (b.a - a.a) * t,
+            )
+        })
+        .unwrap_or_default()
+    }
+}
+
+fn sample<T: Copy>(keys: &[(f32, T)], t: f32, lerp: impl Fn(T, T, f32) -> T) -> Option<T> {
+    let first = keys.first()?;
+    let last = keys.last()?;
+
+    if t <= first.0 {
+        return Some(first.1);
+    }
+
+    if t >= last.0 {
+        return Some(last.1);
+    }
+
+    let next = keys.iter().position(|(time, _)| *time > t)?;
+    let (start, a) = keys[next - 1];
+    let (end, b) = keys[next];
+
+    Some(lerp(a, b, (t - start) / (end - start).max(f32::EPSILON)))
+}
+
+/// Changes applied to every live particle as it ages, in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Modifier {
+    /// Acceleration in pixels per second squared, y-up.
+    Gravity { x: f32, y: f32 },
+    /// Rate the velocity decays at, it is scaled by `exp(-drag * seconds)`, framerate independent. A drag of 1 leaves
+    /// about 37% of it after a second.
+    Drag(f32),
+    /// Multiplies the start color.
+    ColorOverLifetime(Gradient),
+    /// Multiplies the start size.
+    SizeOverLifetime(Curve),
+    /// Multiplies the start angular velocity.
+    SpinOverLifetime(Curve),
+}
+
+/// Everything describing an effect, loadable from JSON so it can be tuned without recompiling.
+///
+/// Missing fields take their defaults, an empty object is a point shooting white particles upwards.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmitterConfig {
+    pub shape: EmitterShape,
+    /// Particles per second while emitting.
+    pub rate: f32,
+    pub bursts: Vec<Burst>,
+    /// Seconds the emitter emits for, forever if `None`.
+    pub duration: Option<f32>,
+    /// Particles beyond this count are not emitted.
+    pub max_particles: usize,
+
+    /// Seconds a particle lives.
+    pub lifetime: Range,
+    /// Pixels per second.
+    pub speed: Range,
+    /// Degrees counter-clockwise from the x axis.
+    pub direction: f32,
+    /// Degrees the direction varies by, centered on `direction`.
+    pub spread: f32,
+    /// Initial rotation in degrees.
+    pub rotation: Range,
+    /// Degrees per second, counter-clockwise.
+    pub angular_velocity: Range,
+    /// Width in pixels at birth, the height follows the aspect ratio of the region.
+    pub size: Range,
+    pub color: Color<f32>,
+
+    pub modifiers: Vec<Modifier>,
+    pub blend_mode: BlendMode,
+}
+
+impl Default for EmitterConfig {
+    fn default() -> Self {
+        Self {
+            shape: EmitterShape::Point,
+            rate: 10.0,
+            bursts: vec![],
+            duration: None,
+            max_particles: 1000,
+
+            lifetime: Range::constant(1.0),
+            speed: Range::constant(50.0),
+            direction: 90.0,
+            spread: 0.0,
+            rotation: Range::default(),
+            angular_velocity: Range::default(),
+            size: Range::constant(4.0),
+            color: Color::default(),
+
+            modifiers: vec![],
+            blend_mode: BlendMode::Alpha,
+        }
+    }
+}
+
+impl EmitterConfig {
+    pub fn from_json_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    pub fn from_json_path(path: &Path) -> anyhow::Result<Self> {
+        Self::from_json_bytes(&fs::read(path)?)
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    //
+
+    /// Gray puffs rising slowly, growing and fading out.
+    pub fn smoke() -> Self {
+        Self {
+            shape: EmitterShape::Circle {
+                radius: 4.0,
+                edge: false,
+            },
+            rate: 12.0,
+            max_particles: 64,
+            lifetime: Range::new(2.0, 3.0),
+            speed: Range::new(10.0, 20.0),
+            spread: 30.0,
+            rotation: Range::new(0.0, 360.0),
+            angular_velocity: Range::new(-30.0, 30.0),
+            size: Range::new(8.0, 12.0),
+            color: Color::new(0.5, 0.5, 0.5, 0.6),
+            modifiers: vec![
+                Modifier::Gravity {
+                    x: 4.0,
+                    y: 0.0,
+                },
+                Modifier::SizeOverLifetime(Curve::linear(1.0, 3.0)),
+                Modifier::ColorOverLifetime(Gradient(vec![
+                    (0.0, Color::new(1.0, 1.0, 1.0, 0.0)),
+                    (0.2, Color::new(1.0, 1.0, 1.0, 1.0)),
+                    (1.0, Color::new(1.0, 1.0, 1.0, 0.0)),
+                ])),
+            ],
+            ..Default::default()
+        }
+    }
+
+    /// Flames licking upwards, additively blended from yellow to red.
+    pub fn fire() -> Self {
+        Self {
+            shape: EmitterShape::Line {
+                length: 12.0,
+                angle: 0.0,
+            },
+            rate: 60.0,
+            max_particles: 128,
+            lifetime: Range::new(0.5, 1.0),
+            speed: Range::new(20.0, 40.0),
+            spread: 20.0,
+            size: Range::new(6.0, 10.0),
+            color: Color::new(1.0, 0.8, 0.3, 1.0),
+            modifiers: vec![
+                Modifier::SizeOverLifetime(Curve::linear(1.0, 0.2)),
+                Modifier::ColorOverLifetime(Gradient(vec![
+                    (0.0, Color::new(1.0, 1.0, 1.0, 1.0)),
+                    (0.5, Color::new(1.0, 0.4, 0.2, 0.8)),
+                    (1.0, Color::new(0.6, 0.0, 0.0, 0.0)),
+                ])),
+            ],
+            blend_mode: BlendMode::Additive,
+            ..Default::default()
+        }
+    }
+
+    /// Quick bright sparks bursting out in every direction and falling.
+    pub fn sparks() -> Self {
+        Self {
+            rate: 0.0,
+            bursts: vec![Burst {
+                time: 0.0,
+                count: 40,
+                interval: Some(1.0),
+            }],
+            max_particles: 256,
+            lifetime: Range::new(0.3, 0.8),
+            speed: Range::new(60.0, 120.0),
+            spread: 360.0,
+            size: Range::new(1.0, 2.0),
+            color: Color::new(1.0, 0.9, 0.5, 1.0),
+            modifiers: vec![
+                Modifier::Gravity {
+                    x: 0.0,
+                    y: -200.0,
+                },
+                Modifier::Drag(1.5),
+                Modifier::ColorOverLifetime(Gradient::linear(Color::new(1.0, 1.0, 1.0, 1.0), Color::new(1.0, 0.3, 0.0, 0.0))),
+            ],
+            blend_mode: BlendMode::Additive,
+            ..Default::default()
+        }
+    }
+
+    /// Drops falling from a line `width` pixels wide, place the emitter above the area to cover.
+    pub fn rain(width: f32) -> Self {
+        Self {
+            shape: EmitterShape::Line {
+                length: width,
+                angle: 0.0,
+            },
+            rate: width * 0.5,
+            max_particles: 1000,
+            lifetime: Range::constant(1.5),
+            speed: Range::new(200.0, 260.0),
+            direction: 260.0,
+            size: Range::constant(1.0),
+            color: Color::new(0.6, 0.7, 1.0, 0.6),
+            modifiers: vec![Modifier::Gravity {
+                x: 0.0,
+                y: -100.0,
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// A single burst of debris and a shrinking, fading flash.
+    pub fn explosion() -> Self {
+        Self {
+            rate: 0.0,
+            bursts: vec![Burst {
+                time: 0.0,
+                count: 60,
+                interval: None,
+            }],
+            duration: Some(0.0),
+            max_particles: 60,
+            lifetime: Range::new(0.4, 1.0),
+            speed: Range::new(40.0, 160.0),
+            spread: 360.0,
+            rotation: Range::new(0.0, 360.0),
+            angular_velocity: Range::new(-360.0, 360.0),
+            size: Range::new(3.0, 6.0),
+            color: Color::new(1.0, 0.7, 0.3, 1.0),
+            modifiers: vec![
+                Modifier::Drag(3.0),
+                Modifier::SizeOverLifetime(Curve::linear(1.0, 0.0)),
+                Modifier::ColorOverLifetime(Gradient::linear(Color::new(1.0, 1.0, 1.0, 1.0), Color::new(0.4, 0.1, 0.0, 0.0))),
+            ],
+            blend_mode: BlendMode::Additive,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,434 @@
+mod config;
+
+use std::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+
+pub use config::{
+    Burst,
+    Curve,
+    EmitterConfig,
+    EmitterShape,
+    Gradient,
+    Modifier,
+    Range,
+};
+
+use crate::{
+    Color,
+    GameTime,
+    Position,
+    Rectangle,
+    TextureRegion,
+};
+
+static NEXT_SEED: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone)]
+struct Particle {
+    position: glam::Vec2,
+    velocity: glam::Vec2,
+    /// Radians, counter-clockwise.
+    rotation: f32,
+    angular_velocity: f32,
+    size: f32,
+    age: f32,
+    lifetime: f32,
+
+    current_size: f32,
+    current_color: Color<f32>,
+}
+
+/// Xorshift generator, small and the same on every platform so effects replay identically from a seed.
+#[derive(Debug, Clone)]
+struct Random(u64);
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        // splitmix step, so neighbouring seeds start far apart and the state is never zero
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+        Self((z ^ (z >> 31)).max(1))
+    }
+
+    /// Uniform in `0.0..1.0`.
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+
+        (self.0.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 40) as f32 / (1u32 << 24) as f32
+    }
+
+    fn range(&mut self, range: Range) -> f32 {
+        range.at(self.next())
+    }
+}
+
+//
+
+/// CPU particle emitter driven by an `EmitterConfig`, drawn with `Batch::draw_particles`.
+///
+/// Particles live in world space, moving the emitter does not drag the live ones along.
+#[derive(Debug, Clone)]
+pub struct ParticleEmitter {
+    config: EmitterConfig,
+    region: Option<TextureRegion>,
+    position: Position<f32>,
+    layer_depth: f32,
+
+    particles: Vec<Particle>,
+    random: Random,
+
+    elapsed: f32,
+    accumulator: f32,
+    /// Next time each burst fires, `None` once it is done.
+    bursts: Vec<Option<f32>>,
+    emitting: bool,
+}
+
+impl ParticleEmitter {
+    /// Emitter of untextured squares, emitting right away. Every emitter gets a different seed.
+    pub fn new(config: EmitterConfig) -> Self {
+        let bursts = config.bursts.iter().map(|burst| Some(burst.time)).collect();
+
+        Self {
+            config,
+            region: None,
+            position: Position::default(),
+            layer_depth: 0.0,
+
+            particles: vec![],
+            random: Random::new(NEXT_SEED.fetch_add(1, Ordering::Relaxed)),
+
+            elapsed: 0.0,
+            accumulator: 0.0,
+            bursts,
+            emitting: true,
+        }
+    }
+
+    pub fn from_region(region: &TextureRegion, config: EmitterConfig) -> Self {
+        let mut emitter = Self::new(config);
+        emitter.set_region(Some(region));
+
+        emitter
+    }
+
+    pub fn config(&self) -> &EmitterConfig {
+        &self.config
+    }
+
+    /// Replaces the config and starts emitting from the beginning, live particles are kept.
+    pub fn set_config(&mut self, config: EmitterConfig) {
+        self.config = config;
+        self.start();
+    }
+
+    pub fn region(&self) -> Option<&TextureRegion> {
+        self.region.as_ref()
+    }
+
+    /// Texture of every particle, `None` draws plain squares.
+    pub fn set_region(&mut self, region: Option<&TextureRegion>) {
+        self.region = region.cloned();
+    }
+
+    pub fn position(&self) -> Position<f32> {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: Position<f32>) {
+        self.position = position;
+    }
+
+    /// Sort key for `SortMode::BackToFront` and `SortMode::FrontToBack`, 0 is the front and 1 the back.
+    pub fn layer_depth(&self) -> f32 {
+        self.layer_depth
+    }
+
+    pub fn set_layer_depth(&mut self, layer_depth: f32) {
+        self.layer_depth = layer_depth;
+    }
+
+    /// Restarts the random sequence, the same seed and updates give the same particles.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.random = Random::new(seed);
+    }
+
+    //
+
+    /// Starts emitting from the beginning, with the bursts and duration of the config.
+    pub fn start(&mut self) {
+        self.elapsed = 0.0;
+        self.accumulator = 0.0;
+        self.bursts = self.config.bursts.iter().map(|burst| Some(burst.time)).collect();
+        self.emitting = true;
+    }
+
+    /// Stops emitting, live particles play out.
+    pub fn stop(&mut self) {
+        self.emitting = false;
+    }
+
+    /// Removes every live particle.
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    /// Emits `count` particles right away, even if stopped.
+    pub fn burst(&mut self, count: u32) {
+        for _ in 0..count {
+            self.emit();
+        }
+
+        self.style();
+    }
+
+    pub fn is_emitting(&self) -> bool {
+        self.emitting
+    }
+
+    /// Stopped and without live particles.
+    pub fn is_finished(&self) -> bool {
+        !self.emitting && self.particles.is_empty()
+    }
+
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    //
+
+    pub fn update(&mut self, game_time: &GameTime) {
+        self.advance(game_time.delta());
+    }
+
+    /// Ages live particles by `delta` seconds, then emits the particles due in that time.
+    pub fn advance(&mut self, delta: f32) {
+        self.simulate(delta);
+
+        if self.emitting {
+            self.schedule(delta);
+        }
+
+        self.style();
+    }
+
+    fn simulate(&mut self, delta: f32) {
+        self.particles.retain_mut(|particle| {
+            particle.age += delta;
+            particle.age < particle.lifetime
+        });
+
+        for particle in &mut self.particles {
+            let t = particle.age / particle.lifetime;
+            let mut spin = 1.0;
+
+            for modifier in &self.config.modifiers {
+                match modifier {
+                    Modifier::Gravity {
+                        x,
+                        y,
+                    } => particle.velocity += glam::Vec2::new(*x, *y) * delta,
+                    Modifier::Drag(drag) => particle.velocity *= (-drag * delta).exp(),
+                    Modifier::SpinOverLifetime(curve) => spin *= curve.sample(t),
+                    Modifier::ColorOverLifetime(_) | Modifier::SizeOverLifetime(_) => {},
+                }
+            }
+
+            particle.position += particle.velocity * delta;
+            particle.rotation += particle.angular_velocity * spin * delta;
+        }
+    }
+
+    /// Size and color at the current age, new particles included.
+    fn style(&mut self) {
+        for particle in &mut self.particles {
+            let t = particle.age / particle.lifetime;
+
+            particle.current_size = particle.size;
+            particle.current_color = self.config.color;
+
+            for modifier in &self.config.modifiers {
+                match modifier {
+                    Modifier::ColorOverLifetime(gradient) => {
+                        let color = gradient.sample(t);
+                        let current = &mut particle.current_color;
+
+                        *current = Color::new(current.r * color.r, current.g * color.g, current.b * color.b, current.a * color.a);
+                    },
+                    Modifier::SizeOverLifetime(curve) => particle.current_size *= curve.sample(t),
+                    Modifier::Gravity {
+                        ..
+                    }
+                    | Modifier::Drag(_)
+                    | Modifier::SpinOverLifetime(_) => {},
+                }
+            }
+        }
+    }
+
+    fn schedule(&mut self, delta: f32) {
+        let start = self.elapsed;
+        let end = match self.config.duration {
+            Some(duration) => (start + delta).min(duration),
+            None => start + delta,
+        };
+
+        self.accumulator += self.config.rate * (end - start).max(0.0);
+
+        while self.accumulator >= 1.0 {
+            self.accumulator -= 1.0;
+            self.emit();
+        }
+
+        for index in 0..self.bursts.len() {
+            let burst = self.config.bursts[index];
+
+            while let Some(time) = self.bursts[index].filter(|time| *time <= end) {
+                for _ in 0..burst.count {
+                    self.emit();
+                }
+
+                // zero intervals fire once per update instead of looping forever
+                self.bursts[index] = burst.interval.map(|interval| match interval > 0.0 {
+                    true => time + interval,
+                    false => time + delta.max(f32::EPSILON),
+                });
+            }
+        }
+
+        self.elapsed = end;
+
+        if self.config.duration.is_some_and(|duration| self.elapsed >= duration) {
+            self.emitting = false;
+        }
+    }
+
+    fn emit(&mut self) {
+        if self.particles.len() >= self.config.max_particles {
+            return;
+        }
+
+        let config = &self.config;
+        let random = &mut self.random;
+
+        let offset = match config.shape {
+            EmitterShape::Point => glam::Vec2::ZERO,
+            EmitterShape::Line {
+                length,
+                angle,
+            } => glam::Vec2::from_angle(angle.to_radians()) * (random.next() - 0.5) * length,
+            EmitterShape::Circle {
+                radius,
+                edge,
+            } => {
+                let angle = random.next() * std::f32::consts::TAU;
+                // square root keeps the points spread evenly over the disc
+                let distance = if edge {
+                    radius
+                } else {
+                    random.next().sqrt() * radius
+                };
+
+                glam::Vec2::from_angle(angle) * distance
+            },
+            EmitterShape::Rectangle {
+                width,
+                height,
+            } => glam::Vec2::new((random.next() - 0.5) * width, (random.next() - 0.5) * height),
+        };
+
+        let direction = config.direction + (random.next() - 0.5) * config.spread;
+        let velocity = glam::Vec2::from_angle(direction.to_radians()) * random.range(config.speed);
+
+        let size = random.range(config.size);
+
+        self.particles.push(Particle {
+            position: glam::Vec2::new(self.position.x, self.position.y) + offset,
+            velocity,
+            rotation: random.range(config.rotation).to_radians(),
+            angular_velocity: random.range(config.angular_velocity).to_radians(),
+            size,
+            age: 0.0,
+            lifetime: random.range(config.lifetime).max(f32::EPSILON),
+
+            current_size: size,
+            current_color: config.color,
+        });
+    }
+
+    //
+
+    /// Normalized texture coordinates of the region, the whole texture without one.
+    pub(crate) fn uv(&self) -> Rectangle<f32> {
+        match &self.region {
+            Some(region) => {
+                let source = region.source();
+                let (width, height) = (region.texture().dimensions.0 as f32, region.texture().dimensions.1 as f32);
+
+                Rectangle::new(source.x / width, source.y / height, source.width / width, source.height / height)
+            },
+            None => Rectangle::new(0.0, 0.0, 1.0, 1.0),
+        }
+    }
+
+    /// Corners and color of every live particle, oldest first.
+    pub(crate) fn quads(&self) -> impl Iterator<Item = ([glam::Vec2; 4], Color<f32>)> + '_ {
+        let aspect = match &self.region {
+            Some(region) if region.width() > 0.0 => region.height() / region.width(),
+            _ => 1.0,
+        };
+
+        self.particles.iter().map(move |particle| {
+            let half = glam::Vec2::new(particle.current_size, particle.current_size * aspect) / 2.0;
+            let rotation = glam::Vec2::from_angle(particle.rotation);
+
+            let corners = [(-half.x, -half.y), (-half.x, half.y), (half.x, half.y), (half.x, -half.y)]
+                .map(|(x, y)| particle.position + rotation.rotate(glam::Vec2::new(x, y)));
+
+            (corners, particle.current_color)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bursts(interval: f32, updates: usize) -> usize {
+        let mut emitter = ParticleEmitter::new(EmitterConfig {
+            rate: 0.0,
+            bursts: vec![Burst {
+                time: 0.0,
+                count: 1,
+                interval: Some(interval),
+            }],
+            lifetime: Range::constant(100.0),
+            ..Default::default()
+        });
+
+        for _ in 0..updates {
+            emitter.advance(1.0 / 60.0);
+        }
+
+        emitter.particle_count()
+    }
+
+    #[test]
+    fn burst_intervals() {
+        // every burst due within a frame fires, not just one per frame
+        let count = bursts(0.01, 60);
+        assert!((100..=101).contains(&count), "{count} bursts");
+
+        assert_eq!(bursts(0.4, 60), 3);
+
+        // zero intervals fire about once per update
+        let count = bursts(0.0, 60);
+        assert!((60..=61).contains(&count), "{count} bursts");
+    }
+}
@@ -21,18 +21,17 @@ pub fn create_bind_group(layout: &wgpu::BindGroupLayout, entries: &[BindGroupEnt
     for entry in entries {
         __entries.push(wgpu::BindGroupEntry {
             binding: entry.binding,
-            resource: if entry.size.is_none() {
-                match entry.resource {
+            resource: match entry.size {
+                None => match entry.resource {
                     Resource::Uniform(uniform) => uniform.as_entire_binding(),
-                }
-            } else {
-                match entry.resource {
+                },
+                Some(size) => match entry.resource {
                     Resource::Uniform(uniform) => wgpu::BindingResource::Buffer(wgpu::BufferBinding {
                         buffer: uniform,
                         offset: 0,
-                        size: Some(NonZeroU64::new(entry.size.unwrap()).expect("Bind group size cant be 0")),
+                        size: Some(NonZeroU64::new(size).expect("Bind group size cant be 0")),
                     }),
-                }
+                },
             },
         });
     }
@@ -96,11 +95,7 @@ pub fn create_bind_group_layout(entries: &[BindGroupLayoutEntry]) -> wgpu::BindG
                     crate::gfx::raw::SamplerBindingType::Comparison => wgpu::SamplerBindingType::Comparison,
                 }),
             },
-            count: if entry.count.is_none() {
-                None
-            } else {
-                Some(NonZeroU32::new(entry.count.unwrap()).unwrap())
-            },
+            count: entry.count.map(|count| NonZeroU32::new(count).unwrap()),
         });
     }
 
@@ -122,9 +122,41 @@ pub struct ContextConfig {
     pub width: u32,
     pub height: u32,
     pub vsync: bool,
+    pub virtual_resolution: Option<VirtualResolutionConfig>,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+/// How a virtual resolution is mapped onto the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScalingPolicy {
+    /// Covers the whole window, ignoring the aspect ratio.
+    Stretch,
+    /// Largest size keeping the aspect ratio, with bars along the sides that are left over.
+    #[default]
+    Fit,
+    /// Smallest size keeping the aspect ratio that covers the window, cropping what does not fit.
+    Fill,
+    /// Largest whole multiple that fits, with bars around it. Windows smaller than the resolution crop it at 1x.
+    PixelPerfect,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct VirtualResolutionConfig {
+    pub width: u32,
+    pub height: u32,
+    pub policy: ScalingPolicy,
+}
+
+impl Default for VirtualResolutionConfig {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            policy: ScalingPolicy::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum AddressMode {
     #[default]
     ClampToEdge = 0,
@@ -133,13 +165,14 @@ pub enum AddressMode {
     ClampToBorder = 3,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum FilterMode {
     #[default]
     Nearest = 0,
     Linear = 1,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CompareFunction {
     Never = 1,
     Less = 2,
@@ -151,6 +184,7 @@ pub enum CompareFunction {
     Always = 8,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SamplerBorderColor {
     TransparentBlack,
     OpaqueBlack,
@@ -158,6 +192,40 @@ pub enum SamplerBorderColor {
     Zero,
 }
 
+/// Pixel format of a texture, the bytes it is created and written with are laid out to match.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextureFormat {
+    /// 4 bytes per pixel, decoded from sRGB when sampled.
+    #[default]
+    Rgba8UnormSrgb,
+    /// 4 bytes per pixel, sampled as they are, for data like normal maps and lookup tables.
+    Rgba8Unorm,
+    /// 1 byte per pixel, for masks and glyph coverage. Batches draw it as `(1, 1, 1, r)`, white with the byte as alpha.
+    R8Unorm,
+    /// 4 little endian 16 bit floats per pixel, for HDR data outside of 0 to 1.
+    Rgba16Float,
+}
+
+impl TextureFormat {
+    pub fn bytes_per_pixel(&self) -> u32 {
+        match self {
+            TextureFormat::Rgba8UnormSrgb | TextureFormat::Rgba8Unorm => 4,
+            TextureFormat::R8Unorm => 1,
+            TextureFormat::Rgba16Float => 8,
+        }
+    }
+
+    pub(crate) fn wgpu(&self) -> wgpu::TextureFormat {
+        match self {
+            TextureFormat::Rgba8UnormSrgb => wgpu::TextureFormat::Rgba8UnormSrgb,
+            TextureFormat::Rgba8Unorm => wgpu::TextureFormat::Rgba8Unorm,
+            TextureFormat::R8Unorm => wgpu::TextureFormat::R8Unorm,
+            TextureFormat::Rgba16Float => wgpu::TextureFormat::Rgba16Float,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct TextureConfig {
     pub address_mode_u: AddressMode,
     pub address_mode_v: AddressMode,
@@ -170,6 +238,11 @@ pub struct TextureConfig {
     pub compare: Option<CompareFunction>,
     pub anisotropy_clamp: u16,
     pub border_color: Option<SamplerBorderColor>,
+    /// Format `Texture::new` creates the texture in, render targets use the one of their `RenderTargetConfig`.
+    pub format: TextureFormat,
+    /// Has `Texture::new` generate every mip level down to 1x1, for `mipmap_filter` and the `lod_*_clamp`s to pick
+    /// from when the texture is drawn smaller than it is.
+    pub mipmaps: bool,
 }
 
 impl Default for TextureConfig {
@@ -186,10 +259,23 @@ impl Default for TextureConfig {
             compare: None,
             anisotropy_clamp: 1,
             border_color: None,
+            format: TextureFormat::default(),
+            mipmaps: false,
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RenderTargetConfig {
+    pub depth: bool,
+    /// `None` uses the format of the frame, which every pipeline draws into unless it names another one.
+    pub format: Option<TextureFormat>,
+    /// `None` multisamples like the frame, see `raw::sample_count`. Targets only drawn into by passes without
+    /// multisampling, like post processing, save the multisampled texture with `Some(1)`.
+    pub sample_count: Option<u32>,
+    pub texture_config: TextureConfig,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct Color {
     pub r: f64,
@@ -215,10 +301,51 @@ pub enum LoadOp {
     Load,
 }
 
+/// How fragments are combined with what is already in the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlendMode {
+    /// Straight alpha, `src * a + dst * (1 - a)`.
+    #[default]
+    Alpha,
+    /// Colors already multiplied by alpha, `src + dst * (1 - a)`.
+    Premultiplied,
+    /// `src * a + dst`.
+    Additive,
+    /// Colors already multiplied by alpha, `src * dst + dst * (1 - a)`, transparent parts leave the target as it is.
+    /// Batches multiply their colors by alpha for this mode themselves.
+    Multiply,
+    /// Replaces the target, alpha included.
+    Opaque,
+}
+
+/// How a pipeline uses the stencil buffer, compared against the reference of `RenderPass::set_stencil_reference`.
+///
+/// Pipelines using the stencil buffer need a render pass with it attached, see `Frame::create_stencil_render_pass`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum StencilMode {
+    #[default]
+    Disabled,
+    /// Draws where the stencil equals the reference.
+    Test,
+    /// Increments the stencil where it equals the reference, without drawing any color.
+    Increment,
+    /// Decrements the stencil where it equals the reference, without drawing any color.
+    Decrement,
+}
+
 #[derive(Debug, Default)]
 pub struct PipelineConfig<'a> {
     pub shader_source: &'a str,
     pub vertex_buffer_layouts: &'a [wgpu::VertexBufferLayout<'a>],
-    pub bind_group_layouts: Vec<(Order, wgpu::BindGroupLayout)>,
+    /// Borrowed, so bind groups can be created with the same layouts the pipeline was.
+    pub bind_group_layouts: Vec<(Order, &'a wgpu::BindGroupLayout)>,
     pub depth_stencil_write_enabled: bool,
+    pub blend_mode: BlendMode,
+    pub stencil: StencilMode,
+    /// `None` draws into the format of the frame and of render targets without a format of their own.
+    pub format: Option<wgpu::TextureFormat>,
+    /// `None` matches the frame and render targets, see `raw::sample_count`. Passes drawing straight into single
+    /// sampled textures, like post processing, need `Some(1)`.
+    pub sample_count: Option<u32>,
 }
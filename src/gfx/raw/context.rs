@@ -1,20 +1,32 @@
+use std::path::PathBuf;
+
 #[allow(deprecated)]
 use raw_window_handle::{
     HasRawDisplayHandle,
     HasRawWindowHandle,
 };
 
-use crate::gfx::raw::{
-    ContextConfig,
-    Depth,
-    Frame,
+use crate::{
+    gfx::raw::{
+        ContextConfig,
+        Depth,
+        Frame,
+        Mipmaps,
+        Multisample,
+        PendingScreenshot,
+        PostProcess,
+        Screenshot,
+        TextureArray,
+        Viewport,
+    },
+    Size,
 };
 
 // DO NOT TOUCH MY LOVELY SINGLETON!
 pub(crate) static mut CONTEXT: Option<Context> = None;
 
 #[allow(dead_code)]
-pub fn init<T>(handle: &T, size: (u32, u32))
+pub fn init<T>(handle: &T, size: (u32, u32), sample_count: u32, texture_arrays: bool)
 where T: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandle {
     #[allow(deprecated)]
     unsafe {
@@ -22,10 +34,30 @@ where T: raw_window_handle::HasWindowHandle + raw_window_handle::HasDisplayHandl
             handle.raw_display_handle().unwrap(),
             handle.raw_window_handle().unwrap(),
             size,
+            sample_count,
+            texture_arrays,
         ));
     }
 }
 
+#[allow(dead_code)]
+pub fn init_headless(size: (u32, u32), force_software: bool, sample_count: u32, texture_arrays: bool) {
+    unsafe {
+        CONTEXT = Some(Context::new_headless(size, force_software, sample_count, texture_arrays));
+    }
+}
+
+/// Drops the context, on the thread that used it, which GL backends require. Screenshots still being read back are
+/// waited for and saved first.
+#[allow(dead_code)]
+pub fn release() {
+    ctx!().save_screenshots(true);
+
+    unsafe {
+        CONTEXT = None;
+    }
+}
+
 #[allow(dead_code)]
 #[inline]
 pub fn reset(config: ContextConfig) {
@@ -65,6 +97,14 @@ pub fn format() -> wgpu::TextureFormat {
     ctx!().config.format
 }
 
+/// Samples per pixel of the frame and render targets, the requested count or the highest one below it the adapter
+/// supports.
+#[allow(dead_code)]
+#[inline]
+pub fn sample_count() -> u32 {
+    ctx!().sample_count
+}
+
 #[allow(dead_code)]
 #[inline]
 pub fn queue() -> &'static wgpu::Queue {
@@ -77,26 +117,84 @@ pub fn frame() -> Option<&'static mut Frame> {
     ctx!().frame.as_mut()
 }
 
+/// Number of frames started so far, changes once per `render`.
+#[allow(dead_code)]
+#[inline]
+pub fn frame_index() -> u64 {
+    ctx!().frames
+}
+
+/// Placement of the virtual resolution in the window, if one is configured.
+#[allow(dead_code)]
+#[inline]
+pub fn viewport() -> Option<Viewport> {
+    ctx!().viewport
+}
+
+/// Effects run over every frame before it is presented.
+#[allow(dead_code)]
+#[inline]
+pub fn post_process() -> &'static mut PostProcess {
+    &mut ctx!().post_process
+}
+
+/// Copy of the frame currently being drawn, after post processing, recorded when the frame ends.
+#[allow(dead_code)]
+#[inline]
+pub fn screenshot() -> Screenshot {
+    let (screenshot, pending) = Screenshot::new();
+    ctx!().screenshots.push(pending);
+
+    screenshot
+}
+
+/// Saves the frame currently being drawn as a PNG at `path`, once it has been read back.
+#[allow(dead_code)]
+#[inline]
+pub fn save_screenshot(path: PathBuf) {
+    let screenshot = screenshot();
+    ctx!().saves.push((screenshot, path));
+}
+
 //
 
+pub(crate) enum Target {
+    Surface(wgpu::Surface<'static>),
+    Offscreen(wgpu::Texture),
+}
+
 pub(crate) struct Context {
-    pub(crate) surface: wgpu::Surface<'static>,
+    pub(crate) target: Target,
     pub(crate) device: wgpu::Device,
     pub(crate) queue: wgpu::Queue,
     pub(crate) config: wgpu::SurfaceConfiguration,
 
+    pub(crate) sample_count: u32,
+    /// Drawn into instead of the frame's texture when multisampling, resolved into it by every pass.
+    pub(crate) multisample: Option<Multisample>,
     pub(crate) depth_texture: Depth,
     pub(crate) frame: Option<Frame>,
+    pub(crate) viewport: Option<Viewport>,
+    pub(crate) post_process: PostProcess,
+    pub(crate) mipmaps: Mipmaps,
+
+    pub(crate) frames: u64,
+    pub(crate) screenshots: Vec<PendingScreenshot>,
+    pub(crate) saves: Vec<(Screenshot, PathBuf)>,
 }
 
 impl Context {
+    pub const OFFSCREEN_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
     #[allow(deprecated)]
     pub fn new(
         display_handle: raw_window_handle::RawDisplayHandle,
         window_handle: raw_window_handle::RawWindowHandle,
         size: (u32, u32),
+        sample_count: u32,
+        texture_arrays: bool,
     ) -> Self {
-        pollster::block_on(Context::_new(display_handle, window_handle, size))
+        pollster::block_on(Context::_new(display_handle, window_handle, size, sample_count, texture_arrays))
     }
 
     #[allow(deprecated)]
@@ -104,15 +202,10 @@ impl Context {
         display_handle: raw_window_handle::RawDisplayHandle,
         window_handle: raw_window_handle::RawWindowHandle,
         size: (u32, u32),
+        sample_count: u32,
+        texture_arrays: bool,
     ) -> Self {
-        let backends = wgpu::Backends::all();
-
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends,
-            flags: wgpu::InstanceFlags::default(),
-            dx12_shader_compiler: wgpu::Dx12Compiler::default(),
-            gles_minor_version: wgpu::Gles3MinorVersion::default(),
-        });
+        let instance = Context::create_instance();
 
         let surface = unsafe {
             match instance.create_surface_unsafe(wgpu::SurfaceTargetUnsafe::RawHandle {
@@ -136,29 +229,7 @@ impl Context {
             .await
             .unwrap();
 
-        let features = wgpu::Features::empty();
-
-        log::trace!("Requested features: {:?}", features);
-
-        let features = Context::request_features(&adapter, features);
-
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    required_features: features,
-                    required_limits: if cfg!(target_arch = "wasm32") {
-                        wgpu::Limits::downlevel_webgl2_defaults()
-                    } else {
-                        adapter.limits()
-                    },
-                    label: None,
-                },
-                None,
-            )
-            .await
-            .unwrap();
-
-        log::trace!("Supported features: {:?}", features);
+        let (device, queue) = Context::request_device(&adapter, texture_arrays).await;
 
         let surface_caps = surface.get_capabilities(&adapter);
 
@@ -169,8 +240,14 @@ impl Context {
             .find(|f| f.is_srgb())
             .unwrap_or(surface_caps.formats[0]);
 
+        let usage = if surface_caps.usages.contains(wgpu::TextureUsages::COPY_SRC) {
+            wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC
+        } else {
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+        };
+
         let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage,
             format: surface_format,
             width: size.0,
             height: size.1,
@@ -184,19 +261,177 @@ impl Context {
 
         log::trace!("Backend: {:?}", adapter.get_info().backend);
 
-        let depth_texture = Depth::create_depth_texture(&device, &config, "depth");
+        let sample_count = Context::supported_sample_count(&adapter, &device, config.format, sample_count);
+        let multisample = Context::create_multisample(&device, &config, sample_count);
+        let depth_texture = Depth::create_depth_texture(&device, &config, sample_count, "depth");
 
         Context {
-            surface,
+            target: Target::Surface(surface),
             device,
             queue,
             config,
 
+            sample_count,
+            multisample,
             depth_texture,
             frame: None,
+            viewport: None,
+            post_process: PostProcess::default(),
+            mipmaps: Mipmaps::default(),
+
+            frames: 0,
+            screenshots: vec![],
+            saves: vec![],
         }
     }
 
+    pub fn new_headless(size: (u32, u32), force_software: bool, sample_count: u32, texture_arrays: bool) -> Self {
+        pollster::block_on(Context::_new_headless(size, force_software, sample_count, texture_arrays))
+    }
+
+    async fn _new_headless(size: (u32, u32), force_software: bool, sample_count: u32, texture_arrays: bool) -> Self {
+        let instance = Context::create_instance();
+
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: force_software,
+            })
+            .await
+            .expect("No suitable adapter found for headless context.");
+
+        let (device, queue) = Context::request_device(&adapter, texture_arrays).await;
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format: Context::OFFSCREEN_FORMAT,
+            width: size.0,
+            height: size.1,
+            present_mode: wgpu::PresentMode::AutoNoVsync,
+            alpha_mode: wgpu::CompositeAlphaMode::Auto,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
+        };
+
+        log::trace!("Backend: {:?} ({})", adapter.get_info().backend, adapter.get_info().name);
+
+        let sample_count = Context::supported_sample_count(&adapter, &device, config.format, sample_count);
+        let offscreen = Context::create_offscreen_texture(&device, &config);
+        let multisample = Context::create_multisample(&device, &config, sample_count);
+        let depth_texture = Depth::create_depth_texture(&device, &config, sample_count, "depth");
+
+        Context {
+            target: Target::Offscreen(offscreen),
+            device,
+            queue,
+            config,
+
+            sample_count,
+            multisample,
+            depth_texture,
+            frame: None,
+            viewport: None,
+            post_process: PostProcess::default(),
+            mipmaps: Mipmaps::default(),
+
+            frames: 0,
+            screenshots: vec![],
+            saves: vec![],
+        }
+    }
+
+    fn create_instance() -> wgpu::Instance {
+        let backends = wgpu::Backends::all();
+
+        wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends,
+            flags: wgpu::InstanceFlags::default(),
+            dx12_shader_compiler: wgpu::Dx12Compiler::default(),
+            gles_minor_version: wgpu::Gles3MinorVersion::default(),
+        })
+    }
+
+    async fn request_device(adapter: &wgpu::Adapter, texture_arrays: bool) -> (wgpu::Device, wgpu::Queue) {
+        // optional, only used when the adapter supports them, without texture array features batches bind textures one by one
+        let features = match texture_arrays {
+            true => TextureArray::FEATURES,
+            false => wgpu::Features::empty(),
+        } | wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES;
+
+        log::trace!("Requested features: {:?}", features);
+
+        let features = Context::request_features(adapter, features);
+
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    required_features: features,
+                    required_limits: if cfg!(target_arch = "wasm32") {
+                        wgpu::Limits::downlevel_webgl2_defaults()
+                    } else {
+                        adapter.limits()
+                    },
+                    label: None,
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        log::trace!("Supported features: {:?}", features);
+
+        (device, queue)
+    }
+
+    fn create_offscreen_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Texture"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        })
+    }
+
+    /// Highest of 8, 4, 2 and 1 samples up to `requested` that the frame's color and depth formats both support.
+    fn supported_sample_count(adapter: &wgpu::Adapter, device: &wgpu::Device, format: wgpu::TextureFormat, requested: u32) -> u32 {
+        // without adapter specific format features only the counts every WebGPU device has are allowed
+        let adapter_specific = device.features().contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES);
+
+        let supported = |count: u32| {
+            if !adapter_specific {
+                return count == 1 || count == 4;
+            }
+
+            [format, Depth::DEPTH_FORMAT]
+                .iter()
+                .all(|format| adapter.get_texture_format_features(*format).flags.sample_count_supported(count))
+        };
+
+        let sample_count = [8, 4, 2, 1]
+            .into_iter()
+            .find(|count| *count <= requested && supported(*count))
+            .unwrap_or(1);
+
+        if sample_count != requested {
+            log::warn!("{} samples are not supported, using {}", requested, sample_count);
+        }
+
+        sample_count
+    }
+
+    fn create_multisample(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, sample_count: u32) -> Option<Multisample> {
+        (sample_count > 1).then(|| Multisample::new(device, (config.width, config.height), config.format, sample_count, "multisample"))
+    }
+
     fn request_features(adapter: &wgpu::Adapter, features: wgpu::Features) -> wgpu::Features {
         let mut out = wgpu::Features::empty();
 
@@ -220,25 +455,67 @@ impl Context {
                 wgpu::PresentMode::AutoNoVsync
             };
 
-            self.surface.configure(&self.device, &self.config);
+            match &mut self.target {
+                Target::Surface(surface) => surface.configure(&self.device, &self.config),
+                Target::Offscreen(texture) => *texture = Context::create_offscreen_texture(&self.device, &self.config),
+            }
 
-            self.depth_texture = Depth::create_depth_texture(&self.device, &self.config, "depth");
+            self.multisample = Context::create_multisample(&self.device, &self.config, self.sample_count);
+            self.depth_texture = Depth::create_depth_texture(&self.device, &self.config, self.sample_count, "depth");
+
+            self.viewport = config.virtual_resolution.map(|virtual_resolution| {
+                Viewport::new(
+                    Size {
+                        width: config.width,
+                        height: config.height,
+                    },
+                    virtual_resolution,
+                )
+            });
         }
     }
 
     pub fn render<T>(&mut self, mut func: T)
     where T: FnMut() {
-        match self.surface.get_current_texture() {
-            Ok(output) => {
-                let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        match self.acquire() {
+            Ok((output, view)) => {
+                let mut frame = Frame::new(output, view, self.create_encoder());
 
-                self.frame = Some(Frame::new(output, view, self.create_encoder()));
+                if self.post_process.is_active() {
+                    frame.scene = Some(self.post_process.scene((self.config.width, self.config.height)));
+                }
+
+                self.frame = Some(frame);
+                self.frames += 1;
 
                 func();
 
-                let frame = self.frame.take().unwrap();
-                self.queue.submit(std::iter::once(frame.encoder.finish()));
-                frame.output.present();
+                let mut frame = self.frame.take().unwrap();
+
+                if let Some(scene) = frame.scene.take() {
+                    self.post_process.run(
+                        &mut frame.encoder,
+                        &scene.texture,
+                        &frame.view,
+                        (self.config.width, self.config.height),
+                    );
+                }
+
+                for pending in self.screenshots.drain(..) {
+                    *pending.borrow_mut() = Some(frame.read_pixels());
+                }
+
+                self.submit(frame.encoder.finish());
+
+                for submitted in frame.readbacks {
+                    submitted.set(true);
+                }
+
+                if let Some(output) = frame.output {
+                    output.present();
+                }
+
+                self.save_screenshots(false);
             },
             Err(err) => {
                 log::error!("{:?}", err);
@@ -248,6 +525,44 @@ impl Context {
         crate::gfx::raw::reset_dynamic_uniforms();
     }
 
+    fn acquire(&self) -> Result<(Option<wgpu::SurfaceTexture>, wgpu::TextureView), wgpu::SurfaceError> {
+        match &self.target {
+            Target::Surface(surface) => {
+                let output = surface.get_current_texture()?;
+                let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+                Ok((Some(output), view))
+            },
+            Target::Offscreen(texture) => Ok((None, texture.create_view(&wgpu::TextureViewDescriptor::default()))),
+        }
+    }
+
+    /// Saves the screenshots whose pixels have been read back, or all of them after waiting if `wait` is set.
+    fn save_screenshots(&mut self, wait: bool) {
+        for (mut screenshot, path) in std::mem::take(&mut self.saves) {
+            let pixels = if wait {
+                screenshot.wait()
+            } else {
+                match screenshot.try_read() {
+                    Some(pixels) => pixels,
+                    None => {
+                        self.saves.push((screenshot, path));
+                        continue;
+                    },
+                }
+            };
+
+            if let Err(err) = pixels.and_then(|pixels| pixels.save(&path)) {
+                log::error!("Failed to save screenshot {:?}: {:?}", path, err);
+            }
+        }
+    }
+
+    #[inline]
+    pub fn submit(&mut self, command_buffer: wgpu::CommandBuffer) {
+        self.queue.submit(std::iter::once(command_buffer));
+    }
+
     #[inline]
     pub fn create_encoder(&self) -> wgpu::CommandEncoder {
         self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
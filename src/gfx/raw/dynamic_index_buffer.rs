@@ -1,55 +1,83 @@
-use wgpu::util::DeviceExt;
-
 use crate::gfx::raw::IndexFormat;
 
+/// Index buffer that can be rewritten, growing geometrically and never shrinking.
 #[derive(Debug)]
 pub struct DynamicIndexBuffer {
     pub(crate) buffer: wgpu::Buffer,
     pub(crate) format: wgpu::IndexFormat,
 
-    size: usize,
+    capacity: u64,
+    reallocations: u32,
 
     len: u32,
 }
 
 impl DynamicIndexBuffer {
     pub fn new(data: &[u8], format: IndexFormat, len: usize) -> Self {
-        let buffer = ctx!().device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Dynamic Index Buffer"),
-            contents: data,
-            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
-        });
+        let capacity = (data.len() as u64)
+            .next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT)
+            .max(wgpu::COPY_BUFFER_ALIGNMENT);
 
-        Self {
-            buffer,
+        let mut buffer = Self {
+            buffer: Self::create_buffer(capacity),
             format: match format {
                 IndexFormat::Uint16 => wgpu::IndexFormat::Uint16,
                 IndexFormat::Uint32 => wgpu::IndexFormat::Uint32,
             },
 
-            size: data.len(),
+            capacity,
+            reallocations: 0,
 
-            len: len as u32,
-        }
+            len: 0,
+        };
+
+        buffer.set(data, len);
+
+        buffer
     }
 
+    fn create_buffer(capacity: u64) -> wgpu::Buffer {
+        ctx!().device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Dynamic Index Buffer"),
+            size: capacity,
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Writes `data` to the start of the buffer, replacing it with one at least twice as large if it does not fit.
     pub fn set(&mut self, data: &[u8], len: usize) {
-        if data.len() != self.size {
-            self.buffer = ctx!().device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("Dynamic Index Buffer"),
-                contents: data,
-                usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
-            });
-
-            self.len = len as u32;
-            self.size = data.len();
+        let size = (data.len() as u64).next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT);
+
+        if size > self.capacity {
+            self.capacity = size.max(self.capacity * 2);
+            self.buffer = Self::create_buffer(self.capacity);
+            self.reallocations += 1;
+        }
+
+        // writes must be a multiple of 4 bytes, odd u16 counts get padded
+        if data.len() as u64 == size {
+            ctx!().queue.write_buffer(&self.buffer, 0, data);
+        } else {
+            let mut padded = data.to_vec();
+            padded.resize(size as usize, 0);
+            ctx!().queue.write_buffer(&self.buffer, 0, &padded);
         }
 
-        ctx!().queue.write_buffer(&self.buffer, 0, data);
+        self.len = len as u32;
     }
 
     #[allow(clippy::all)]
     pub fn len(&self) -> u32 {
         self.len
     }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// How often the buffer had to be replaced by a larger one.
+    pub fn reallocations(&self) -> u32 {
+        self.reallocations
+    }
 }
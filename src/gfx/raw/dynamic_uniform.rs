@@ -18,6 +18,7 @@ use crate::gfx::raw::{
 #[allow(clippy::all)]
 static mut DYNAMIC_UNIFORMS_RESET_QUEUE: Lazy<HashMap<wgpu::Id<wgpu::Buffer>, *mut DynamicUniform>> = Lazy::new(|| HashMap::new());
 
+#[allow(static_mut_refs)]
 pub(crate) fn reset_dynamic_uniforms() {
     unsafe {
         if DYNAMIC_UNIFORMS_RESET_QUEUE.is_empty() {
@@ -34,6 +35,7 @@ pub(crate) fn reset_dynamic_uniforms() {
 
 //
 
+#[derive(Debug)]
 pub struct DynamicUniform {
     pub(crate) buffer: wgpu::Buffer,
     pub(crate) bind_groups: Vec<Bind>,
@@ -47,6 +49,7 @@ pub struct DynamicUniform {
 }
 
 impl Drop for DynamicUniform {
+    #[allow(static_mut_refs)]
     fn drop(&mut self) {
         unsafe {
             if DYNAMIC_UNIFORMS_RESET_QUEUE.contains_key(&self.buffer.global_id()) {
@@ -119,7 +122,7 @@ impl DynamicUniform {
 
         encoder.copy_buffer_to_buffer(&self.buffer, 0, &buffer, 0, buffer.size());
 
-        ctx!().queue.submit(Some(encoder.finish()));
+        ctx!().submit(encoder.finish());
 
         self.buffer = buffer;
 
@@ -146,7 +149,7 @@ impl DynamicUniform {
 
         encoder.copy_buffer_to_buffer(&self.buffer, 0, &buffer, 0, self.buffer.size());
 
-        ctx!().queue.submit(Some(encoder.finish()));
+        ctx!().submit(encoder.finish());
 
         self.buffer = buffer;
 
@@ -161,8 +164,14 @@ impl DynamicUniform {
     }
 
     #[inline]
+    #[allow(static_mut_refs)]
     pub fn set(&mut self, data: &[u8]) {
         if self.offset + 1 > self.length {
+            // the queue is keyed by buffer, a stale key would reset this uniform twice
+            unsafe {
+                DYNAMIC_UNIFORMS_RESET_QUEUE.remove(&self.buffer.global_id());
+            }
+
             self.length += 1;
             self.expand();
         }
@@ -202,7 +211,7 @@ impl DynamicUniform {
         let step = ctx!().device.limits().min_uniform_buffer_offset_alignment;
 
         let divide_and_ceil = value as u32 / step
-            + if value as u32 % step == 0 {
+            + if (value as u32).is_multiple_of(step) {
                 0
             } else {
                 1
@@ -1,38 +1,94 @@
-use wgpu::util::DeviceExt;
-
+/// Vertex buffer that is rewritten every frame, growing geometrically and never shrinking.
 #[derive(Debug)]
 pub struct DynamicVertexBuffer {
     pub(crate) buffer: wgpu::Buffer,
 
-    size: usize,
+    capacity: u64,
+    cursor: u64,
+    frame: u64,
+    reallocations: u32,
 }
 
 impl DynamicVertexBuffer {
     pub fn new(data: &[u8]) -> Self {
-        let buffer = ctx!().device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Dynamic Vertex Buffer"),
-            contents: data,
-            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-        });
+        let mut buffer = Self::with_capacity(data.len() as u64);
+        buffer.set(data);
+
+        buffer
+    }
+
+    pub fn with_capacity(capacity: u64) -> Self {
+        let capacity = capacity.max(wgpu::COPY_BUFFER_ALIGNMENT);
 
         Self {
-            buffer,
+            buffer: Self::create_buffer(capacity),
 
-            size: data.len(),
+            capacity,
+            cursor: 0,
+            frame: crate::gfx::raw::frame_index(),
+            reallocations: 0,
         }
     }
 
-    pub fn set(&mut self, data: &[u8]) {
-        if data.len() != self.size {
-            self.buffer = ctx!().device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                label: Some("Dynamic Vertex Buffer"),
-                contents: data,
-                usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
-            });
-
-            self.size = data.len();
+    fn create_buffer(capacity: u64) -> wgpu::Buffer {
+        ctx!().device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Dynamic Vertex Buffer"),
+            size: capacity,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Replaces the buffer if `size` does not fit, the old contents are not carried over.
+    fn reserve(&mut self, size: u64) -> bool {
+        if size <= self.capacity {
+            return false;
         }
 
+        self.capacity = size.max(self.capacity * 2).next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT);
+        self.buffer = Self::create_buffer(self.capacity);
+        self.reallocations += 1;
+
+        true
+    }
+
+    /// Writes `data` to the start of the buffer.
+    pub fn set(&mut self, data: &[u8]) {
+        self.reserve(data.len() as u64);
+
         ctx!().queue.write_buffer(&self.buffer, 0, data);
     }
+
+    /// Writes `data` after everything pushed earlier in the same frame and returns its byte offset.
+    ///
+    /// Queue writes land before the frame's commands run, so regions used by earlier passes of the frame are
+    /// never overwritten; a buffer that runs out of room is replaced and earlier passes keep the old one.
+    pub fn push(&mut self, data: &[u8]) -> u64 {
+        let frame = crate::gfx::raw::frame_index();
+
+        if self.frame != frame {
+            self.frame = frame;
+            self.cursor = 0;
+        }
+
+        let mut offset = self.cursor.next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT);
+
+        if self.reserve(offset + data.len() as u64) {
+            offset = 0;
+        }
+
+        ctx!().queue.write_buffer(&self.buffer, offset, data);
+        self.cursor = offset + data.len() as u64;
+
+        offset
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// How often the buffer had to be replaced by a larger one.
+    pub fn reallocations(&self) -> u32 {
+        self.reallocations
+    }
 }
@@ -1,60 +1,210 @@
+use std::{
+    cell::Cell,
+    rc::Rc,
+};
+
 use crate::gfx::raw::{
+    context::Target,
     LoadOp,
+    Readback,
     RenderPass,
+    RenderTarget,
 };
 
+fn output_texture(output: &Option<wgpu::SurfaceTexture>) -> &wgpu::Texture {
+    match output {
+        Some(output) => &output.texture,
+        None => match &ctx!().target {
+            Target::Offscreen(texture) => texture,
+            Target::Surface(_) => unreachable!(),
+        },
+    }
+}
+
+//
+
 pub struct Frame {
-    pub output: wgpu::SurfaceTexture,
+    pub output: Option<wgpu::SurfaceTexture>,
     pub view: wgpu::TextureView,
     pub encoder: wgpu::CommandEncoder,
+    /// Target drawn into instead of `view` while post processing is active.
+    pub(crate) scene: Option<RenderTarget>,
+    /// Readbacks recorded into `encoder`, marked as submitted once the frame is.
+    pub(crate) readbacks: Vec<Rc<Cell<bool>>>,
 }
 
 impl Frame {
-    pub(crate) fn new(output: wgpu::SurfaceTexture, view: wgpu::TextureView, encoder: wgpu::CommandEncoder) -> Self {
+    pub(crate) fn new(output: Option<wgpu::SurfaceTexture>, view: wgpu::TextureView, encoder: wgpu::CommandEncoder) -> Self {
         Self {
             output,
             view,
             encoder,
+            scene: None,
+            readbacks: vec![],
         }
     }
 
-    pub fn create_render_pass(&mut self, depth_write_enabled: bool) -> RenderPass {
+    /// Texture drawn into, the post processing scene target while effects are active.
+    pub fn texture(&self) -> &wgpu::Texture {
+        match &self.scene {
+            Some(scene) => &scene.texture.texture,
+            None => output_texture(&self.output),
+        }
+    }
+
+    /// Records a copy of the frame as it is at this point, readable once the frame is submitted.
+    pub fn read_pixels(&mut self) -> anyhow::Result<Readback> {
+        let texture = match &self.scene {
+            Some(scene) => &scene.texture.texture,
+            None => output_texture(&self.output),
+        };
+
+        let readback = Readback::new(&mut self.encoder, texture)?;
+        self.readbacks.push(readback.submitted());
+
+        Ok(readback)
+    }
+
+    /// Records a copy of `texture` into the frame, ordered after everything drawn so far.
+    pub(crate) fn read_texture(&mut self, texture: &wgpu::Texture) -> anyhow::Result<Readback> {
+        let readback = Readback::new(&mut self.encoder, texture)?;
+        self.readbacks.push(readback.submitted());
+
+        Ok(readback)
+    }
+
+    pub fn create_render_pass(&mut self, depth_write_enabled: bool) -> RenderPass<'_> {
         self.create_render_pass_with_load_op(depth_write_enabled, LoadOp::Load)
     }
 
-    pub fn create_render_pass_with_load_op(&mut self, depth_write_enabled: bool, load_op: LoadOp) -> RenderPass {
-        RenderPass::new(self.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("Render Pass"),
+    pub fn create_render_pass_with_load_op(&mut self, depth_write_enabled: bool, load_op: LoadOp) -> RenderPass<'_> {
+        let depth = if depth_write_enabled {
+            Some(&ctx!().depth_texture.view)
+        } else {
+            None
+        };
+
+        let attachment = attachment(&self.scene, &self.view);
+        let mut render_pass = begin_render_pass(&mut self.encoder, attachment, depth, load_op);
+
+        // clears still cover the whole frame, leaving the bars around the viewport in the clear color
+        if let Some(viewport) = ctx!().viewport {
+            let area = viewport.visible_area();
+
+            render_pass
+                .render_pass
+                .set_viewport(area.x, area.y, area.width, area.height, 0.0, 1.0);
+        }
+
+        render_pass
+    }
+
+    /// Render pass drawing into `target` instead of the frame, depth is only attached if the target has one.
+    pub fn create_target_render_pass<'a>(
+        &'a mut self,
+        target: &'a RenderTarget,
+        depth_write_enabled: bool,
+        load_op: LoadOp,
+    ) -> RenderPass<'a> {
+        let depth = match &target.depth {
+            Some(depth) if depth_write_enabled => Some(&depth.view),
+            _ => None,
+        };
+
+        begin_render_pass(&mut self.encoder, target.attachment(), depth, load_op)
+    }
+
+    /// Render pass with the stencil buffer attached, drawing into `target` or the frame. Colors and depth are kept,
+    /// the stencil is cleared to 0 first if `clear_stencil` is set. `None` if the target has no depth buffer.
+    pub fn create_stencil_render_pass<'a>(&'a mut self, target: Option<&'a RenderTarget>, clear_stencil: bool) -> Option<RenderPass<'a>> {
+        let ((view, resolve_target), depth) = match target {
+            Some(target) => (target.attachment(), &target.depth.as_ref()?.view),
+            None => (attachment(&self.scene, &self.view), &ctx!().depth_texture.view),
+        };
+
+        let mut render_pass = RenderPass::new(self.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Stencil Render Pass"),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &self.view,
-                resolve_target: None,
+                view,
+                resolve_target,
                 ops: wgpu::Operations {
-                    load: match load_op {
-                        LoadOp::Clear(color) => wgpu::LoadOp::Clear(wgpu::Color {
-                            r: color.r,
-                            g: color.g,
-                            b: color.b,
-                            a: color.a,
-                        }),
-                        LoadOp::Load => wgpu::LoadOp::Load,
-                    },
+                    load: wgpu::LoadOp::Load,
                     store: wgpu::StoreOp::Store,
                 },
             })],
-            depth_stencil_attachment: if depth_write_enabled {
-                Some(wgpu::RenderPassDepthStencilAttachment {
-                    view: &ctx!().depth_texture.view,
-                    depth_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
-                        store: wgpu::StoreOp::Store,
-                    }),
-                    stencil_ops: None,
-                })
-            } else {
-                None
-            },
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: depth,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: Some(wgpu::Operations {
+                    load: if clear_stencil {
+                        wgpu::LoadOp::Clear(0)
+                    } else {
+                        wgpu::LoadOp::Load
+                    },
+                    store: wgpu::StoreOp::Store,
+                }),
+            }),
             occlusion_query_set: None,
             timestamp_writes: None,
-        }))
+        }));
+
+        if let (None, Some(viewport)) = (target, ctx!().viewport) {
+            let area = viewport.visible_area();
+
+            render_pass
+                .render_pass
+                .set_viewport(area.x, area.y, area.width, area.height, 0.0, 1.0);
+        }
+
+        Some(render_pass)
+    }
+}
+
+/// View frame passes draw into and the view it is resolved into, if multisampling.
+fn attachment<'a>(scene: &'a Option<RenderTarget>, view: &'a wgpu::TextureView) -> (&'a wgpu::TextureView, Option<&'a wgpu::TextureView>) {
+    match (scene, &ctx!().multisample) {
+        (Some(scene), _) => scene.attachment(),
+        (None, Some(multisample)) => (&multisample.view, Some(view)),
+        (None, None) => (view, None),
     }
 }
+
+fn begin_render_pass<'a>(
+    encoder: &'a mut wgpu::CommandEncoder,
+    (view, resolve_target): (&'a wgpu::TextureView, Option<&'a wgpu::TextureView>),
+    depth: Option<&'a wgpu::TextureView>,
+    load_op: LoadOp,
+) -> RenderPass<'a> {
+    RenderPass::new(encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("Render Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target,
+            ops: wgpu::Operations {
+                load: match load_op {
+                    LoadOp::Clear(color) => wgpu::LoadOp::Clear(wgpu::Color {
+                        r: color.r,
+                        g: color.g,
+                        b: color.b,
+                        a: color.a,
+                    }),
+                    LoadOp::Load => wgpu::LoadOp::Load,
+                },
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: depth.map(|view| wgpu::RenderPassDepthStencilAttachment {
+            view,
+            depth_ops: Some(wgpu::Operations {
+                load: wgpu::LoadOp::Clear(1.0),
+                store: wgpu::StoreOp::Store,
+            }),
+            stencil_ops: None,
+        }),
+        occlusion_query_set: None,
+        timestamp_writes: None,
+    }))
+}
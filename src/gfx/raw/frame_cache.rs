@@ -0,0 +1,42 @@
+use std::{
+    hash::Hash,
+    rc::Rc,
+};
+
+use hashbrown::HashMap;
+
+use crate::gfx::raw::frame_index;
+
+/// GPU objects by key, kept across frames. Entries not used during the last frame anything was looked up in are
+/// dropped, so they do not keep the resources they reference alive.
+#[derive(Debug)]
+pub struct FrameCache<K, V> {
+    entries: HashMap<K, (Rc<V>, u64)>,
+    frame: u64,
+}
+
+impl<K, V> Default for FrameCache<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            frame: 0,
+        }
+    }
+}
+
+impl<K: Eq + Hash, V> FrameCache<K, V> {
+    pub fn get_or_insert_with(&mut self, key: K, create: impl FnOnce() -> V) -> Rc<V> {
+        let frame = frame_index();
+
+        if frame != self.frame {
+            let last = self.frame;
+            self.entries.retain(|_, (_, used)| *used == last);
+            self.frame = frame;
+        }
+
+        let (value, used) = self.entries.entry(key).or_insert_with(|| (Rc::new(create()), frame));
+        *used = frame;
+
+        Rc::clone(value)
+    }
+}
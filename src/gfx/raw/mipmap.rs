@@ -0,0 +1,105 @@
+use crate::gfx::raw::{
+    BlendMode,
+    Pipeline,
+    PipelineConfig,
+    StencilMode,
+    Texture,
+};
+
+/// Blit pipelines filling each mip level of a texture from the level above it, created once per texture format.
+#[derive(Debug, Default)]
+pub(crate) struct Mipmaps {
+    pipelines: Vec<(wgpu::TextureFormat, Pipeline)>,
+    sampler: Option<wgpu::Sampler>,
+}
+
+impl Mipmaps {
+    pub(crate) fn generate(&mut self, texture: &wgpu::Texture) {
+        if texture.mip_level_count() < 2 {
+            return;
+        }
+
+        let format = texture.format();
+
+        if !self.pipelines.iter().any(|(pipeline_format, _)| *pipeline_format == format) {
+            let pipeline = Pipeline::new(PipelineConfig {
+                shader_source: include_str_from_root!("res/shaders/mipmap.wgsl"),
+                vertex_buffer_layouts: &[],
+                bind_group_layouts: vec![(0, &Texture::bind_group_layout())],
+                depth_stencil_write_enabled: false,
+                blend_mode: BlendMode::Opaque,
+                stencil: StencilMode::Disabled,
+                sample_count: Some(1),
+                format: Some(format),
+            });
+
+            self.pipelines.push((format, pipeline));
+        }
+
+        let Some((_, pipeline)) = self.pipelines.iter().find(|(pipeline_format, _)| *pipeline_format == format) else {
+            return;
+        };
+
+        let sampler = self.sampler.get_or_insert_with(|| {
+            ctx!().device.create_sampler(&wgpu::SamplerDescriptor {
+                label: Some("Mipmap Sampler"),
+                mag_filter: wgpu::FilterMode::Linear,
+                min_filter: wgpu::FilterMode::Linear,
+                ..Default::default()
+            })
+        });
+
+        let layout = Texture::bind_group_layout();
+        let level_view = |level| {
+            texture.create_view(&wgpu::TextureViewDescriptor {
+                base_mip_level: level,
+                mip_level_count: Some(1),
+                ..Default::default()
+            })
+        };
+
+        let mut encoder = ctx!().create_encoder();
+
+        for level in 1..texture.mip_level_count() {
+            let source = level_view(level - 1);
+            let destination = level_view(level);
+
+            let bind_group = ctx!().device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&source),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(sampler),
+                    },
+                ],
+                label: Some("Mipmap Bind Group"),
+            });
+
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Mipmap Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &destination,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            render_pass.set_pipeline(&pipeline.pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+        }
+
+        // queued writes to the top level land before this submission
+        ctx!().submit(encoder.finish());
+    }
+}
@@ -10,12 +10,21 @@ mod dynamic_index_buffer;
 mod dynamic_uniform;
 mod dynamic_vertex_buffer;
 mod frame;
+mod frame_cache;
 mod index_buffer;
+mod mipmap;
+mod multisample;
 mod pipeline;
+mod post_process;
+mod readback;
 mod render_pass;
+mod render_target;
+mod sampler;
 mod texture;
+mod texture_array;
 mod uniform;
 mod vertex_buffer;
+mod viewport;
 
 pub use config::*;
 pub use context::{
@@ -23,10 +32,18 @@ pub use context::{
     features,
     format,
     frame,
+    frame_index,
     init,
+    init_headless,
+    post_process,
     queue,
+    release,
     render,
     reset,
+    sample_count,
+    save_screenshot,
+    screenshot,
+    viewport,
 };
 pub use depth::Depth;
 pub use dynamic_index_buffer::DynamicIndexBuffer;
@@ -34,12 +51,36 @@ use dynamic_uniform::reset_dynamic_uniforms;
 pub use dynamic_uniform::DynamicUniform;
 pub use dynamic_vertex_buffer::DynamicVertexBuffer;
 pub use frame::Frame;
+pub use frame_cache::FrameCache;
 pub use index_buffer::IndexBuffer;
+pub(crate) use mipmap::Mipmaps;
+pub(crate) use multisample::Multisample;
 pub use pipeline::Pipeline;
+pub use post_process::{
+    PostEffect,
+    PostProcess,
+};
+pub(crate) use readback::PendingScreenshot;
+pub use readback::{
+    read_texture,
+    Pixels,
+    Readback,
+    Screenshot,
+};
 pub use render_pass::RenderPass;
-pub use texture::Texture;
+pub use render_target::RenderTarget;
+pub use sampler::Sampler;
+pub use texture::{
+    Texture,
+    TextureBinding,
+};
+pub use texture_array::{
+    TextureArray,
+    TextureArrayCache,
+};
 pub use uniform::*;
 pub use vertex_buffer::VertexBuffer;
+pub use viewport::Viewport;
 
 // ____________________________
 // < What are you doing here? >
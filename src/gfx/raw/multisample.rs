@@ -0,0 +1,32 @@
+/// Multisampled color attachment, drawn into by every pass and resolved into the single sampled texture it belongs to.
+#[derive(Debug)]
+pub struct Multisample {
+    pub(crate) texture: wgpu::Texture,
+    pub(crate) view: wgpu::TextureView,
+}
+
+impl Multisample {
+    pub fn new(device: &wgpu::Device, dimensions: (u32, u32), format: wgpu::TextureFormat, sample_count: u32, label: &str) -> Self {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width: dimensions.0,
+                height: dimensions.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        Self {
+            texture,
+            view,
+        }
+    }
+}
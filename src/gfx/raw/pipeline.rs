@@ -1,6 +1,8 @@
 use crate::gfx::raw::{
+    BlendMode,
     Depth,
     PipelineConfig,
+    StencilMode,
 };
 
 #[derive(Debug)]
@@ -17,7 +19,7 @@ impl Pipeline {
 
         let mut layouts = config.bind_group_layouts;
         layouts.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
-        let layouts_ref = layouts.iter().map(|ol| &ol.1).collect::<Vec<_>>();
+        let layouts_ref = layouts.iter().map(|ol| ol.1).collect::<Vec<_>>();
 
         let render_pipeline_layout = ctx!().device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Render Pipeline Layout"),
@@ -37,9 +39,12 @@ impl Pipeline {
                 module: &shader,
                 entry_point: "fs_main",
                 targets: &[Some(wgpu::ColorTargetState {
-                    format: ctx!().config.format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
+                    format: config.format.unwrap_or(ctx!().config.format),
+                    blend: Some(Self::blend_state(config.blend_mode)),
+                    write_mask: match config.stencil {
+                        StencilMode::Increment | StencilMode::Decrement => wgpu::ColorWrites::empty(),
+                        StencilMode::Disabled | StencilMode::Test => wgpu::ColorWrites::ALL,
+                    },
                 })],
             }),
             primitive: wgpu::PrimitiveState {
@@ -52,19 +57,23 @@ impl Pipeline {
                 unclipped_depth: false,
                 conservative: false,
             },
-            depth_stencil: if config.depth_stencil_write_enabled {
+            depth_stencil: if config.depth_stencil_write_enabled || config.stencil != StencilMode::Disabled {
                 Some(wgpu::DepthStencilState {
                     format: Depth::DEPTH_FORMAT,
-                    depth_write_enabled: true,
-                    depth_compare: wgpu::CompareFunction::Less,
-                    stencil: wgpu::StencilState::default(),
+                    depth_write_enabled: config.depth_stencil_write_enabled,
+                    depth_compare: if config.depth_stencil_write_enabled {
+                        wgpu::CompareFunction::Less
+                    } else {
+                        wgpu::CompareFunction::Always
+                    },
+                    stencil: Self::stencil_state(config.stencil),
                     bias: wgpu::DepthBiasState::default(),
                 })
             } else {
                 None
             },
             multisample: wgpu::MultisampleState {
-                count: 1,
+                count: config.sample_count.unwrap_or(ctx!().sample_count),
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
@@ -75,4 +84,63 @@ impl Pipeline {
             pipeline,
         }
     }
+
+    fn stencil_state(stencil: StencilMode) -> wgpu::StencilState {
+        let pass_op = match stencil {
+            StencilMode::Disabled => return wgpu::StencilState::default(),
+            StencilMode::Test => wgpu::StencilOperation::Keep,
+            StencilMode::Increment => wgpu::StencilOperation::IncrementClamp,
+            StencilMode::Decrement => wgpu::StencilOperation::DecrementClamp,
+        };
+
+        let face = wgpu::StencilFaceState {
+            compare: wgpu::CompareFunction::Equal,
+            fail_op: wgpu::StencilOperation::Keep,
+            depth_fail_op: wgpu::StencilOperation::Keep,
+            pass_op,
+        };
+
+        wgpu::StencilState {
+            front: face,
+            back: face,
+            read_mask: 0xff,
+            write_mask: if stencil == StencilMode::Test {
+                0
+            } else {
+                0xff
+            },
+        }
+    }
+
+    fn blend_state(blend_mode: BlendMode) -> wgpu::BlendState {
+        match blend_mode {
+            BlendMode::Alpha => wgpu::BlendState::ALPHA_BLENDING,
+            BlendMode::Premultiplied => wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+            BlendMode::Additive => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            },
+            BlendMode::Multiply => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Dst,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::DstAlpha,
+                    dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            },
+            BlendMode::Opaque => wgpu::BlendState::REPLACE,
+        }
+    }
 }
@@ -0,0 +1,562 @@
+use std::rc::Rc;
+
+use crate::gfx::raw::{
+    BlendMode,
+    FilterMode,
+    FrameCache,
+    Pipeline,
+    PipelineConfig,
+    RenderTarget,
+    RenderTargetConfig,
+    Sampler,
+    ShaderStages,
+    StencilMode,
+    Texture,
+    TextureConfig,
+    Uniform,
+};
+
+/// Full screen shader run over the frame, or a render target, after everything else is drawn.
+///
+/// Every pass source must define `fn effect(texcoord: vec2<f32>) -> vec4<f32>`, with `texcoord` starting at the top
+/// left. It can call `source(uv)` for the previous pass, `input(uv)` for what the effect started from, `extra(uv)` for
+/// the texture of `set_texture`, `texel_size()`, and read `globals.resolution`, `globals.time` and
+/// `globals.pass_index`. The uniform block of `with_uniform` is bound at `@group(1) @binding(0)`.
+///
+/// Colors are linear, sampling and writing the sRGB targets converts them.
+#[derive(Debug)]
+pub struct PostEffect {
+    passes: Vec<Pipeline>,
+    uniform: Option<Uniform>,
+    texture: Option<Rc<Texture>>,
+
+    /// Shared by the passes, for the bind groups of what they sample.
+    layout: wgpu::BindGroupLayout,
+    bind_groups: FrameCache<BindGroupKey, wgpu::BindGroup>,
+}
+
+/// Pass source, effect input, extra texture and globals a pass bind group was created for.
+type BindGroupKey = (
+    wgpu::Id<wgpu::BindGroup>,
+    wgpu::Id<wgpu::BindGroup>,
+    wgpu::Id<wgpu::BindGroup>,
+    usize,
+);
+
+impl PostEffect {
+    pub fn new(source: &str) -> Self {
+        Self::with_passes(&[source], None)
+    }
+
+    pub fn with_uniform(source: &str, data: &[u8]) -> Self {
+        Self::with_passes(&[source], Some(data))
+    }
+
+    /// Effect running `sources` in order, each one a pass reading the output of the one before.
+    pub fn with_passes(sources: &[&str], data: Option<&[u8]>) -> Self {
+        let uniform = data.map(|data| Uniform::new(data, ShaderStages::Fragment));
+
+        let layout = pass_bind_group_layout();
+        let uniform_layout = Uniform::bind_group_layout(ShaderStages::Fragment);
+
+        let passes = sources
+            .iter()
+            .map(|source| {
+                let mut bind_group_layouts = vec![(0, &layout)];
+
+                if uniform.is_some() {
+                    bind_group_layouts.push((1, &uniform_layout));
+                }
+
+                Pipeline::new(PipelineConfig {
+                    shader_source: &include_str_from_root!("res/shaders/post_process.wgsl").replace("#effect", source),
+                    vertex_buffer_layouts: &[],
+                    bind_group_layouts,
+                    depth_stencil_write_enabled: false,
+                    blend_mode: BlendMode::Opaque,
+                    stencil: StencilMode::Disabled,
+                    format: None,
+                    sample_count: Some(1),
+                })
+            })
+            .collect();
+
+        Self {
+            passes,
+            uniform,
+            texture: None,
+
+            layout,
+            bind_groups: FrameCache::default(),
+        }
+    }
+
+    /// Lands when the frame is submitted, so every pass of the frame sees the last value. Does nothing for effects
+    /// without a uniform.
+    pub fn set_uniform(&self, data: &[u8]) {
+        if let Some(uniform) = &self.uniform {
+            uniform.set(data);
+        }
+    }
+
+    pub fn has_uniform(&self) -> bool {
+        self.uniform.is_some()
+    }
+
+    /// Texture the passes sample with `extra(uv)`, using its own sampler.
+    pub fn set_texture(&mut self, texture: Texture) {
+        self.texture = Some(Rc::new(texture));
+    }
+
+    pub fn passes(&self) -> usize {
+        self.passes.len()
+    }
+
+    //
+
+    /// Darkens the corners. `radius` is where the darkening ends, 1 being the corners, `softness` how far in it starts.
+    pub fn vignette(intensity: f32, radius: f32, softness: f32) -> Self {
+        Self::with_uniform(
+            include_str_from_root!("res/shaders/post_process/vignette.wgsl"),
+            crate::cast(&[intensity, radius, softness, 0.0]),
+        )
+    }
+
+    /// Maps colors through a LUT strip of `size` square slices of `size` by `size` texels side by side, red along x,
+    /// green along y and blue picking the slice. `intensity` blends between the original and the graded color.
+    pub fn color_grading(lut: &[u8], size: u32, intensity: f32) -> Self {
+        let mut effect = Self::with_uniform(
+            include_str_from_root!("res/shaders/post_process/color_grading.wgsl"),
+            crate::cast(&[intensity, 0.0, 0.0, 0.0]),
+        );
+
+        effect.set_texture(Texture::new(lut, (size * size, size), TextureConfig {
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        }));
+
+        effect
+    }
+
+    /// Scanlines and a curved screen. With `lines` at 0 there is a scanline every two pixels.
+    pub fn crt(scanline_intensity: f32, curvature: f32, lines: f32) -> Self {
+        Self::with_uniform(
+            include_str_from_root!("res/shaders/post_process/crt.wgsl"),
+            crate::cast(&[scanline_intensity, curvature, lines, 0.0]),
+        )
+    }
+
+    /// Splits red and blue apart towards the edges, by `offset` pixels at the border.
+    pub fn chromatic_aberration(offset: f32) -> Self {
+        Self::with_uniform(
+            include_str_from_root!("res/shaders/post_process/chromatic_aberration.wgsl"),
+            crate::cast(&[offset, 0.0, 0.0, 0.0]),
+        )
+    }
+
+    /// Separable blur over `radius` pixels in two passes.
+    pub fn gaussian_blur(radius: f32) -> Self {
+        let source = include_str_from_root!("res/shaders/post_process/blur.wgsl");
+
+        Self::with_passes(&[source, source], Some(crate::cast(&[radius, 0.0, 0.0, 0.0])))
+    }
+
+    /// Adds a blurred copy of everything brighter than `threshold`, scaled by `intensity`.
+    pub fn bloom(threshold: f32, intensity: f32, radius: f32) -> Self {
+        let blur = include_str_from_root!("res/shaders/post_process/bloom_blur.wgsl");
+
+        Self::with_passes(
+            &[
+                include_str_from_root!("res/shaders/post_process/bloom_threshold.wgsl"),
+                blur,
+                blur,
+                include_str_from_root!("res/shaders/post_process/bloom_composite.wgsl"),
+            ],
+            Some(crate::cast(&[threshold, intensity, radius, 0.0])),
+        )
+    }
+}
+
+//
+
+#[derive(Debug)]
+struct Entry {
+    name: String,
+    effect: PostEffect,
+    enabled: bool,
+}
+
+/// Ordered effects applied to the whole frame between `Game::draw` and presenting it, see `post_process_mut`.
+///
+/// While at least one effect is enabled the frame is drawn into an intermediate target first. `read_frame` then reads
+/// the frame before the effects, screenshots are taken after them.
+#[derive(Debug, Default)]
+pub struct PostProcess {
+    effects: Vec<Entry>,
+    time: f32,
+
+    /// Target the frame is drawn into while the chain is active, multisampled like the frame.
+    scene: Option<RenderTarget>,
+    /// Intermediate targets of the current size, created as passes need them. Passes draw into them directly, so
+    /// they are single sampled.
+    targets: Vec<RenderTarget>,
+    /// One per pass run in a frame, since uniform writes land before any pass runs.
+    globals: Vec<Uniform>,
+    used_globals: usize,
+    globals_frame: u64,
+
+    sampler: Option<Rc<Sampler>>,
+    blank: Option<Rc<Texture>>,
+}
+
+impl PostProcess {
+    /// Appends `effect` at the end of the chain, replacing an effect of the same name in place.
+    pub fn push(&mut self, name: impl Into<String>, effect: PostEffect) {
+        let name = name.into();
+
+        match self.effects.iter_mut().find(|entry| entry.name == name) {
+            Some(entry) => entry.effect = effect,
+            None => self.effects.push(Entry {
+                name,
+                effect,
+                enabled: true,
+            }),
+        }
+    }
+
+    /// Inserts `effect` at `index`, clamped to the length of the chain.
+    pub fn insert(&mut self, index: usize, name: impl Into<String>, effect: PostEffect) {
+        let name = name.into();
+        self.remove(&name);
+
+        self.effects.insert(index.min(self.effects.len()), Entry {
+            name,
+            effect,
+            enabled: true,
+        });
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<PostEffect> {
+        let index = self.effects.iter().position(|entry| entry.name == name)?;
+
+        Some(self.effects.remove(index).effect)
+    }
+
+    pub fn clear(&mut self) {
+        self.effects.clear();
+    }
+
+    pub fn effect(&self, name: &str) -> Option<&PostEffect> {
+        self.effects.iter().find(|entry| entry.name == name).map(|entry| &entry.effect)
+    }
+
+    pub fn effect_mut(&mut self, name: &str) -> Option<&mut PostEffect> {
+        self.effects
+            .iter_mut()
+            .find(|entry| entry.name == name)
+            .map(|entry| &mut entry.effect)
+    }
+
+    /// Skips the effect without removing it.
+    pub fn set_enabled(&mut self, name: &str, enabled: bool) {
+        if let Some(entry) = self.effects.iter_mut().find(|entry| entry.name == name) {
+            entry.enabled = enabled;
+        }
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.effects.iter().any(|entry| entry.name == name && entry.enabled)
+    }
+
+    /// Names in the order the effects run.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.effects.iter().map(|entry| entry.name.as_str())
+    }
+
+    /// Whether any effect is enabled, the frame is only redirected then.
+    pub fn is_active(&self) -> bool {
+        self.effects.iter().any(|entry| entry.enabled && !entry.effect.passes.is_empty())
+    }
+
+    /// Seconds passed to the shaders as `globals.time`, set to `GameTime::total` before every frame.
+    pub fn set_time(&mut self, time: f32) {
+        self.time = time;
+    }
+
+    /// Runs the enabled effects from `source` into `destination` as part of the current frame, both must be of the
+    /// same size. Without an enabled effect `source` is copied over as it is, which needs matching formats too. Does
+    /// nothing outside of a frame.
+    pub fn apply(&mut self, source: &RenderTarget, destination: &RenderTarget) {
+        let Some(frame) = crate::gfx::raw::frame() else {
+            return;
+        };
+
+        if self.is_active() {
+            self.run(
+                &mut frame.encoder,
+                &source.texture,
+                &destination.texture.texture_view,
+                destination.dimensions(),
+            );
+            return;
+        }
+
+        if source.format().remove_srgb_suffix() != destination.format().remove_srgb_suffix() {
+            log::error!(
+                "Can not copy a {:?} target into a {:?} one without an effect",
+                source.format(),
+                destination.format()
+            );
+            return;
+        }
+
+        let (width, height) = source.dimensions();
+
+        frame.encoder.copy_texture_to_texture(
+            source.texture.texture.as_image_copy(),
+            destination.texture.texture.as_image_copy(),
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    //
+
+    /// Intermediate target the frame is drawn into while the chain is active.
+    pub(crate) fn scene(&mut self, dimensions: (u32, u32)) -> RenderTarget {
+        self.resize(dimensions);
+
+        self.scene
+            .get_or_insert_with(|| RenderTarget::new(dimensions, false, TextureConfig::default()))
+            .clone()
+    }
+
+    pub(crate) fn run(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &Rc<Texture>,
+        destination: &wgpu::TextureView,
+        dimensions: (u32, u32),
+    ) {
+        self.resize(dimensions);
+
+        if self.globals_frame != crate::gfx::raw::frame_index() {
+            self.globals_frame = crate::gfx::raw::frame_index();
+            self.used_globals = 0;
+        }
+
+        let passes = self
+            .effects
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.enabled)
+            .flat_map(|(effect, entry)| (0..entry.effect.passes.len()).map(move |pass| (effect, pass)))
+            .collect::<Vec<_>>();
+
+        let sampler = Rc::clone(self.sampler.get_or_insert_with(|| {
+            Rc::new(Sampler::new(TextureConfig {
+                mag_filter: FilterMode::Linear,
+                min_filter: FilterMode::Linear,
+                ..Default::default()
+            }))
+        }));
+        let blank = Rc::clone(
+            self.blank
+                .get_or_insert_with(|| Rc::new(Texture::new(&[255, 255, 255, 255], (1, 1), TextureConfig::default()))),
+        );
+
+        // effect inputs and pass sources, either the caller's source or one of the intermediate targets
+        let mut input = Rc::clone(source);
+        let mut previous = Rc::clone(source);
+
+        for (index, &(effect, pass)) in passes.iter().enumerate() {
+            if pass == 0 {
+                input = Rc::clone(&previous);
+            }
+
+            let last = index == passes.len() - 1;
+
+            let target = if last {
+                None
+            } else {
+                Some(self.target(&[&input, &previous]).clone_texture_rc())
+            };
+
+            let globals = self.globals(dimensions, pass as u32);
+            let globals_buffer = &self.globals[globals].buffer;
+
+            let effect = &mut self.effects[effect].effect;
+            let extra = effect.texture.as_ref().unwrap_or(&blank);
+            let layout = &effect.layout;
+
+            let bind_group = effect
+                .bind_groups
+                .get_or_insert_with((previous.id(), input.id(), extra.id(), globals), || {
+                    ctx!().device.create_bind_group(&wgpu::BindGroupDescriptor {
+                        label: Some("Post Process Bind Group"),
+                        layout,
+                        entries: &[
+                            wgpu::BindGroupEntry {
+                                binding: 0,
+                                resource: wgpu::BindingResource::TextureView(&previous.texture_view),
+                            },
+                            wgpu::BindGroupEntry {
+                                binding: 1,
+                                resource: wgpu::BindingResource::Sampler(&sampler.sampler),
+                            },
+                            wgpu::BindGroupEntry {
+                                binding: 2,
+                                resource: wgpu::BindingResource::TextureView(&input.texture_view),
+                            },
+                            wgpu::BindGroupEntry {
+                                binding: 3,
+                                resource: wgpu::BindingResource::Sampler(&sampler.sampler),
+                            },
+                            wgpu::BindGroupEntry {
+                                binding: 4,
+                                resource: wgpu::BindingResource::TextureView(&extra.texture_view),
+                            },
+                            wgpu::BindGroupEntry {
+                                binding: 5,
+                                resource: wgpu::BindingResource::Sampler(&extra.sampler),
+                            },
+                            wgpu::BindGroupEntry {
+                                binding: 6,
+                                resource: globals_buffer.as_entire_binding(),
+                            },
+                        ],
+                    })
+                });
+
+            {
+                let view = match &target {
+                    Some(target) => &target.texture_view,
+                    None => destination,
+                };
+
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Post Process Pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    occlusion_query_set: None,
+                    timestamp_writes: None,
+                });
+
+                render_pass.set_pipeline(&effect.passes[pass].pipeline);
+                render_pass.set_bind_group(0, &bind_group, &[]);
+
+                if let Some(uniform) = &effect.uniform {
+                    render_pass.set_bind_group(1, &uniform.bind_group.bind_group, &[]);
+                }
+
+                render_pass.draw(0..3, 0..1);
+            }
+
+            if let Some(target) = target {
+                previous = target;
+            }
+        }
+    }
+
+    /// Drops the scene and intermediate targets when the size changes.
+    fn resize(&mut self, dimensions: (u32, u32)) {
+        if self.scene.as_ref().is_some_and(|scene| scene.dimensions() != dimensions) {
+            self.scene = None;
+        }
+
+        if self.targets.first().is_some_and(|target| target.dimensions() != dimensions) {
+            self.targets.clear();
+        }
+    }
+
+    /// Intermediate target other than the ones in `busy`.
+    fn target(&mut self, busy: &[&Rc<Texture>]) -> RenderTarget {
+        let free = self
+            .targets
+            .iter()
+            .find(|target| !busy.iter().any(|texture| Rc::ptr_eq(texture, &target.texture)));
+
+        if let Some(target) = free {
+            return target.clone();
+        }
+
+        let dimensions = (ctx!().config.width, ctx!().config.height);
+        let dimensions = self.targets.first().map(|target| target.dimensions()).unwrap_or(dimensions);
+
+        let target = RenderTarget::with_config(dimensions, RenderTargetConfig {
+            sample_count: Some(1),
+            ..Default::default()
+        });
+        self.targets.push(target.clone());
+
+        target
+    }
+
+    fn globals(&mut self, dimensions: (u32, u32), pass: u32) -> usize {
+        // the pass index is read as a `u32` by the shader
+        let data = [dimensions.0 as f32, dimensions.1 as f32, self.time, f32::from_bits(pass)];
+
+        if self.used_globals == self.globals.len() {
+            self.globals.push(Uniform::new(crate::cast(&data), ShaderStages::Fragment));
+        } else {
+            self.globals[self.used_globals].set(crate::cast(&data));
+        }
+
+        self.used_globals += 1;
+        self.used_globals - 1
+    }
+}
+
+fn pass_bind_group_layout() -> wgpu::BindGroupLayout {
+    let texture = |binding| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            multisampled: false,
+            view_dimension: wgpu::TextureViewDimension::D2,
+            sample_type: wgpu::TextureSampleType::Float {
+                filterable: true,
+            },
+        },
+        count: None,
+    };
+    let sampler = |binding| wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    };
+
+    ctx!().device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Post Process Bind Group Layout"),
+        entries: &[
+            texture(0),
+            sampler(1),
+            texture(2),
+            sampler(3),
+            texture(4),
+            sampler(5),
+            wgpu::BindGroupLayoutEntry {
+                binding: 6,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ],
+    })
+}
@@ -0,0 +1,279 @@
+use std::{
+    cell::{
+        Cell,
+        RefCell,
+    },
+    path::Path,
+    rc::Rc,
+    sync::mpsc,
+};
+
+use crate::errors::ReadbackError;
+
+//
+
+pub fn read_texture(texture: &wgpu::Texture) -> anyhow::Result<Readback> {
+    let mut encoder = ctx!().create_encoder();
+
+    let readback = Readback::new(&mut encoder, texture)?;
+
+    ctx!().submit(encoder.finish());
+    readback.submitted.set(true);
+
+    Ok(readback)
+}
+
+//
+
+#[derive(Debug, Clone)]
+pub struct Pixels {
+    pub width: u32,
+    pub height: u32,
+    /// Tightly packed RGBA8 rows, top to bottom.
+    pub data: Vec<u8>,
+}
+
+impl Pixels {
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let index = ((y * self.width + x) * 4) as usize;
+
+        [self.data[index], self.data[index + 1], self.data[index + 2], self.data[index + 3]]
+    }
+
+    pub fn to_image(&self) -> image::RgbaImage {
+        image::RgbaImage::from_raw(self.width, self.height, self.data.clone()).expect("Pixel data does not match dimensions.")
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        image::save_buffer_with_format(
+            path,
+            &self.data,
+            self.width,
+            self.height,
+            image::ColorType::Rgba8,
+            image::ImageFormat::Png,
+        )?;
+
+        Ok(())
+    }
+}
+
+//
+
+#[derive(Debug)]
+pub struct Readback {
+    buffer: wgpu::Buffer,
+    format: wgpu::TextureFormat,
+
+    width: u32,
+    height: u32,
+    padded_bytes_per_row: u32,
+
+    submitted: Rc<Cell<bool>>,
+    receiver: Option<mpsc::Receiver<Result<(), wgpu::BufferAsyncError>>>,
+}
+
+impl Readback {
+    pub(crate) fn new(encoder: &mut wgpu::CommandEncoder, texture: &wgpu::Texture) -> anyhow::Result<Self> {
+        let format = texture.format();
+
+        if !matches!(
+            format,
+            wgpu::TextureFormat::Rgba8Unorm
+                | wgpu::TextureFormat::Rgba8UnormSrgb
+                | wgpu::TextureFormat::Bgra8Unorm
+                | wgpu::TextureFormat::Bgra8UnormSrgb
+        ) {
+            return Err(anyhow::anyhow!(ReadbackError::UnsupportedFormat(format)));
+        }
+
+        if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+            return Err(anyhow::anyhow!(ReadbackError::NotCopyable));
+        }
+
+        let width = texture.width();
+        let height = texture.height();
+
+        let unpadded_bytes_per_row = width * 4;
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let buffer = ctx!().device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: (padded_bytes_per_row * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        Ok(Self {
+            buffer,
+            format,
+
+            width,
+            height,
+            padded_bytes_per_row,
+
+            submitted: Rc::default(),
+            receiver: None,
+        })
+    }
+
+    /// Returns `true` once the commands that produced this readback have been submitted.
+    pub fn is_submitted(&self) -> bool {
+        self.submitted.get()
+    }
+
+    /// Flag set by whoever submits the encoder this readback was recorded into.
+    pub(crate) fn submitted(&self) -> Rc<Cell<bool>> {
+        Rc::clone(&self.submitted)
+    }
+
+    /// Non-blocking poll, returns `None` while the copy is still in flight.
+    pub fn try_read(&mut self) -> Option<anyhow::Result<Pixels>> {
+        if !self.is_submitted() {
+            return None;
+        }
+
+        self.map();
+
+        ctx!().device.poll(wgpu::Maintain::Poll);
+
+        match self.receiver.as_ref().unwrap().try_recv() {
+            Ok(result) => Some(self.finish(result)),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => Some(Err(anyhow::anyhow!(ReadbackError::Disconnected))),
+        }
+    }
+
+    pub async fn read(mut self) -> anyhow::Result<Pixels> {
+        if !self.is_submitted() {
+            return Err(anyhow::anyhow!(ReadbackError::NotSubmitted));
+        }
+
+        self.map();
+
+        ctx!().device.poll(wgpu::Maintain::Wait);
+
+        match self.receiver.as_ref().unwrap().recv() {
+            Ok(result) => self.finish(result),
+            Err(_) => Err(anyhow::anyhow!(ReadbackError::Disconnected)),
+        }
+    }
+
+    pub fn wait(self) -> anyhow::Result<Pixels> {
+        pollster::block_on(self.read())
+    }
+
+    fn map(&mut self) {
+        if self.receiver.is_some() {
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+
+        self.buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+            _ = sender.send(result);
+        });
+
+        self.receiver = Some(receiver);
+    }
+
+    fn finish(&self, result: Result<(), wgpu::BufferAsyncError>) -> anyhow::Result<Pixels> {
+        result?;
+
+        let unpadded_bytes_per_row = (self.width * 4) as usize;
+        let mut data = Vec::with_capacity(unpadded_bytes_per_row * self.height as usize);
+
+        {
+            let mapped = self.buffer.slice(..).get_mapped_range();
+
+            for row in mapped.chunks(self.padded_bytes_per_row as usize) {
+                data.extend_from_slice(&row[..unpadded_bytes_per_row]);
+            }
+        }
+
+        self.buffer.unmap();
+
+        if matches!(self.format, wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb) {
+            for pixel in data.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        Ok(Pixels {
+            width: self.width,
+            height: self.height,
+            data,
+        })
+    }
+}
+
+//
+
+pub(crate) type PendingScreenshot = Rc<RefCell<Option<anyhow::Result<Readback>>>>;
+
+/// Copy of a presented frame, recorded after post processing at the end of the frame it was requested in.
+#[derive(Debug, Default)]
+pub struct Screenshot {
+    pending: PendingScreenshot,
+}
+
+impl Screenshot {
+    pub(crate) fn new() -> (Self, PendingScreenshot) {
+        let screenshot = Self::default();
+        let pending = Rc::clone(&screenshot.pending);
+
+        (screenshot, pending)
+    }
+
+    /// Returns `true` once the frame has ended and its copy has been recorded and submitted.
+    pub fn is_submitted(&self) -> bool {
+        match &*self.pending.borrow() {
+            Some(Ok(readback)) => readback.is_submitted(),
+            Some(Err(_)) => true,
+            None => false,
+        }
+    }
+
+    /// Non-blocking poll, returns `None` while the frame has not ended or the copy is still in flight.
+    pub fn try_read(&mut self) -> Option<anyhow::Result<Pixels>> {
+        let mut pending = self.pending.borrow_mut();
+
+        match pending.as_mut()? {
+            Ok(readback) => readback.try_read(),
+            Err(_) => pending.take().and_then(Result::err).map(Err),
+        }
+    }
+
+    pub async fn read(self) -> anyhow::Result<Pixels> {
+        match self.pending.take() {
+            Some(readback) => readback?.read().await,
+            None => Err(anyhow::anyhow!(ReadbackError::NotSubmitted)),
+        }
+    }
+
+    pub fn wait(self) -> anyhow::Result<Pixels> {
+        pollster::block_on(self.read())
+    }
+}
@@ -7,6 +7,8 @@ use crate::gfx::raw::{
     IndexBuffer,
     Pipeline,
     Texture,
+    TextureArray,
+    TextureBinding,
     Uniform,
     VertexBuffer,
 };
@@ -25,7 +27,7 @@ pub struct RenderPass<'a> {
     pub render_pass: wgpu::RenderPass<'a>,
 }
 
-#[allow(dead_code)]
+#[allow(dead_code, clippy::missing_safety_doc)]
 impl<'a> RenderPass<'a> {
     pub fn new(render_pass: wgpu::RenderPass<'a>) -> Self {
         Self {
@@ -43,6 +45,16 @@ impl<'a> RenderPass<'a> {
         self.render_pass.set_bind_group(index, &texture.bind_group, &[]);
     }
 
+    #[inline]
+    pub fn set_texture_binding(&mut self, index: u32, texture_binding: &'a TextureBinding) {
+        self.render_pass.set_bind_group(index, &texture_binding.bind_group, &[]);
+    }
+
+    #[inline]
+    pub fn set_texture_array(&mut self, index: u32, texture_array: &'a TextureArray) {
+        self.render_pass.set_bind_group(index, &texture_array.bind_group, &[]);
+    }
+
     #[inline]
     pub fn set_uniform(&mut self, index: u32, uniform: &'a Uniform) {
         self.render_pass.set_bind_group(index, &uniform.bind_group.bind_group, &[]);
@@ -76,6 +88,12 @@ impl<'a> RenderPass<'a> {
         self.render_pass.set_vertex_buffer(index, vertex_buffer.buffer.slice(..));
     }
 
+    /// Binds the vertex buffer from `offset` on, as returned by `DynamicVertexBuffer::push`.
+    #[inline]
+    pub fn set_dynamic_vertex_buffer_at(&mut self, index: u32, vertex_buffer: &'a DynamicVertexBuffer, offset: u64) {
+        self.render_pass.set_vertex_buffer(index, vertex_buffer.buffer.slice(offset..));
+    }
+
     #[inline]
     pub fn set_dynamic_index_buffer(&mut self, index_buffer: &'a DynamicIndexBuffer) {
         self.render_pass
@@ -148,7 +166,19 @@ impl<'a> RenderPass<'a> {
         self.render_pass.set_viewport(x, y, width, height, 0.0, 1.0);
     }
 
-    pub fn raw(&'a mut self) -> &'a mut wgpu::RenderPass {
+    /// Discards everything outside of the rectangle, in pixels of the attachment from its top left corner.
+    #[inline]
+    pub fn set_scissor_rect(&mut self, x: u32, y: u32, width: u32, height: u32) {
+        self.render_pass.set_scissor_rect(x, y, width, height);
+    }
+
+    /// Value the pipeline's `StencilMode` compares the stencil buffer against.
+    #[inline]
+    pub fn set_stencil_reference(&mut self, reference: u32) {
+        self.render_pass.set_stencil_reference(reference);
+    }
+
+    pub fn raw(&'a mut self) -> &'a mut wgpu::RenderPass<'a> {
         &mut self.render_pass
     }
 }
@@ -0,0 +1,145 @@
+use std::rc::Rc;
+
+use crate::{
+    gfx::raw::{
+        Color,
+        Depth,
+        LoadOp,
+        Multisample,
+        Readback,
+        RenderTargetConfig,
+        Texture,
+        TextureConfig,
+    },
+    Sprite,
+};
+
+/// Offscreen color (and optional depth) attachment that can be drawn into and sampled afterwards.
+///
+/// Cloning is cheap, clones share the same textures.
+///
+/// While multisampling, passes draw into a multisampled texture of the target and resolve it into the sampled one.
+/// Anything written into the sampled texture outside of those passes, like `PostProcess::apply`, is replaced by the
+/// next pass drawing into the target.
+#[derive(Debug, Clone)]
+pub struct RenderTarget {
+    pub(crate) texture: Rc<Texture>,
+    pub(crate) multisample: Option<Rc<Multisample>>,
+    pub(crate) depth: Option<Rc<Depth>>,
+}
+
+impl RenderTarget {
+    pub fn new(dimensions: (u32, u32), depth: bool, config: TextureConfig) -> Self {
+        Self::with_config(dimensions, RenderTargetConfig {
+            depth,
+            texture_config: config,
+            ..Default::default()
+        })
+    }
+
+    pub fn with_config(dimensions: (u32, u32), config: RenderTargetConfig) -> Self {
+        let format = config.format.map_or(ctx!().config.format, |format| format.wgpu());
+
+        let texture = ctx!().device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Render Target"),
+            size: wgpu::Extent3d {
+                width: dimensions.0,
+                height: dimensions.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC
+                | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        let sample_count = config.sample_count.unwrap_or(ctx!().sample_count);
+
+        let multisample = (sample_count > 1).then(|| {
+            Rc::new(Multisample::new(
+                &ctx!().device,
+                dimensions,
+                format,
+                sample_count,
+                "render target multisample",
+            ))
+        });
+
+        let depth = if config.depth {
+            Some(Rc::new(Depth::new(&ctx!().device, dimensions, sample_count, "render target depth")))
+        } else {
+            None
+        };
+
+        Self {
+            texture: Rc::new(Texture::from_raw(texture, config.texture_config)),
+            multisample,
+            depth,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.texture.dimensions.0
+    }
+
+    pub fn height(&self) -> u32 {
+        self.texture.dimensions.1
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.texture.dimensions
+    }
+
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Format pipelines drawing into the target are created for.
+    pub(crate) fn format(&self) -> wgpu::TextureFormat {
+        self.texture.texture.format()
+    }
+
+    /// Sample count pipelines drawing into the target are created for.
+    pub(crate) fn sample_count(&self) -> u32 {
+        self.multisample
+            .as_ref()
+            .map_or(1, |multisample| multisample.texture.sample_count())
+    }
+
+    /// View passes draw into and the view it is resolved into, if multisampling.
+    pub(crate) fn attachment(&self) -> (&wgpu::TextureView, Option<&wgpu::TextureView>) {
+        match &self.multisample {
+            Some(multisample) => (&multisample.view, Some(&self.texture.texture_view)),
+            None => (&self.texture.texture_view, None),
+        }
+    }
+
+    pub(crate) fn clone_texture_rc(&self) -> Rc<Texture> {
+        Rc::clone(&self.texture)
+    }
+
+    /// Sprite sampling this target, covering the whole target at its native size.
+    pub fn sprite(&self) -> Sprite {
+        Sprite::from_rc(self.clone_texture_rc())
+    }
+
+    /// Clears the target as part of the current frame, does nothing outside of a frame.
+    pub fn clear(&self, color: Color) {
+        if let Some(frame) = crate::gfx::raw::frame() {
+            _ = frame.create_target_render_pass(self, self.depth.is_some(), LoadOp::Clear(color));
+        }
+    }
+
+    /// Records a copy of the target, inside a frame the copy is ordered after everything drawn so far.
+    pub fn read_pixels(&self) -> anyhow::Result<Readback> {
+        match crate::gfx::raw::frame() {
+            Some(frame) => frame.read_texture(&self.texture.texture),
+            None => crate::gfx::raw::read_texture(&self.texture.texture),
+        }
+    }
+}
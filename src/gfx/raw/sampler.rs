@@ -0,0 +1,78 @@
+use crate::gfx::raw::{
+    AddressMode,
+    CompareFunction,
+    FilterMode,
+    SamplerBorderColor,
+    TextureConfig,
+};
+
+/// Standalone sampler, every `Texture` owns one built from its own `TextureConfig` as well.
+#[derive(Debug)]
+pub struct Sampler {
+    pub(crate) sampler: wgpu::Sampler,
+}
+
+impl Sampler {
+    pub fn new(config: TextureConfig) -> Self {
+        let sampler = ctx!().device.create_sampler(&wgpu::SamplerDescriptor {
+            label: None,
+            address_mode_u: match config.address_mode_u {
+                AddressMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+                AddressMode::Repeat => wgpu::AddressMode::Repeat,
+                AddressMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+                AddressMode::ClampToBorder => wgpu::AddressMode::ClampToBorder,
+            },
+            address_mode_v: match config.address_mode_v {
+                AddressMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+                AddressMode::Repeat => wgpu::AddressMode::Repeat,
+                AddressMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+                AddressMode::ClampToBorder => wgpu::AddressMode::ClampToBorder,
+            },
+            address_mode_w: match config.address_mode_w {
+                AddressMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+                AddressMode::Repeat => wgpu::AddressMode::Repeat,
+                AddressMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+                AddressMode::ClampToBorder => wgpu::AddressMode::ClampToBorder,
+            },
+            mag_filter: match config.mag_filter {
+                FilterMode::Nearest => wgpu::FilterMode::Nearest,
+                FilterMode::Linear => wgpu::FilterMode::Linear,
+            },
+            min_filter: match config.min_filter {
+                FilterMode::Nearest => wgpu::FilterMode::Nearest,
+                FilterMode::Linear => wgpu::FilterMode::Linear,
+            },
+            mipmap_filter: match config.mipmap_filter {
+                FilterMode::Nearest => wgpu::FilterMode::Nearest,
+                FilterMode::Linear => wgpu::FilterMode::Linear,
+            },
+            lod_min_clamp: config.lod_min_clamp,
+            lod_max_clamp: config.lod_max_clamp,
+            compare: config.compare.map(|compare| match compare {
+                CompareFunction::Never => wgpu::CompareFunction::Never,
+                CompareFunction::Less => wgpu::CompareFunction::Less,
+                CompareFunction::Equal => wgpu::CompareFunction::Equal,
+                CompareFunction::LessEqual => wgpu::CompareFunction::LessEqual,
+                CompareFunction::Greater => wgpu::CompareFunction::Greater,
+                CompareFunction::NotEqual => wgpu::CompareFunction::NotEqual,
+                CompareFunction::GreaterEqual => wgpu::CompareFunction::GreaterEqual,
+                CompareFunction::Always => wgpu::CompareFunction::Always,
+            }),
+            anisotropy_clamp: config.anisotropy_clamp,
+            border_color: config.border_color.map(|border_color| match border_color {
+                SamplerBorderColor::TransparentBlack => wgpu::SamplerBorderColor::TransparentBlack,
+                SamplerBorderColor::OpaqueBlack => wgpu::SamplerBorderColor::OpaqueBlack,
+                SamplerBorderColor::OpaqueWhite => wgpu::SamplerBorderColor::OpaqueWhite,
+                SamplerBorderColor::Zero => wgpu::SamplerBorderColor::Zero,
+            }),
+        });
+
+        Self {
+            sampler,
+        }
+    }
+
+    pub fn id(&self) -> wgpu::Id<wgpu::Sampler> {
+        self.sampler.global_id()
+    }
+}
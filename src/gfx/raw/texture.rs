@@ -1,20 +1,33 @@
 use crate::gfx::raw::{
-    AddressMode,
-    CompareFunction,
-    FilterMode,
-    SamplerBorderColor,
+    Sampler,
     TextureConfig,
 };
 
 #[derive(Debug)]
 pub struct Texture {
+    pub(crate) texture: wgpu::Texture,
     pub(crate) texture_view: wgpu::TextureView,
+    pub(crate) sampler: wgpu::Sampler,
     pub(crate) bind_group: wgpu::BindGroup,
     pub(crate) dimensions: (u32, u32),
 }
 
 impl Texture {
+    /// Texture of `bytes`, tightly packed rows laid out as `config.format`.
     pub fn new(bytes: &[u8], dimensions: (u32, u32), config: TextureConfig) -> Self {
+        let mip_level_count = if config.mipmaps {
+            u32::BITS - dimensions.0.max(dimensions.1).max(1).leading_zeros()
+        } else {
+            1
+        };
+
+        // mip levels are drawn from the level above them
+        let usage = if mip_level_count > 1 {
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::RENDER_ATTACHMENT
+        } else {
+            wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST
+        };
+
         let texture_size = wgpu::Extent3d {
             width: dimensions.0,
             height: dimensions.1,
@@ -23,11 +36,11 @@ impl Texture {
 
         let texture = ctx!().device.create_texture(&wgpu::TextureDescriptor {
             size: texture_size,
-            mip_level_count: 1,
+            mip_level_count,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            format: config.format.wgpu(),
+            usage,
             label: Some("Texture"),
             view_formats: &[],
         });
@@ -42,74 +55,23 @@ impl Texture {
             bytes,
             wgpu::ImageDataLayout {
                 offset: 0,
-                bytes_per_row: Some(4 * dimensions.0),
+                bytes_per_row: Some(config.format.bytes_per_pixel() * dimensions.0),
                 rows_per_image: Some(dimensions.1),
             },
             texture_size,
         );
 
+        ctx!().mipmaps.generate(&texture);
+
+        Self::from_raw(texture, config)
+    }
+
+    pub(crate) fn from_raw(texture: wgpu::Texture, config: TextureConfig) -> Self {
+        let dimensions = (texture.width(), texture.height());
+
         let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
-        let sampler = ctx!().device.create_sampler(&wgpu::SamplerDescriptor {
-            label: None,
-            address_mode_u: match config.address_mode_u {
-                AddressMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
-                AddressMode::Repeat => wgpu::AddressMode::Repeat,
-                AddressMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
-                AddressMode::ClampToBorder => wgpu::AddressMode::ClampToBorder,
-            },
-            address_mode_v: match config.address_mode_v {
-                AddressMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
-                AddressMode::Repeat => wgpu::AddressMode::Repeat,
-                AddressMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
-                AddressMode::ClampToBorder => wgpu::AddressMode::ClampToBorder,
-            },
-            address_mode_w: match config.address_mode_w {
-                AddressMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
-                AddressMode::Repeat => wgpu::AddressMode::Repeat,
-                AddressMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
-                AddressMode::ClampToBorder => wgpu::AddressMode::ClampToBorder,
-            },
-            mag_filter: match config.mag_filter {
-                FilterMode::Nearest => wgpu::FilterMode::Nearest,
-                FilterMode::Linear => wgpu::FilterMode::Linear,
-            },
-            min_filter: match config.min_filter {
-                FilterMode::Nearest => wgpu::FilterMode::Nearest,
-                FilterMode::Linear => wgpu::FilterMode::Linear,
-            },
-            mipmap_filter: match config.mipmap_filter {
-                FilterMode::Nearest => wgpu::FilterMode::Nearest,
-                FilterMode::Linear => wgpu::FilterMode::Linear,
-            },
-            lod_min_clamp: config.lod_min_clamp,
-            lod_max_clamp: config.lod_max_clamp,
-            compare: if config.compare.is_some() {
-                Some(match config.compare.unwrap() {
-                    CompareFunction::Never => wgpu::CompareFunction::Never,
-                    CompareFunction::Less => wgpu::CompareFunction::Less,
-                    CompareFunction::Equal => wgpu::CompareFunction::Equal,
-                    CompareFunction::LessEqual => wgpu::CompareFunction::LessEqual,
-                    CompareFunction::Greater => wgpu::CompareFunction::Greater,
-                    CompareFunction::NotEqual => wgpu::CompareFunction::NotEqual,
-                    CompareFunction::GreaterEqual => wgpu::CompareFunction::GreaterEqual,
-                    CompareFunction::Always => wgpu::CompareFunction::Always,
-                })
-            } else {
-                None
-            },
-            anisotropy_clamp: config.anisotropy_clamp,
-            border_color: if config.border_color.is_some() {
-                Some(match config.border_color.unwrap() {
-                    SamplerBorderColor::TransparentBlack => wgpu::SamplerBorderColor::TransparentBlack,
-                    SamplerBorderColor::OpaqueBlack => wgpu::SamplerBorderColor::OpaqueBlack,
-                    SamplerBorderColor::OpaqueWhite => wgpu::SamplerBorderColor::OpaqueWhite,
-                    SamplerBorderColor::Zero => wgpu::SamplerBorderColor::Zero,
-                })
-            } else {
-                None
-            },
-        });
+        let sampler = Sampler::new(config).sampler;
 
         let bind_group = ctx!().device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &Texture::bind_group_layout(),
@@ -127,12 +89,51 @@ impl Texture {
         });
 
         Self {
+            texture,
             texture_view,
+            sampler,
             bind_group,
             dimensions,
         }
     }
 
+    /// Overwrites a region of the top mip level with tightly packed `bytes` in the texture's format. Levels below keep
+    /// their old content until `generate_mipmaps`.
+    pub fn write(&self, origin: (u32, u32), dimensions: (u32, u32), bytes: &[u8]) {
+        ctx!().queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: origin.0,
+                    y: origin.1,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            bytes,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(self.texture.format().block_copy_size(None).unwrap_or(4) * dimensions.0),
+                rows_per_image: Some(dimensions.1),
+            },
+            wgpu::Extent3d {
+                width: dimensions.0,
+                height: dimensions.1,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    /// Redraws every mip level below the top one from it, does nothing for textures created without mipmaps.
+    pub fn generate_mipmaps(&self) {
+        ctx!().mipmaps.generate(&self.texture);
+    }
+
+    pub fn mip_level_count(&self) -> u32 {
+        self.texture.mip_level_count()
+    }
+
     pub fn bind_group_layout() -> wgpu::BindGroupLayout {
         ctx!().device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             entries: &[
@@ -163,3 +164,34 @@ impl Texture {
         self.bind_group.global_id()
     }
 }
+
+//
+
+/// Bind group of a texture with a sampler other than its own, laid out like `Texture::bind_group_layout`.
+#[derive(Debug)]
+pub struct TextureBinding {
+    pub(crate) bind_group: wgpu::BindGroup,
+}
+
+impl TextureBinding {
+    pub fn new(texture: &Texture, sampler: &Sampler) -> Self {
+        let bind_group = ctx!().device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &Texture::bind_group_layout(),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&texture.texture_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler.sampler),
+                },
+            ],
+            label: Some("Texture Binding Bind Group"),
+        });
+
+        Self {
+            bind_group,
+        }
+    }
+}
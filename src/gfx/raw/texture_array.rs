@@ -0,0 +1,117 @@
+use std::rc::Rc;
+
+use hashbrown::HashMap;
+
+use crate::gfx::raw::{
+    FrameCache,
+    Sampler,
+    Texture,
+};
+
+/// Bind group with a `binding_array` of textures at binding 0 and one of samplers at binding 1, letting a shader
+/// pick the texture per fragment.
+#[derive(Debug)]
+pub struct TextureArray {
+    pub(crate) bind_group: wgpu::BindGroup,
+}
+
+impl TextureArray {
+    pub(crate) const FEATURES: wgpu::Features =
+        wgpu::Features::TEXTURE_BINDING_ARRAY.union(wgpu::Features::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING);
+
+    pub fn is_supported() -> bool {
+        ctx!().device.features().contains(Self::FEATURES)
+    }
+
+    /// Largest array the device can bind to a single shader stage.
+    pub fn max_count() -> u32 {
+        let limits = ctx!().device.limits();
+
+        limits
+            .max_sampled_textures_per_shader_stage
+            .min(limits.max_samplers_per_shader_stage)
+    }
+
+    /// Array of `textures`, sampled with their own samplers or `sampler` instead. `layout` must have been created for
+    /// as many textures.
+    fn new(layout: &wgpu::BindGroupLayout, textures: &[&Texture], sampler: Option<&Sampler>) -> Self {
+        let views = textures.iter().map(|texture| &texture.texture_view).collect::<Vec<_>>();
+        let samplers = match sampler {
+            Some(sampler) => vec![&sampler.sampler; textures.len()],
+            None => textures.iter().map(|texture| &texture.sampler).collect(),
+        };
+
+        let bind_group = ctx!().device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureViewArray(&views),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::SamplerArray(&samplers),
+                },
+            ],
+            label: Some("Texture Array Bind Group"),
+        });
+
+        Self {
+            bind_group,
+        }
+    }
+
+    pub fn bind_group_layout(count: u32) -> wgpu::BindGroupLayout {
+        ctx!().device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float {
+                            filterable: true,
+                        },
+                    },
+                    count: std::num::NonZeroU32::new(count),
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: std::num::NonZeroU32::new(count),
+                },
+            ],
+            label: Some("Texture Array Bind Group Layout"),
+        })
+    }
+}
+
+/// Textures and sampler override a texture array was created for.
+type ArrayKey = (Vec<wgpu::Id<wgpu::BindGroup>>, Option<wgpu::Id<wgpu::Sampler>>);
+
+/// Texture arrays by the textures and sampler they bind, with one layout per array size. Draw calls binding the same
+/// textures as in the last frame reuse its arrays.
+#[derive(Debug, Default)]
+pub struct TextureArrayCache {
+    layouts: HashMap<u32, wgpu::BindGroupLayout>,
+    arrays: FrameCache<ArrayKey, TextureArray>,
+}
+
+impl TextureArrayCache {
+    /// Array of `textures`, sampled with `sampler` instead of their own samplers if there is one.
+    pub fn get(&mut self, textures: &[&Texture], sampler: Option<&Sampler>) -> Rc<TextureArray> {
+        let key = (
+            textures.iter().map(|texture| texture.id()).collect(),
+            sampler.map(|sampler| sampler.id()),
+        );
+
+        let layout = self
+            .layouts
+            .entry(textures.len() as u32)
+            .or_insert_with(|| TextureArray::bind_group_layout(textures.len() as u32));
+
+        self.arrays.get_or_insert_with(key, || TextureArray::new(layout, textures, sampler))
+    }
+}
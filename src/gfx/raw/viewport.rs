@@ -0,0 +1,107 @@
+use crate::{
+    gfx::raw::{
+        ScalingPolicy,
+        VirtualResolutionConfig,
+    },
+    Position,
+    Rectangle,
+    Size,
+};
+
+/// Placement of a virtual resolution inside the window, in window pixels from the top left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub virtual_size: Size<u32>,
+    pub window_size: Size<u32>,
+    /// Window pixels per virtual pixel, horizontally and vertically.
+    pub scale: (f32, f32),
+    /// Window area covered by the virtual resolution, reaching past the window edges when cropping.
+    pub area: Rectangle<f32>,
+}
+
+impl Viewport {
+    pub fn new(window_size: Size<u32>, config: VirtualResolutionConfig) -> Self {
+        let (window_width, window_height) = (window_size.width as f32, window_size.height as f32);
+        let (width, height) = (config.width.max(1) as f32, config.height.max(1) as f32);
+
+        let (fit_x, fit_y) = (window_width / width, window_height / height);
+
+        let scale = match config.policy {
+            ScalingPolicy::Stretch => (fit_x, fit_y),
+            ScalingPolicy::Fit => (fit_x.min(fit_y), fit_x.min(fit_y)),
+            ScalingPolicy::Fill => (fit_x.max(fit_y), fit_x.max(fit_y)),
+            ScalingPolicy::PixelPerfect => {
+                let scale = fit_x.min(fit_y).floor().max(1.0);
+
+                (scale, scale)
+            },
+        };
+
+        let size = (width * scale.0, height * scale.1);
+        let mut origin = ((window_width - size.0) / 2.0, (window_height - size.1) / 2.0);
+
+        // whole pixel offsets keep pixel perfect scaling crisp
+        if config.policy == ScalingPolicy::PixelPerfect {
+            origin = (origin.0.floor(), origin.1.floor());
+        }
+
+        Self {
+            virtual_size: Size {
+                width: config.width.max(1),
+                height: config.height.max(1),
+            },
+            window_size,
+            scale,
+            area: Rectangle::new(origin.0, origin.1, size.0, size.1),
+        }
+    }
+
+    /// Part of `area` inside the window, render passes into the frame are limited to it.
+    pub fn visible_area(&self) -> Rectangle<f32> {
+        let left = self.area.x.max(0.0);
+        let top = self.area.y.max(0.0);
+        let right = (self.area.x + self.area.width).min(self.window_size.width as f32);
+        let bottom = (self.area.y + self.area.height).min(self.window_size.height as f32);
+
+        Rectangle::new(left, top, (right - left).max(0.0), (bottom - top).max(0.0))
+    }
+
+    /// Window pixels to virtual pixels, both measured from the top left.
+    pub fn to_virtual(&self, position: Position<f32>) -> Position<f32> {
+        Position {
+            x: (position.x - self.area.x) / self.scale.0,
+            y: (position.y - self.area.y) / self.scale.1,
+        }
+    }
+
+    /// Virtual pixels to window pixels, both measured from the top left.
+    pub fn to_window(&self, position: Position<f32>) -> Position<f32> {
+        Position {
+            x: position.x * self.scale.0 + self.area.x,
+            y: position.y * self.scale.1 + self.area.y,
+        }
+    }
+
+    /// Maps clip space spanning the whole virtual resolution onto clip space spanning `visible_area`, so cropped
+    /// parts fall outside of it.
+    pub fn correction(&self) -> glam::Mat4 {
+        let visible = self.visible_area();
+
+        if visible.width <= 0.0 || visible.height <= 0.0 {
+            return glam::Mat4::IDENTITY;
+        }
+
+        let area_center = (self.area.x + self.area.width / 2.0, self.area.y + self.area.height / 2.0);
+        let visible_center = (visible.x + visible.width / 2.0, visible.y + visible.height / 2.0);
+
+        let scale = glam::Vec3::new(self.area.width / visible.width, self.area.height / visible.height, 1.0);
+        let translation = glam::Vec3::new(
+            (area_center.0 - visible_center.0) * 2.0 / visible.width,
+            // clip space grows upwards, window pixels downwards
+            (visible_center.1 - area_center.1) * 2.0 / visible.height,
+            0.0,
+        );
+
+        glam::Mat4::from_translation(translation) * glam::Mat4::from_scale(scale)
+    }
+}
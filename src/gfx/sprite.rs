@@ -3,29 +3,74 @@ use std::rc::Rc;
 use crate::{
     raw,
     Color,
+    Position,
     Rectangle,
+    TextureRegion,
 };
 
 #[derive(Debug)]
 pub struct Sprite {
     texture: Rc<raw::Texture>,
+    normal_map: Option<Rc<raw::Texture>>,
 
     target: Rectangle<f32>,
     color: Color<f32>,
+
+    source: Rectangle<f32>,
+    origin: Position<f32>,
+    rotation: f32,
+    scale: glam::Vec2,
+    flip_x: bool,
+    flip_y: bool,
+    layer_depth: f32,
 }
 
 impl Sprite {
     pub fn new(texture: raw::Texture) -> Self {
+        Self::from_rc(Rc::new(texture))
+    }
+
+    pub(crate) fn from_rc(texture: Rc<raw::Texture>) -> Self {
         let target = Rectangle::new(0.0, 0.0, texture.dimensions.0 as f32, texture.dimensions.1 as f32);
 
         Self {
-            texture: Rc::new(texture),
+            texture,
+            normal_map: None,
 
             target,
             color: Color::default(),
+
+            source: target,
+            origin: Position::default(),
+            rotation: 0.0,
+            scale: glam::Vec2::ONE,
+            flip_x: false,
+            flip_y: false,
+            layer_depth: 0.0,
         }
     }
 
+    /// Sprite covering `region`, with a target of the region's size. Trimmed regions are moved by their offset, so
+    /// the sprite lands where the untrimmed image would.
+    pub fn from_region(region: &TextureRegion) -> Self {
+        let mut sprite = Self::from_rc(Rc::clone(region.texture_rc()));
+        let offset = region.offset();
+
+        // the offset is measured down from the top of the untrimmed image, targets go up from its bottom
+        let y = region.original_size().height - offset.y - region.height();
+
+        sprite.set_region(region);
+        sprite.target = Rectangle::new(offset.x, y, region.width(), region.height());
+
+        sprite
+    }
+
+    /// Swaps texture and source for those of `region`, keeping the target and transform.
+    pub fn set_region(&mut self, region: &TextureRegion) {
+        self.texture = Rc::clone(region.texture_rc());
+        self.source = region.source();
+    }
+
     pub fn width(&self) -> u32 {
         self.texture.dimensions.0
     }
@@ -34,14 +79,25 @@ impl Sprite {
         self.texture.dimensions.1
     }
 
-    pub(crate) fn clone_texture_rc(&self) -> Rc<raw::Texture> {
-        Rc::clone(&self.texture)
+    pub(crate) fn texture_rc(&self) -> &Rc<raw::Texture> {
+        &self.texture
     }
 
     pub fn texture(&self) -> &raw::Texture {
         &self.texture
     }
 
+    pub(crate) fn normal_map_rc(&self) -> Option<&Rc<raw::Texture>> {
+        self.normal_map.as_ref()
+    }
+
+    /// Normal map drawn in the normal pass of `Lighting`, laid out like the sprite's texture and sampled at the same
+    /// source. Only the texture of `region` is used, so one region can serve every sprite of a sheet. Normal maps hold
+    /// vectors rather than colors, load them as `TextureFormat::Rgba8Unorm`.
+    pub fn set_normal_map(&mut self, region: Option<&TextureRegion>) {
+        self.normal_map = region.map(|region| Rc::clone(region.texture_rc()));
+    }
+
     pub fn target(&self) -> Rectangle<f32> {
         self.target
     }
@@ -65,4 +121,126 @@ impl Sprite {
     pub fn set_color(&mut self, color: Color<f32>) {
         self.color = color;
     }
+
+    /// Region of the texture in pixels, measured from its top left corner.
+    pub fn source(&self) -> Rectangle<f32> {
+        self.source
+    }
+
+    pub fn source_mut(&mut self) -> &mut Rectangle<f32> {
+        &mut self.source
+    }
+
+    pub fn set_source(&mut self, source: Rectangle<f32>) {
+        self.source = source;
+    }
+
+    /// Pivot for rotation and scale, relative to the bottom left corner of the target.
+    pub fn origin(&self) -> Position<f32> {
+        self.origin
+    }
+
+    pub fn origin_mut(&mut self) -> &mut Position<f32> {
+        &mut self.origin
+    }
+
+    pub fn set_origin(&mut self, origin: Position<f32>) {
+        self.origin = origin;
+    }
+
+    pub fn set_origin_center(&mut self) {
+        self.origin = Position {
+            x: self.target.width / 2.0,
+            y: self.target.height / 2.0,
+        };
+    }
+
+    /// Counter-clockwise rotation around the origin, in radians.
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    pub fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+    }
+
+    pub fn scale(&self) -> glam::Vec2 {
+        self.scale
+    }
+
+    pub fn set_scale(&mut self, scale: glam::Vec2) {
+        self.scale = scale;
+    }
+
+    pub fn flip_x(&self) -> bool {
+        self.flip_x
+    }
+
+    pub fn set_flip_x(&mut self, flip_x: bool) {
+        self.flip_x = flip_x;
+    }
+
+    pub fn flip_y(&self) -> bool {
+        self.flip_y
+    }
+
+    pub fn set_flip_y(&mut self, flip_y: bool) {
+        self.flip_y = flip_y;
+    }
+
+    /// Sort key for `SortMode::BackToFront` and `SortMode::FrontToBack`, 0 is the front and 1 the back.
+    pub fn layer_depth(&self) -> f32 {
+        self.layer_depth
+    }
+
+    pub fn set_layer_depth(&mut self, layer_depth: f32) {
+        self.layer_depth = layer_depth;
+    }
+
+    /// Transformed corners of the target: bottom left, top left, top right, bottom right. A scale mirroring one axis
+    /// swaps left and right, which keeps the winding of the quad so it is not culled, `uv` mirrors along with it.
+    pub fn corners(&self) -> [glam::Vec2; 4] {
+        let origin = glam::Vec2::new(self.target.x + self.origin.x, self.target.y + self.origin.y);
+        let rotation = glam::Vec2::from_angle(self.rotation);
+
+        let mut left = self.target.x - origin.x;
+        let bottom = self.target.y - origin.y;
+        let mut right = left + self.target.width;
+        let top = bottom + self.target.height;
+
+        if self.is_mirrored() {
+            std::mem::swap(&mut left, &mut right);
+        }
+
+        [(left, bottom), (left, top), (right, top), (right, bottom)]
+            .map(|(x, y)| origin + rotation.rotate(glam::Vec2::new(x, y) * self.scale))
+    }
+
+    /// Normalized texture coordinates of the source, mirrored when flipped.
+    pub(crate) fn uv(&self) -> Rectangle<f32> {
+        let (width, height) = (self.texture.dimensions.0 as f32, self.texture.dimensions.1 as f32);
+
+        let mut uv = Rectangle::new(
+            self.source.x / width,
+            self.source.y / height,
+            self.source.width / width,
+            self.source.height / height,
+        );
+
+        if self.flip_x != self.is_mirrored() {
+            uv.x += uv.width;
+            uv.width = -uv.width;
+        }
+
+        if self.flip_y {
+            uv.y += uv.height;
+            uv.height = -uv.height;
+        }
+
+        uv
+    }
+
+    fn is_mirrored(&self) -> bool {
+        self.scale.x * self.scale.y < 0.0
+    }
 }
@@ -0,0 +1,92 @@
+use std::rc::Rc;
+
+use crate::{
+    raw,
+    Position,
+    Rectangle,
+    Size,
+    Sprite,
+};
+
+/// Rectangle of a shared texture, in pixels measured from its top left corner.
+///
+/// Cloning is cheap, clones share the same texture.
+#[derive(Debug, Clone)]
+pub struct TextureRegion {
+    texture: Rc<raw::Texture>,
+    source: Rectangle<f32>,
+
+    // where the source sits within the image before transparent borders were trimmed off
+    offset: Position<f32>,
+    original_size: Size<f32>,
+}
+
+impl TextureRegion {
+    pub fn new(texture: raw::Texture, source: Rectangle<f32>) -> Self {
+        Self::from_rc(Rc::new(texture), source)
+    }
+
+    pub(crate) fn from_rc(texture: Rc<raw::Texture>, source: Rectangle<f32>) -> Self {
+        Self {
+            texture,
+            source,
+
+            offset: Position::default(),
+            original_size: Size {
+                width: source.width,
+                height: source.height,
+            },
+        }
+    }
+
+    /// Marks the region as cut out of an image of `original_size`, with its top left corner at `offset`.
+    pub(crate) fn with_trim(mut self, trim: Option<(Position<f32>, Size<f32>)>) -> Self {
+        if let Some((offset, original_size)) = trim {
+            self.offset = offset;
+            self.original_size = original_size;
+        }
+
+        self
+    }
+
+    pub(crate) fn texture_rc(&self) -> &Rc<raw::Texture> {
+        &self.texture
+    }
+
+    pub fn texture(&self) -> &raw::Texture {
+        &self.texture
+    }
+
+    pub fn source(&self) -> Rectangle<f32> {
+        self.source
+    }
+
+    pub fn width(&self) -> f32 {
+        self.source.width
+    }
+
+    pub fn height(&self) -> f32 {
+        self.source.height
+    }
+
+    /// Top left corner of the region within its untrimmed image, measured down from the top like the atlas data. Zero
+    /// for untrimmed regions.
+    pub fn offset(&self) -> Position<f32> {
+        self.offset
+    }
+
+    /// Size of the image before trimming, the region's own size for untrimmed regions.
+    pub fn original_size(&self) -> Size<f32> {
+        self.original_size
+    }
+
+    pub fn is_trimmed(&self) -> bool {
+        self.offset != Position::default()
+            || self.original_size.width != self.source.width
+            || self.original_size.height != self.source.height
+    }
+
+    pub fn sprite(&self) -> Sprite {
+        Sprite::from_region(self)
+    }
+}
@@ -0,0 +1,1008 @@
+mod tiled;
+
+use std::{
+    fs,
+    path::Path,
+    rc::Rc,
+};
+
+use hashbrown::HashMap;
+
+use super::batch::Quad;
+use crate::{
+    assets,
+    errors::TilemapError,
+    raw,
+    Batch,
+    Color,
+    GameTime,
+    Position,
+    Rectangle,
+    Size,
+    StaticBatch,
+    TextureRegion,
+};
+
+/// Tiles per side of the square chunks layers are culled and cached in.
+const CHUNK_SIZE: u32 = 16;
+
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+/// Flip flags and the hexagonal rotation flag, which is ignored.
+const FLAGS: u32 = 0xF000_0000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Orthogonal,
+    Isometric,
+}
+
+/// Custom property of a map, layer, object, tileset or tile.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Color(Color<f32>),
+    /// Path as written in the map, relative to it.
+    File(String),
+    /// Object id, 0 if unset.
+    Object(u32),
+}
+
+pub type Properties = HashMap<String, PropertyValue>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectShape {
+    Rectangle,
+    Ellipse,
+    Point,
+    /// Points relative to the object position.
+    Polygon(Vec<Position<f32>>),
+    Polyline(Vec<Position<f32>>),
+    Text(String),
+    /// Global tile id with flip flags, drawn with the map.
+    Tile(u32),
+}
+
+/// Object as placed in Tiled, in map pixels measured from the top left of the map. `TileMap::to_world` converts them.
+#[derive(Debug, Clone)]
+pub struct MapObject {
+    pub id: u32,
+    pub name: String,
+    pub class: String,
+    pub position: Position<f32>,
+    pub size: Size<f32>,
+    /// Clockwise rotation in degrees, as in Tiled.
+    pub rotation: f32,
+    pub visible: bool,
+    pub shape: ObjectShape,
+    pub properties: Properties,
+}
+
+#[derive(Debug, Clone)]
+pub struct TileLayer {
+    pub name: String,
+    pub visible: bool,
+    pub opacity: f32,
+    /// In map pixels, positive values move the layer right and down.
+    pub offset: Position<f32>,
+    pub tint: Color<f32>,
+    pub properties: Properties,
+    width: u32,
+    height: u32,
+    gids: Vec<u32>,
+}
+
+impl TileLayer {
+    /// Global tile id at the column `x` and row `y` from the top left, without flip flags. `None` for empty cells.
+    pub fn gid(&self, x: u32, y: u32) -> Option<u32> {
+        self.raw_gid(x, y).map(|gid| gid & !FLAGS).filter(|gid| *gid != 0)
+    }
+
+    /// Global tile id with the flip flags Tiled stores in its highest bits.
+    pub fn raw_gid(&self, x: u32, y: u32) -> Option<u32> {
+        (x < self.width && y < self.height).then(|| self.gids[(y * self.width + x) as usize])
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ObjectLayer {
+    pub name: String,
+    pub visible: bool,
+    pub opacity: f32,
+    pub offset: Position<f32>,
+    pub tint: Color<f32>,
+    pub properties: Properties,
+    pub objects: Vec<MapObject>,
+}
+
+/// Tile and object layers in drawing order. Groups are flattened into their layers, image layers are skipped.
+#[derive(Debug, Clone)]
+pub enum Layer {
+    Tiles(TileLayer),
+    Objects(ObjectLayer),
+}
+
+impl Layer {
+    pub fn name(&self) -> &str {
+        match self {
+            Layer::Tiles(layer) => &layer.name,
+            Layer::Objects(layer) => &layer.name,
+        }
+    }
+
+    pub fn properties(&self) -> &Properties {
+        match self {
+            Layer::Tiles(layer) => &layer.properties,
+            Layer::Objects(layer) => &layer.properties,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Tileset {
+    first_gid: u32,
+    name: String,
+    /// Indexed by local tile id, `None` for ids without an image in collection tilesets.
+    regions: Vec<Option<TextureRegion>>,
+    /// Local tile ids and durations in seconds.
+    animations: HashMap<u32, Vec<(u32, f32)>>,
+    tile_properties: HashMap<u32, Properties>,
+    properties: Properties,
+    offset: Position<f32>,
+}
+
+/// Static geometry of a chunk, or `None` until `TileMap::cache` records it.
+#[derive(Debug, Default)]
+struct Chunk {
+    bounds: Rectangle<f32>,
+    cache: Option<StaticBatch>,
+    /// Cells of animated tiles, drawn every frame next to the cache.
+    animated: Vec<u32>,
+}
+
+//
+
+/// Map loaded from Tiled. Row 0 is the top of the map and its bottom left corner sits at the world origin.
+#[derive(Debug)]
+pub struct TileMap {
+    orientation: Orientation,
+    width: u32,
+    height: u32,
+    tile_size: Size<f32>,
+    background: Option<Color<f32>>,
+    properties: Properties,
+
+    tilesets: Vec<Tileset>,
+    layers: Vec<Layer>,
+    /// Per layer, empty for object layers.
+    chunks: Vec<Vec<Chunk>>,
+
+    time: f32,
+}
+
+impl TileMap {
+    /// Loads a `.tmx` or a `.tmj`/`.json` map, with tilesets and images relative to it.
+    pub fn from_tiled_path(path: &Path) -> anyhow::Result<Self> {
+        let bytes = fs::read(path)?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("tmx") => Self::from_tmx_bytes(&bytes, directory),
+            _ => Self::from_tmj_bytes(&bytes, directory),
+        }
+    }
+
+    /// External tilesets and images are loaded relative to `directory`.
+    pub fn from_tmx_bytes(bytes: &[u8], directory: &Path) -> anyhow::Result<Self> {
+        Self::from_map(tiled::parse_tmx(bytes)?, directory)
+    }
+
+    /// External tilesets and images are loaded relative to `directory`.
+    pub fn from_tmj_bytes(bytes: &[u8], directory: &Path) -> anyhow::Result<Self> {
+        Self::from_map(tiled::parse_tmj(bytes)?, directory)
+    }
+
+    fn from_map(map: tiled::Map, directory: &Path) -> anyhow::Result<Self> {
+        let orientation = match map.orientation.as_str() {
+            "orthogonal" => Orientation::Orthogonal,
+            "isometric" => Orientation::Isometric,
+            orientation => return Err(anyhow::anyhow!(TilemapError::Unsupported(format!("{orientation} maps")))),
+        };
+
+        if map.infinite {
+            return Err(anyhow::anyhow!(TilemapError::Unsupported("infinite maps".to_string())));
+        }
+
+        let mut tilesets = map
+            .tilesets
+            .into_iter()
+            .map(|tileset| Self::load_tileset(tileset, directory))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        tilesets.sort_by_key(|tileset| tileset.first_gid);
+
+        let mut layers = vec![];
+        Self::flatten_layers(map.layers, (0.0, 0.0), 1.0, true, &mut layers)?;
+
+        for layer in &layers {
+            if let Layer::Tiles(layer) = layer {
+                if (layer.width, layer.height) != (map.width, map.height) {
+                    return Err(anyhow::anyhow!(TilemapError::Parse(format!(
+                        "layer `{}` is {}x{}, expected {}x{}",
+                        layer.name, layer.width, layer.height, map.width, map.height
+                    ))));
+                }
+            }
+        }
+
+        let mut tile_map = Self {
+            orientation,
+            width: map.width,
+            height: map.height,
+            tile_size: Size {
+                width: map.tile_width as f32,
+                height: map.tile_height as f32,
+            },
+            background: map.background_color.as_deref().map(parse_color).transpose()?,
+            properties: properties(map.properties)?,
+
+            tilesets,
+            layers,
+            chunks: vec![],
+
+            time: 0.0,
+        };
+
+        tile_map.chunks = (0..tile_map.layers.len())
+            .map(|layer| match &tile_map.layers[layer] {
+                Layer::Tiles(tiles) => (0..chunk_count(tiles)).map(|chunk| tile_map.chunk(layer, chunk)).collect(),
+                Layer::Objects(_) => vec![],
+            })
+            .collect();
+
+        Ok(tile_map)
+    }
+
+    fn load_tileset(tileset: tiled::Tileset, directory: &Path) -> anyhow::Result<Tileset> {
+        let (tileset, directory) = match &tileset.source {
+            Some(source) => {
+                let path = directory.join(source);
+                let bytes = fs::read(&path)?;
+
+                let mut external = match path.extension().and_then(|extension| extension.to_str()) {
+                    Some("tsx") => tiled::parse_tsx(&bytes)?,
+                    _ => tiled::parse_tsj(&bytes)?,
+                };
+                external.first_gid = tileset.first_gid;
+
+                (external, path.parent().unwrap_or(Path::new("")).to_path_buf())
+            },
+            None => (tileset, directory.to_path_buf()),
+        };
+
+        let mut regions = vec![];
+
+        match &tileset.image {
+            Some(image) => {
+                let texture = Rc::new(assets::load_texture_from_path(&directory.join(image))?);
+                let (width, height) = texture.dimensions;
+
+                let (tile_width, tile_height) = (tileset.tile_width, tileset.tile_height);
+                let step = (tile_width + tileset.spacing, tile_height + tileset.spacing);
+
+                let columns = match tileset.columns {
+                    0 => (width - tileset.margin * 2 + tileset.spacing) / step.0.max(1),
+                    columns => columns,
+                };
+                let count = match tileset.tile_count {
+                    0 => columns * ((height - tileset.margin * 2 + tileset.spacing) / step.1.max(1)),
+                    count => count,
+                };
+
+                regions = (0..count)
+                    .map(|id| {
+                        let x = tileset.margin + (id % columns.max(1)) * step.0;
+                        let y = tileset.margin + (id / columns.max(1)) * step.1;
+
+                        Some(TextureRegion::from_rc(
+                            Rc::clone(&texture),
+                            Rectangle::new(x as f32, y as f32, tile_width as f32, tile_height as f32),
+                        ))
+                    })
+                    .collect();
+            },
+            None => {
+                for tile in &tileset.tiles {
+                    let Some(image) = &tile.image else {
+                        continue;
+                    };
+
+                    let texture = Rc::new(assets::load_texture_from_path(&directory.join(image))?);
+                    let source = Rectangle::new(0.0, 0.0, texture.dimensions.0 as f32, texture.dimensions.1 as f32);
+
+                    if regions.len() <= tile.id as usize {
+                        regions.resize(tile.id as usize + 1, None);
+                    }
+                    regions[tile.id as usize] = Some(TextureRegion::from_rc(texture, source));
+                }
+            },
+        }
+
+        let mut animations = HashMap::new();
+        let mut tile_properties = HashMap::new();
+
+        for tile in tileset.tiles {
+            if !tile.animation.is_empty() {
+                let frames = tile
+                    .animation
+                    .iter()
+                    .map(|frame| (frame.tile_id, frame.duration as f32 / 1000.0))
+                    .collect();
+
+                animations.insert(tile.id, frames);
+            }
+
+            if !tile.properties.is_empty() {
+                tile_properties.insert(tile.id, properties(tile.properties)?);
+            }
+        }
+
+        Ok(Tileset {
+            first_gid: tileset.first_gid,
+            name: tileset.name,
+            regions,
+            animations,
+            tile_properties,
+            properties: properties(tileset.properties)?,
+            offset: Position {
+                x: tileset.tile_offset.x,
+                y: tileset.tile_offset.y,
+            },
+        })
+    }
+
+    /// Group offsets add up and opacities multiply into the layers they hold.
+    fn flatten_layers(
+        raw_layers: Vec<tiled::Layer>,
+        offset: (f32, f32),
+        opacity: f32,
+        visible: bool,
+        layers: &mut Vec<Layer>,
+    ) -> anyhow::Result<()> {
+        for layer in raw_layers {
+            let offset_total = (offset.0 + layer.offset_x, offset.1 + layer.offset_y);
+            let opacity_total = opacity * layer.opacity;
+            let visible_total = visible && layer.visible;
+
+            let tint = layer.tint_color.as_deref().map(parse_color).transpose()?.unwrap_or_default();
+            let position = Position {
+                x: offset_total.0,
+                y: offset_total.1,
+            };
+
+            match layer.kind.as_str() {
+                "tilelayer" => {
+                    let gids = tiled::decode(&layer)?;
+
+                    if gids.len() != (layer.width * layer.height) as usize {
+                        return Err(anyhow::anyhow!(TilemapError::Parse(format!(
+                            "layer `{}` has {} tiles, expected {}x{}",
+                            layer.name,
+                            gids.len(),
+                            layer.width,
+                            layer.height
+                        ))));
+                    }
+
+                    layers.push(Layer::Tiles(TileLayer {
+                        name: layer.name,
+                        visible: visible_total,
+                        opacity: opacity_total,
+                        offset: position,
+                        tint,
+                        properties: properties(layer.properties)?,
+                        width: layer.width,
+                        height: layer.height,
+                        gids,
+                    }));
+                },
+                "objectgroup" => {
+                    let objects = layer.objects.into_iter().map(object).collect::<anyhow::Result<_>>()?;
+
+                    layers.push(Layer::Objects(ObjectLayer {
+                        name: layer.name,
+                        visible: visible_total,
+                        opacity: opacity_total,
+                        offset: position,
+                        tint,
+                        properties: properties(layer.properties)?,
+                        objects,
+                    }));
+                },
+                "group" => Self::flatten_layers(layer.layers, offset_total, opacity_total, visible_total, layers)?,
+                kind => log::warn!("Skipping {} layer `{}`", kind, layer.name),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Width in tiles.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height in tiles.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn tile_size(&self) -> Size<f32> {
+        self.tile_size
+    }
+
+    /// Size of the area covered by the tile grid, in pixels.
+    pub fn pixel_size(&self) -> Size<f32> {
+        match self.orientation {
+            Orientation::Orthogonal => Size {
+                width: self.width as f32 * self.tile_size.width,
+                height: self.height as f32 * self.tile_size.height,
+            },
+            Orientation::Isometric => Size {
+                width: (self.width + self.height) as f32 * self.tile_size.width / 2.0,
+                height: (self.width + self.height) as f32 * self.tile_size.height / 2.0,
+            },
+        }
+    }
+
+    pub fn background(&self) -> Option<Color<f32>> {
+        self.background
+    }
+
+    pub fn properties(&self) -> &Properties {
+        &self.properties
+    }
+
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    pub fn layer(&self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|layer| layer.name() == name)
+    }
+
+    /// Names of the tilesets in order of their first global tile id.
+    pub fn tilesets(&self) -> impl Iterator<Item = &str> {
+        self.tilesets.iter().map(|tileset| tileset.name.as_str())
+    }
+
+    pub fn tileset_properties(&self, name: &str) -> Option<&Properties> {
+        self.tilesets
+            .iter()
+            .find(|tileset| tileset.name == name)
+            .map(|tileset| &tileset.properties)
+    }
+
+    /// Properties Tiled holds for the tile, if it has any.
+    pub fn tile_properties(&self, gid: u32) -> Option<&Properties> {
+        let (tileset, id) = self.lookup(gid)?;
+
+        tileset.tile_properties.get(&id)
+    }
+
+    /// Region of the tile's image, the first frame for animated tiles.
+    pub fn tile_region(&self, gid: u32) -> Option<&TextureRegion> {
+        let (tileset, id) = self.lookup(gid)?;
+
+        tileset.regions.get(id as usize)?.as_ref()
+    }
+
+    /// Shows the layer or hides it from `draw`.
+    pub fn set_layer_visible(&mut self, name: &str, visible: bool) {
+        for layer in self.layers.iter_mut().filter(|layer| layer.name() == name) {
+            match layer {
+                Layer::Tiles(layer) => layer.visible = visible,
+                Layer::Objects(layer) => layer.visible = visible,
+            }
+        }
+    }
+
+    /// Replaces a cell of a tile layer with `gid`, which may carry flip flags, or 0 to clear it. The chunk holding the
+    /// cell is drawn without its cache until the next `cache`.
+    pub fn set_tile(&mut self, layer: usize, x: u32, y: u32, gid: u32) {
+        let Some(Layer::Tiles(tiles)) = self.layers.get_mut(layer) else {
+            return;
+        };
+
+        if x >= tiles.width || y >= tiles.height {
+            return;
+        }
+
+        tiles.gids[(y * tiles.width + x) as usize] = gid;
+
+        let chunk = chunk_index(tiles, x, y);
+        self.chunks[layer][chunk] = self.chunk(layer, chunk);
+    }
+
+    /// Converts a position in map pixels, as Tiled shows them and stores objects, to world coordinates.
+    pub fn to_world(&self, position: Position<f32>) -> Position<f32> {
+        let height = self.pixel_size().height;
+
+        match self.orientation {
+            Orientation::Orthogonal => Position {
+                x: position.x,
+                y: height - position.y,
+            },
+            // isometric objects are placed in tile units scaled by the tile height on both axes
+            Orientation::Isometric => {
+                let (column, row) = (position.x / self.tile_size.height, position.y / self.tile_size.height);
+
+                Position {
+                    x: (column - row) * self.tile_size.width / 2.0 + self.height as f32 * self.tile_size.width / 2.0,
+                    y: height - (column + row) * self.tile_size.height / 2.0,
+                }
+            },
+        }
+    }
+
+    pub fn update(&mut self, game_time: &GameTime) {
+        self.advance(game_time.delta());
+    }
+
+    /// Moves animated tiles forward by `delta` seconds.
+    pub fn advance(&mut self, delta: f32) {
+        self.time += delta;
+    }
+
+    //
+
+    /// Records the tile layers into static batches, so `draw` only binds them instead of batching every tile. Animated
+    /// tiles are left out and drawn every frame.
+    ///
+    /// Isometric maps are not cached, their tiles overlap across chunks and have to be drawn back to front in one go.
+    pub fn cache(&mut self, batch: &mut Batch) -> anyhow::Result<()> {
+        if self.orientation == Orientation::Isometric {
+            return Ok(());
+        }
+
+        for layer in 0..self.layers.len() {
+            for chunk in 0..self.chunks[layer].len() {
+                if self.chunks[layer][chunk].cache.is_some() {
+                    continue;
+                }
+
+                let cache = batch.record(|batch| self.draw_chunk(batch, layer, chunk, false))?;
+                self.chunks[layer][chunk].cache = Some(cache);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draws the visible layers in order, skipping chunks and tile objects outside of `area`, such as `Camera2D::visible_area`. Must be
+    /// called between `Batch::begin` and `Batch::end`.
+    pub fn draw(&self, batch: &mut Batch, area: Rectangle<f32>) -> anyhow::Result<()> {
+        for (index, layer) in self.layers.iter().enumerate() {
+            match layer {
+                // isometric rows draw back to front as they go down the whole layer, chunks only cull
+                Layer::Tiles(layer) if layer.visible && self.orientation == Orientation::Isometric => {
+                    let visible = self.chunks[index]
+                        .iter()
+                        .map(|chunk| overlaps(chunk.bounds, area))
+                        .collect::<Vec<_>>();
+
+                    for cell in 0..layer.gids.len() as u32 {
+                        if visible[chunk_index(layer, cell % layer.width, cell / layer.width)] {
+                            self.draw_tile(batch, layer, cell);
+                        }
+                    }
+                },
+                Layer::Tiles(layer) if layer.visible => {
+                    for (chunk_index, chunk) in self.chunks[index].iter().enumerate() {
+                        if !overlaps(chunk.bounds, area) {
+                            continue;
+                        }
+
+                        match &chunk.cache {
+                            Some(cache) => {
+                                batch.draw_static(cache)?;
+
+                                for &cell in &chunk.animated {
+                                    self.draw_tile(batch, layer, cell);
+                                }
+                            },
+                            None => self.draw_chunk(batch, index, chunk_index, true),
+                        }
+                    }
+                },
+                Layer::Objects(layer) if layer.visible => {
+                    let color = layer_color(layer.tint, layer.opacity);
+
+                    for object in layer.objects.iter().filter(|object| object.visible) {
+                        if let ObjectShape::Tile(gid) = object.shape {
+                            self.draw_object(batch, object, gid, layer.offset, color, area);
+                        }
+                    }
+                },
+                _ => {},
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_chunk(&self, batch: &mut Batch, layer: usize, chunk: usize, animated: bool) {
+        let Layer::Tiles(tiles) = &self.layers[layer] else {
+            return;
+        };
+
+        for cell in chunk_cells(tiles, chunk) {
+            if animated || !self.is_animated(tiles.gids[cell as usize]) {
+                self.draw_tile(batch, tiles, cell);
+            }
+        }
+    }
+
+    fn draw_tile(&self, batch: &mut Batch, layer: &TileLayer, cell: u32) {
+        let gid = layer.gids[cell as usize];
+
+        if let Some((texture, quad)) = self.tile_quad(layer, cell, gid) {
+            batch.draw_texture(&texture, quad, 0.0);
+        }
+    }
+
+    fn draw_object(&self, batch: &mut Batch, object: &MapObject, gid: u32, offset: Position<f32>, color: Color<f32>, area: Rectangle<f32>) {
+        let Some((texture, region, flips)) = self.frame(gid) else {
+            return;
+        };
+
+        // tile objects are anchored at their bottom left corner, on isometric maps at their bottom center
+        let anchor = match self.orientation {
+            Orientation::Orthogonal => 0.0,
+            Orientation::Isometric => -object.size.width / 2.0,
+        };
+
+        let position = self.to_world(Position {
+            x: object.position.x + offset.x,
+            y: object.position.y + offset.y,
+        });
+        let position = glam::Vec2::new(position.x, position.y);
+
+        let size = glam::Vec2::new(object.size.width, object.size.height);
+        let rotation = glam::Vec2::from_angle(-object.rotation.to_radians());
+
+        let corners = [(0.0, 0.0), (0.0, size.y), (size.x, size.y), (size.x, 0.0)]
+            .map(|(x, y)| position + rotation.rotate(glam::Vec2::new(x + anchor, y)));
+
+        if !overlaps(corner_bounds(&corners), area) {
+            return;
+        }
+
+        batch.draw_texture(&texture, flip(Quad::textured(corners, region, color), flips), 0.0);
+    }
+
+    /// Texture, quad and color of the tile in `cell`, at its current animation frame.
+    fn tile_quad(&self, layer: &TileLayer, cell: u32, gid: u32) -> Option<(Rc<raw::Texture>, Quad)> {
+        let (texture, uv, flips) = self.frame(gid)?;
+        let (tileset, _) = self.lookup(gid)?;
+        let region = self.tile_region(gid)?;
+
+        let (column, row) = ((cell % layer.width) as f32, (cell / layer.width) as f32);
+        let (tile_width, tile_height) = (self.tile_size.width, self.tile_size.height);
+
+        // bottom left of the cell in map pixels, images larger than a cell grow up and to the right
+        let (x, bottom) = match self.orientation {
+            Orientation::Orthogonal => (column * tile_width, (row + 1.0) * tile_height),
+            Orientation::Isometric => (
+                (column - row) * tile_width / 2.0 + (self.height as f32 - 1.0) * tile_width / 2.0,
+                (column + row + 2.0) * tile_height / 2.0,
+            ),
+        };
+
+        let target = Rectangle::new(
+            x + layer.offset.x + tileset.offset.x,
+            self.pixel_size().height - bottom - layer.offset.y - tileset.offset.y,
+            region.width(),
+            region.height(),
+        );
+
+        Some((
+            texture,
+            flip(
+                Quad::textured(Batch::corners(target), uv, layer_color(layer.tint, layer.opacity)),
+                flips,
+            ),
+        ))
+    }
+
+    /// Texture, normalized texture coordinates and flip flags of the animation frame shown for `gid`.
+    fn frame(&self, gid: u32) -> Option<(Rc<raw::Texture>, Rectangle<f32>, u32)> {
+        let (tileset, id) = self.lookup(gid)?;
+
+        let id = match tileset.animations.get(&id) {
+            Some(frames) => {
+                let total = frames.iter().map(|(_, duration)| duration).sum::<f32>();
+                let mut time = if total > 0.0 {
+                    self.time % total
+                } else {
+                    0.0
+                };
+
+                frames
+                    .iter()
+                    .find(|(_, duration)| {
+                        time -= duration;
+                        time < 0.0
+                    })
+                    .unwrap_or(&frames[0])
+                    .0
+            },
+            None => id,
+        };
+
+        let region = tileset.regions.get(id as usize)?.as_ref()?;
+        let (width, height) = (region.texture().dimensions.0 as f32, region.texture().dimensions.1 as f32);
+        let source = region.source();
+
+        Some((
+            Rc::clone(region.texture_rc()),
+            Rectangle::new(source.x / width, source.y / height, source.width / width, source.height / height),
+            gid & FLAGS,
+        ))
+    }
+
+    fn lookup(&self, gid: u32) -> Option<(&Tileset, u32)> {
+        let gid = gid & !FLAGS;
+
+        if gid == 0 {
+            return None;
+        }
+
+        let tileset = self.tilesets.iter().rev().find(|tileset| tileset.first_gid <= gid)?;
+
+        Some((tileset, gid - tileset.first_gid))
+    }
+
+    fn is_animated(&self, gid: u32) -> bool {
+        self.lookup(gid).is_some_and(|(tileset, id)| tileset.animations.contains_key(&id))
+    }
+
+    //
+
+    /// Chunk without a cache, bounding every tile it holds.
+    fn chunk(&self, layer: usize, chunk: usize) -> Chunk {
+        let Layer::Tiles(tiles) = &self.layers[layer] else {
+            return Chunk::default();
+        };
+
+        let mut bounds: Option<Rectangle<f32>> = None;
+        let mut animated = vec![];
+
+        for cell in chunk_cells(tiles, chunk) {
+            let gid = tiles.gids[cell as usize];
+
+            let Some((_, quad)) = self.tile_quad(tiles, cell, gid) else {
+                continue;
+            };
+
+            if self.is_animated(gid) {
+                animated.push(cell);
+            }
+
+            let tile = corner_bounds(&quad.corners);
+
+            bounds = Some(match bounds {
+                Some(bounds) => union(bounds, tile),
+                None => tile,
+            });
+        }
+
+        Chunk {
+            bounds: bounds.unwrap_or_default(),
+            cache: None,
+            animated,
+        }
+    }
+}
+
+// chunks split each tile layer by its own dimensions, `from_map` makes sure those are the map's
+
+fn chunk_columns(layer: &TileLayer) -> u32 {
+    layer.width.div_ceil(CHUNK_SIZE)
+}
+
+fn chunk_count(layer: &TileLayer) -> usize {
+    (chunk_columns(layer) * layer.height.div_ceil(CHUNK_SIZE)) as usize
+}
+
+fn chunk_index(layer: &TileLayer, x: u32, y: u32) -> usize {
+    ((y / CHUNK_SIZE) * chunk_columns(layer) + x / CHUNK_SIZE) as usize
+}
+
+/// Cells of the chunk in row order.
+fn chunk_cells(layer: &TileLayer, chunk: usize) -> impl Iterator<Item = u32> + '_ {
+    let (x, y) = (
+        (chunk as u32 % chunk_columns(layer)) * CHUNK_SIZE,
+        (chunk as u32 / chunk_columns(layer)) * CHUNK_SIZE,
+    );
+
+    (y..(y + CHUNK_SIZE).min(layer.height))
+        .flat_map(move |row| (x..(x + CHUNK_SIZE).min(layer.width)).map(move |column| row * layer.width + column))
+}
+
+/// Tiled flips are applied to the texture coordinates, diagonally first, then horizontally and vertically.
+fn flip(mut quad: Quad, flips: u32) -> Quad {
+    let [bottom_left, top_left, top_right, bottom_right] = quad.texcoords;
+
+    if flips & FLIPPED_DIAGONALLY != 0 {
+        quad.texcoords = [top_right, top_left, bottom_left, bottom_right];
+    }
+
+    let [bottom_left, top_left, top_right, bottom_right] = quad.texcoords;
+
+    if flips & FLIPPED_HORIZONTALLY != 0 {
+        quad.texcoords = [bottom_right, top_right, top_left, bottom_left];
+    }
+
+    let [bottom_left, top_left, top_right, bottom_right] = quad.texcoords;
+
+    if flips & FLIPPED_VERTICALLY != 0 {
+        quad.texcoords = [top_left, bottom_left, bottom_right, top_right];
+    }
+
+    quad
+}
+
+fn layer_color(tint: Color<f32>, opacity: f32) -> Color<f32> {
+    Color::new(tint.r, tint.g, tint.b, tint.a * opacity)
+}
+
+fn overlaps(a: Rectangle<f32>, b: Rectangle<f32>) -> bool {
+    a.x < b.x + b.width && b.x < a.x + a.width && a.y < b.y + b.height && b.y < a.y + a.height
+}
+
+fn corner_bounds(corners: &[glam::Vec2; 4]) -> Rectangle<f32> {
+    let min = corners.iter().copied().reduce(glam::Vec2::min).unwrap();
+    let max = corners.iter().copied().reduce(glam::Vec2::max).unwrap();
+
+    Rectangle::new(min.x, min.y, max.x - min.x, max.y - min.y)
+}
+
+fn union(a: Rectangle<f32>, b: Rectangle<f32>) -> Rectangle<f32> {
+    let (x, y) = (a.x.min(b.x), a.y.min(b.y));
+
+    Rectangle::new(
+        x,
+        y,
+        (a.x + a.width).max(b.x + b.width) - x,
+        (a.y + a.height).max(b.y + b.height) - y,
+    )
+}
+
+/// Tiled colors are `#RRGGBB` or `#AARRGGBB`.
+fn parse_color(color: &str) -> Result<Color<f32>, TilemapError> {
+    let hex = color.trim_start_matches('#');
+    let invalid = || TilemapError::Parse(format!("invalid color `{color}`"));
+
+    let value = u32::from_str_radix(hex, 16).map_err(|_| invalid())?;
+
+    let (a, rgb) = match hex.len() {
+        6 => (255, value),
+        8 => (value >> 24, value & 0xFF_FFFF),
+        _ => return Err(invalid()),
+    };
+
+    Ok(Color::new(
+        (rgb >> 16 & 0xFF) as f32 / 255.0,
+        (rgb >> 8 & 0xFF) as f32 / 255.0,
+        (rgb & 0xFF) as f32 / 255.0,
+        a as f32 / 255.0,
+    ))
+}
+
+fn properties(raw: Vec<tiled::Property>) -> anyhow::Result<Properties> {
+    raw.into_iter()
+        .map(|property| {
+            let invalid = || TilemapError::Parse(format!("invalid {} property `{}`", property.kind, property.name));
+            let value = &property.value;
+            let text = value.as_str();
+
+            let value = match property.kind.as_str() {
+                "bool" => PropertyValue::Bool(value.as_bool().or_else(|| text.map(|text| text == "true")).ok_or_else(invalid)?),
+                "int" => PropertyValue::Int(
+                    value
+                        .as_i64()
+                        .or_else(|| text.and_then(|text| text.parse().ok()))
+                        .ok_or_else(invalid)?,
+                ),
+                "float" => PropertyValue::Float(
+                    value
+                        .as_f64()
+                        .or_else(|| text.and_then(|text| text.parse().ok()))
+                        .ok_or_else(invalid)?,
+                ),
+                "color" => match text {
+                    Some("") | None => PropertyValue::Color(Color::new(0.0, 0.0, 0.0, 0.0)),
+                    Some(text) => PropertyValue::Color(parse_color(text)?),
+                },
+                "file" => PropertyValue::File(text.unwrap_or_default().to_string()),
+                "object" => PropertyValue::Object(
+                    value
+                        .as_u64()
+                        .map(|id| id as u32)
+                        .or_else(|| text.and_then(|text| text.parse().ok()))
+                        .ok_or_else(invalid)?,
+                ),
+                // class properties are kept as their JSON
+                _ => PropertyValue::String(match value {
+                    serde_json::Value::String(text) => text.clone(),
+                    value => value.to_string(),
+                }),
+            };
+
+            Ok((property.name, value))
+        })
+        .collect()
+}
+
+fn object(object: tiled::Object) -> anyhow::Result<MapObject> {
+    let points = |points: Vec<tiled::Point>| {
+        points
+            .into_iter()
+            .map(|point| Position {
+                x: point.x,
+                y: point.y,
+            })
+            .collect()
+    };
+
+    let shape = match object {
+        tiled::Object {
+            gid: Some(gid), ..
+        } => ObjectShape::Tile(gid),
+        tiled::Object {
+            ellipse: true, ..
+        } => ObjectShape::Ellipse,
+        tiled::Object {
+            point: true, ..
+        } => ObjectShape::Point,
+        tiled::Object {
+            polygon: Some(ref polygon),
+            ..
+        } => ObjectShape::Polygon(points(polygon.clone())),
+        tiled::Object {
+            polyline: Some(ref polyline),
+            ..
+        } => ObjectShape::Polyline(points(polyline.clone())),
+        tiled::Object {
+            text: Some(ref text),
+            ..
+        } => ObjectShape::Text(text.text.clone()),
+        _ => ObjectShape::Rectangle,
+    };
+
+    Ok(MapObject {
+        id: object.id,
+        name: object.name,
+        class: object.class,
+        position: Position {
+            x: object.x,
+            y: object.y,
+        },
+        size: Size {
+            width: object.width,
+            height: object.height,
+        },
+        rotation: object.rotation,
+        visible: object.visible,
+        shape,
+        properties: properties(object.properties)?,
+    })
+}
@@ -0,0 +1,459 @@
+use std::io::Read;
+
+use base64::Engine;
+use serde::Deserialize;
+
+use crate::errors::TilemapError;
+
+// Tiled writes maps as JSON (.tmj) or XML (.tmx), both are read into these. XML attributes hold strings, so property
+// values are kept as JSON values and converted once their type is known.
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Map {
+    pub orientation: String,
+    pub width: u32,
+    pub height: u32,
+    #[serde(rename = "tilewidth")]
+    pub tile_width: u32,
+    #[serde(rename = "tileheight")]
+    pub tile_height: u32,
+    #[serde(default)]
+    pub infinite: bool,
+    #[serde(default, rename = "backgroundcolor")]
+    pub background_color: Option<String>,
+    #[serde(default)]
+    pub layers: Vec<Layer>,
+    #[serde(default)]
+    pub tilesets: Vec<Tileset>,
+    #[serde(default)]
+    pub properties: Vec<Property>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Layer {
+    /// `tilelayer`, `objectgroup`, `imagelayer` or `group`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "yes")]
+    pub visible: bool,
+    #[serde(default = "one")]
+    pub opacity: f32,
+    #[serde(default, rename = "offsetx")]
+    pub offset_x: f32,
+    #[serde(default, rename = "offsety")]
+    pub offset_y: f32,
+    #[serde(default, rename = "tintcolor")]
+    pub tint_color: Option<String>,
+    #[serde(default)]
+    pub width: u32,
+    #[serde(default)]
+    pub height: u32,
+    pub data: Option<Data>,
+    pub encoding: Option<String>,
+    pub compression: Option<String>,
+    #[serde(default)]
+    pub objects: Vec<Object>,
+    #[serde(default)]
+    pub layers: Vec<Layer>,
+    #[serde(default)]
+    pub properties: Vec<Property>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Data {
+    Gids(Vec<u32>),
+    Encoded(String),
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct Object {
+    #[serde(default)]
+    pub id: u32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, rename = "type", alias = "class")]
+    pub class: String,
+    #[serde(default)]
+    pub x: f32,
+    #[serde(default)]
+    pub y: f32,
+    #[serde(default)]
+    pub width: f32,
+    #[serde(default)]
+    pub height: f32,
+    #[serde(default)]
+    pub rotation: f32,
+    #[serde(default = "yes")]
+    pub visible: bool,
+    pub gid: Option<u32>,
+    #[serde(default)]
+    pub ellipse: bool,
+    #[serde(default)]
+    pub point: bool,
+    pub polygon: Option<Vec<Point>>,
+    pub polyline: Option<Vec<Point>>,
+    pub text: Option<Text>,
+    #[serde(default)]
+    pub properties: Vec<Property>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub(crate) struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Text {
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct Tileset {
+    /// Missing in external tilesets, the map referencing them holds it.
+    #[serde(default, rename = "firstgid")]
+    pub first_gid: u32,
+    /// External tileset, relative to the map.
+    pub source: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, rename = "tilewidth")]
+    pub tile_width: u32,
+    #[serde(default, rename = "tileheight")]
+    pub tile_height: u32,
+    #[serde(default, rename = "tilecount")]
+    pub tile_count: u32,
+    #[serde(default)]
+    pub columns: u32,
+    #[serde(default)]
+    pub margin: u32,
+    #[serde(default)]
+    pub spacing: u32,
+    /// Image tilesets only, collections give every tile its own image.
+    pub image: Option<String>,
+    #[serde(default, rename = "tileoffset")]
+    pub tile_offset: Point,
+    #[serde(default)]
+    pub tiles: Vec<Tile>,
+    #[serde(default)]
+    pub properties: Vec<Property>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct Tile {
+    pub id: u32,
+    pub image: Option<String>,
+    #[serde(default)]
+    pub animation: Vec<Frame>,
+    #[serde(default)]
+    pub properties: Vec<Property>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Frame {
+    #[serde(rename = "tileid")]
+    pub tile_id: u32,
+    /// In milliseconds.
+    pub duration: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Property {
+    pub name: String,
+    #[serde(default = "string", rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub value: serde_json::Value,
+}
+
+fn yes() -> bool {
+    true
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+fn string() -> String {
+    "string".to_string()
+}
+
+//
+
+pub(crate) fn parse_tmj(json: &[u8]) -> anyhow::Result<Map> {
+    serde_json::from_slice(json).map_err(|err| anyhow::anyhow!(TilemapError::Parse(err.to_string())))
+}
+
+pub(crate) fn parse_tsj(json: &[u8]) -> anyhow::Result<Tileset> {
+    serde_json::from_slice(json).map_err(|err| anyhow::anyhow!(TilemapError::Parse(err.to_string())))
+}
+
+pub(crate) fn parse_tmx(xml: &[u8]) -> anyhow::Result<Map> {
+    let text = std::str::from_utf8(xml).map_err(|err| TilemapError::Parse(err.to_string()))?;
+    let document = roxmltree::Document::parse(text).map_err(|err| TilemapError::Parse(err.to_string()))?;
+    let map = document.root_element();
+
+    if map.tag_name().name() != "map" {
+        return Err(anyhow::anyhow!(TilemapError::Parse("root element is not <map>".to_string())));
+    }
+
+    Ok(Map {
+        orientation: map.attribute("orientation").unwrap_or("orthogonal").to_string(),
+        width: attribute(map, "width")?.unwrap_or(0),
+        height: attribute(map, "height")?.unwrap_or(0),
+        tile_width: attribute(map, "tilewidth")?.unwrap_or(0),
+        tile_height: attribute(map, "tileheight")?.unwrap_or(0),
+        infinite: attribute::<u32>(map, "infinite")?.unwrap_or(0) == 1,
+        background_color: map.attribute("backgroundcolor").map(str::to_string),
+        layers: xml_layers(map)?,
+        tilesets: children(map, "tileset").map(xml_tileset).collect::<anyhow::Result<_>>()?,
+        properties: xml_properties(map),
+    })
+}
+
+pub(crate) fn parse_tsx(xml: &[u8]) -> anyhow::Result<Tileset> {
+    let text = std::str::from_utf8(xml).map_err(|err| TilemapError::Parse(err.to_string()))?;
+    let document = roxmltree::Document::parse(text).map_err(|err| TilemapError::Parse(err.to_string()))?;
+
+    xml_tileset(document.root_element())
+}
+
+/// Global tile ids of a layer, with their flip flags. Empty cells are 0.
+pub(crate) fn decode(layer: &Layer) -> anyhow::Result<Vec<u32>> {
+    let encoded = match &layer.data {
+        None => return Ok(vec![]),
+        Some(Data::Gids(gids)) => return Ok(gids.clone()),
+        Some(Data::Encoded(encoded)) => encoded,
+    };
+
+    if layer.encoding.as_deref() != Some("base64") {
+        return Err(anyhow::anyhow!(TilemapError::Unsupported(format!(
+            "layer encoding {:?}",
+            layer.encoding
+        ))));
+    }
+
+    let compact = encoded.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(compact)
+        .map_err(|err| TilemapError::Parse(err.to_string()))?;
+
+    let mut decompressed = vec![];
+
+    match layer.compression.as_deref() {
+        None | Some("") => decompressed = bytes,
+        Some("zlib") => {
+            flate2::read::ZlibDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+        },
+        Some("gzip") => {
+            flate2::read::GzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+        },
+        Some(compression) => {
+            return Err(anyhow::anyhow!(TilemapError::Unsupported(format!("{compression} compression"))));
+        },
+    }
+
+    Ok(decompressed
+        .chunks_exact(4)
+        .map(|gid| u32::from_le_bytes([gid[0], gid[1], gid[2], gid[3]]))
+        .collect())
+}
+
+//
+
+fn children<'a, 'input: 'a>(node: roxmltree::Node<'a, 'input>, name: &'static str) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+    node.children().filter(move |child| child.has_tag_name(name))
+}
+
+fn attribute<T: std::str::FromStr>(node: roxmltree::Node, name: &str) -> Result<Option<T>, TilemapError> {
+    node.attribute(name)
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| TilemapError::Parse(format!("invalid {name} `{value}` on <{}>", node.tag_name().name())))
+        })
+        .transpose()
+}
+
+fn xml_properties(node: roxmltree::Node) -> Vec<Property> {
+    children(node, "properties")
+        .flat_map(|properties| children(properties, "property"))
+        .map(|property| Property {
+            name: property.attribute("name").unwrap_or_default().to_string(),
+            kind: property.attribute("type").unwrap_or("string").to_string(),
+            // multi line strings are written as the element's text
+            value: serde_json::Value::String(
+                property
+                    .attribute("value")
+                    .or_else(|| property.text())
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+        })
+        .collect()
+}
+
+fn xml_layers(node: roxmltree::Node) -> anyhow::Result<Vec<Layer>> {
+    node.children()
+        .filter(|child| ["layer", "objectgroup", "imagelayer", "group"].contains(&child.tag_name().name()))
+        .map(|child| {
+            let kind = match child.tag_name().name() {
+                "layer" => "tilelayer",
+                kind => kind,
+            };
+
+            let data = children(child, "data").next();
+
+            let (data, encoding, compression) = match data {
+                None => (None, None, None),
+                Some(data) => {
+                    let encoding = data.attribute("encoding").map(str::to_string);
+                    let compression = data.attribute("compression").map(str::to_string);
+
+                    if children(data, "chunk").next().is_some() {
+                        return Err(anyhow::anyhow!(TilemapError::Unsupported("infinite maps".to_string())));
+                    }
+
+                    let gids = match encoding.as_deref() {
+                        Some("csv") => Data::Gids(
+                            data.text()
+                                .unwrap_or_default()
+                                .split(',')
+                                .map(|gid| {
+                                    gid.trim()
+                                        .parse()
+                                        .map_err(|_| TilemapError::Parse(format!("invalid gid `{}`", gid.trim())))
+                                })
+                                .collect::<Result<_, _>>()?,
+                        ),
+                        Some(_) => Data::Encoded(data.text().unwrap_or_default().to_string()),
+                        None => Data::Gids(
+                            children(data, "tile")
+                                .map(|tile| attribute(tile, "gid").map(Option::unwrap_or_default))
+                                .collect::<Result<_, _>>()?,
+                        ),
+                    };
+
+                    (Some(gids), encoding, compression)
+                },
+            };
+
+            Ok(Layer {
+                kind: kind.to_string(),
+                name: child.attribute("name").unwrap_or_default().to_string(),
+                visible: attribute::<u32>(child, "visible")?.unwrap_or(1) == 1,
+                opacity: attribute(child, "opacity")?.unwrap_or(1.0),
+                offset_x: attribute(child, "offsetx")?.unwrap_or(0.0),
+                offset_y: attribute(child, "offsety")?.unwrap_or(0.0),
+                tint_color: child.attribute("tintcolor").map(str::to_string),
+                width: attribute(child, "width")?.unwrap_or(0),
+                height: attribute(child, "height")?.unwrap_or(0),
+                data,
+                encoding: encoding.filter(|encoding| encoding != "csv"),
+                compression,
+                objects: children(child, "object").map(xml_object).collect::<anyhow::Result<_>>()?,
+                layers: xml_layers(child)?,
+                properties: xml_properties(child),
+            })
+        })
+        .collect()
+}
+
+fn xml_object(node: roxmltree::Node) -> anyhow::Result<Object> {
+    let points = |name: &'static str| -> anyhow::Result<Option<Vec<Point>>> {
+        let Some(points) = children(node, name).next().and_then(|points| points.attribute("points")) else {
+            return Ok(None);
+        };
+
+        points
+            .split_whitespace()
+            .map(|point| {
+                let (x, y) = point
+                    .split_once(',')
+                    .ok_or_else(|| TilemapError::Parse(format!("invalid point `{point}`")))?;
+
+                Ok(Point {
+                    x: x.parse().map_err(|_| TilemapError::Parse(format!("invalid point `{point}`")))?,
+                    y: y.parse().map_err(|_| TilemapError::Parse(format!("invalid point `{point}`")))?,
+                })
+            })
+            .collect::<anyhow::Result<_>>()
+            .map(Some)
+    };
+
+    Ok(Object {
+        id: attribute(node, "id")?.unwrap_or(0),
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        class: node.attribute("type").or(node.attribute("class")).unwrap_or_default().to_string(),
+        x: attribute(node, "x")?.unwrap_or(0.0),
+        y: attribute(node, "y")?.unwrap_or(0.0),
+        width: attribute(node, "width")?.unwrap_or(0.0),
+        height: attribute(node, "height")?.unwrap_or(0.0),
+        rotation: attribute(node, "rotation")?.unwrap_or(0.0),
+        visible: attribute::<u32>(node, "visible")?.unwrap_or(1) == 1,
+        gid: attribute(node, "gid")?,
+        ellipse: children(node, "ellipse").next().is_some(),
+        point: children(node, "point").next().is_some(),
+        polygon: points("polygon")?,
+        polyline: points("polyline")?,
+        text: children(node, "text").next().map(|text| Text {
+            text: text.text().unwrap_or_default().to_string(),
+        }),
+        properties: xml_properties(node),
+    })
+}
+
+fn xml_tileset(node: roxmltree::Node) -> anyhow::Result<Tileset> {
+    let tiles = children(node, "tile")
+        .map(|tile| {
+            Ok(Tile {
+                id: attribute(tile, "id")?.unwrap_or(0),
+                image: children(tile, "image")
+                    .next()
+                    .and_then(|image| image.attribute("source"))
+                    .map(str::to_string),
+                animation: children(tile, "animation")
+                    .flat_map(|animation| children(animation, "frame"))
+                    .map(|frame| {
+                        Ok(Frame {
+                            tile_id: attribute(frame, "tileid")?.unwrap_or(0),
+                            duration: attribute(frame, "duration")?.unwrap_or(0),
+                        })
+                    })
+                    .collect::<Result<_, TilemapError>>()?,
+                properties: xml_properties(tile),
+            })
+        })
+        .collect::<Result<_, TilemapError>>()?;
+
+    let tile_offset = match children(node, "tileoffset").next() {
+        Some(offset) => Point {
+            x: attribute(offset, "x")?.unwrap_or(0.0),
+            y: attribute(offset, "y")?.unwrap_or(0.0),
+        },
+        None => Point::default(),
+    };
+
+    Ok(Tileset {
+        first_gid: attribute(node, "firstgid")?.unwrap_or(0),
+        source: node.attribute("source").map(str::to_string),
+        name: node.attribute("name").unwrap_or_default().to_string(),
+        tile_width: attribute(node, "tilewidth")?.unwrap_or(0),
+        tile_height: attribute(node, "tileheight")?.unwrap_or(0),
+        tile_count: attribute(node, "tilecount")?.unwrap_or(0),
+        columns: attribute(node, "columns")?.unwrap_or(0),
+        margin: attribute(node, "margin")?.unwrap_or(0),
+        spacing: attribute(node, "spacing")?.unwrap_or(0),
+        image: children(node, "image")
+            .next()
+            .and_then(|image| image.attribute("source"))
+            .map(str::to_string),
+        tile_offset,
+        tiles,
+        properties: xml_properties(node),
+    })
+}
@@ -61,7 +61,7 @@ pub fn init(filter: Filter) {
 
         let mut out0: String = String::new();
         out0 += " <";
-        out0 += &record.module_path().unwrap_or("?");
+        out0 += record.module_path().unwrap_or("?");
         out0 += "(";
         out0 += &record.line().unwrap_or(0).to_string();
         out0 += ")";
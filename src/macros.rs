@@ -2,19 +2,14 @@ macro_rules! ctx {
 	( ) => {
 		unsafe {
 			use crate::gfx::raw::context::CONTEXT;
-			CONTEXT.as_mut().expect("No context created.")
+			// the context is only ever used from the thread that created it
+			#[allow(static_mut_refs)]
+			let context = CONTEXT.as_mut().expect("No context created.");
+			context
 		}
 	};
 }
 
-macro_rules! include_bytes_from_root {
-    ( $x:expr ) => {
-        {
-            include_bytes!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $x))
-        }
-    };
-}
-
 macro_rules! include_str_from_root {
     ( $x:expr ) => {
         {
@@ -4,6 +4,10 @@ use instant::Instant;
 pub struct GameTime {
     total: f64,
     delta: f32,
+    alpha: f32,
+
+    fixed_total: f64,
+    accumulator: f64,
 
     cycle: Instant,
 }
@@ -13,6 +17,10 @@ impl Default for GameTime {
         Self {
             total: 0.0,
             delta: 1.0 / 60.0,
+            alpha: 1.0,
+
+            fixed_total: 0.0,
+            accumulator: 0.0,
 
             cycle: Instant::now(),
         }
@@ -32,6 +40,12 @@ impl GameTime {
         self.delta
     }
 
+    /// How far between the last and the next fixed update the current draw is, in `0.0..1.0`.
+    /// Always `1.0` without a fixed time step.
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
     pub fn update(&mut self) {
         let now = Instant::now();
         let diff = now - self.cycle;
@@ -40,4 +54,103 @@ impl GameTime {
         self.total += diff.as_secs_f64();
         self.delta = diff.as_secs_f32();
     }
+
+    pub(crate) fn step(&mut self, delta: f32) {
+        self.total += delta as f64;
+        self.delta = delta;
+    }
+
+    /// Adds the last frame delta to the accumulator and returns how many fixed updates to run.
+    /// Steps above `max_steps` are dropped instead of carried over to the next frame.
+    pub(crate) fn accumulate(&mut self, step: f32, max_steps: u32) -> u32 {
+        let step = step as f64;
+
+        self.accumulator += self.delta as f64;
+
+        // epsilon keeps float drift from postponing a step that is due
+        let available = (self.accumulator / step + 1e-6).floor() as u32;
+        let steps = available.min(max_steps);
+
+        self.accumulator = (self.accumulator - steps as f64 * step).max(0.0);
+
+        if available > max_steps {
+            self.accumulator %= step;
+        }
+
+        self.alpha = (self.accumulator / step).clamp(0.0, 1.0) as f32;
+
+        steps
+    }
+
+    pub(crate) fn fixed(&mut self, step: f32) -> GameTime {
+        self.fixed_total += step as f64;
+
+        GameTime {
+            total: self.fixed_total,
+            delta: step,
+            alpha: 1.0,
+            ..*self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(game_time: &mut GameTime, delta: f32, step: f32, max_steps: u32) -> (u32, f32) {
+        game_time.step(delta);
+
+        (game_time.accumulate(step, max_steps), game_time.alpha())
+    }
+
+    #[test]
+    fn steps_per_frame() {
+        let mut game_time = GameTime::new();
+
+        assert_eq!(frame(&mut game_time, 1.0 / 30.0, 1.0 / 60.0, 5).0, 2);
+        assert_eq!(frame(&mut game_time, 1.0 / 60.0, 1.0 / 60.0, 5).0, 1);
+
+        // 1/120 short of a step every frame, a step every other frame
+        let steps = (0..4)
+            .map(|_| frame(&mut game_time, 1.0 / 120.0, 1.0 / 60.0, 5).0)
+            .collect::<Vec<_>>();
+        assert_eq!(steps, [0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn alpha() {
+        let mut game_time = GameTime::new();
+
+        assert_eq!(frame(&mut game_time, 0.1875, 0.125, 5), (1, 0.5));
+        assert_eq!(frame(&mut game_time, 0.03125, 0.125, 5), (0, 0.75));
+        assert_eq!(frame(&mut game_time, 0.03125, 0.125, 5), (1, 0.0));
+    }
+
+    #[test]
+    fn max_steps() {
+        let mut game_time = GameTime::new();
+
+        // 8.5 steps due, 5 run and the 3 whole ones left over are dropped
+        assert_eq!(frame(&mut game_time, 1.0625, 0.125, 5), (5, 0.5));
+        assert_eq!(frame(&mut game_time, 0.0625, 0.125, 5), (1, 0.0));
+    }
+
+    #[test]
+    fn fixed() {
+        let mut game_time = GameTime::new();
+        game_time.step(0.25);
+
+        let fixed = (0..game_time.accumulate(0.125, 5))
+            .map(|_| game_time.fixed(0.125))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            fixed
+                .iter()
+                .map(|fixed| (fixed.total(), fixed.delta(), fixed.alpha()))
+                .collect::<Vec<_>>(),
+            [(0.125, 0.125, 1.0), (0.25, 0.125, 1.0)]
+        );
+    }
 }
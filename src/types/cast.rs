@@ -13,7 +13,7 @@ pub fn cast<A: Copy, B: Copy>(a: &[A]) -> &[B] {
 
 #[inline]
 fn is_aligned_to(ptr: *const (), align: usize) -> bool {
-    ((ptr as usize) % align) == 0
+    (ptr as usize).is_multiple_of(align)
 }
 
 unsafe fn try_cast_slice<A: Copy, B: Copy>(a: &[A]) -> Result<&[B], &str> {
@@ -23,7 +23,7 @@ unsafe fn try_cast_slice<A: Copy, B: Copy>(a: &[A]) -> Result<&[B], &str> {
         Ok(unsafe { core::slice::from_raw_parts(a.as_ptr() as *const B, a.len()) })
     } else if size_of::<A>() == 0 || size_of::<B>() == 0 {
         Err("Size Mismatch")
-    } else if core::mem::size_of_val(a) % size_of::<B>() == 0 {
+    } else if core::mem::size_of_val(a).is_multiple_of(size_of::<B>()) {
         let new_len = core::mem::size_of_val(a) / size_of::<B>();
         Ok(unsafe { core::slice::from_raw_parts(a.as_ptr() as *const B, new_len) })
     } else {
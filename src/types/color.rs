@@ -1,5 +1,5 @@
 #[repr(C)]
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Color<T>
 where T: num::Num + num::FromPrimitive {
     pub r: T,
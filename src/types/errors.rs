@@ -8,4 +8,78 @@ pub enum BatchError {
     BatchNotDrawing,
     #[error("Frame is None")]
     FrameIsNone,
+    #[error("Batch is recording a static batch")]
+    BatchIsRecording,
+    #[error("Static batch needs {required} textures per draw call, the pass can bind {capacity}")]
+    TextureCapacity { required: u32, capacity: u32 },
+    #[error("Render target has no depth and stencil buffer to mask with")]
+    NoStencilBuffer,
+}
+
+#[derive(Error, Debug)]
+pub enum FontError {
+    #[error("Failed to parse font: {0}")]
+    Parse(String),
+    #[error("Bitmap font expects {expected} pages, found {found}")]
+    MissingPages { expected: usize, found: usize },
+}
+
+#[derive(Error, Debug)]
+pub enum AtlasError {
+    #[error("Failed to parse atlas: {0}")]
+    Parse(String),
+    #[error("Image `{name}` of {dimensions:?} does not fit into a {max_size}px atlas page")]
+    ImageTooLarge {
+        name: String,
+        dimensions: (u32, u32),
+        max_size: u32,
+    },
+    #[error("Frame `{0}` is rotated, rotated atlas frames are not supported")]
+    RotatedFrame(String),
+}
+
+#[derive(Error, Debug)]
+pub enum AnimationError {
+    #[error("Animation has no frames")]
+    NoFrames,
+    #[error("Frame tag `{0}` points outside of the frames")]
+    TagOutOfRange(String),
+}
+
+#[derive(Error, Debug)]
+pub enum NinePatchError {
+    #[error("Nine patch image of {0}x{1} is too small for its guides")]
+    TooSmall(u32, u32),
+    #[error("Nine patch has no stretch guide on the {0} side")]
+    MissingGuide(&'static str),
+    #[error("Nine patch guide on the {0} side has a pixel that is neither black nor transparent: {1:?}")]
+    InvalidGuide(&'static str, [u8; 4]),
+}
+
+#[derive(Error, Debug)]
+pub enum TilemapError {
+    #[error("Failed to parse map: {0}")]
+    Parse(String),
+    #[error("Unsupported Tiled feature: {0}")]
+    Unsupported(String),
+}
+
+#[derive(Error, Debug)]
+pub enum ReadbackError {
+    #[error("Texture format {0:?} can not be read back")]
+    UnsupportedFormat(wgpu::TextureFormat),
+    #[error("Texture was not created with COPY_SRC usage")]
+    NotCopyable,
+    #[error("Readback commands have not been submitted yet")]
+    NotSubmitted,
+    #[error("Readback channel disconnected")]
+    Disconnected,
+    #[error("Frame is None")]
+    FrameIsNone,
+}
+
+#[derive(Error, Debug)]
+pub enum LightingError {
+    #[error("Frame is None")]
+    FrameIsNone,
 }
@@ -150,7 +150,16 @@ impl Input {
         self.mouse_motion_changed = true;
     }
 
+    /// In virtual pixels when a virtual resolution is configured, window pixels otherwise, from the top left.
     pub fn mouse_position(&self) -> Position<f32> {
+        match crate::gfx::raw::viewport() {
+            Some(viewport) => viewport.to_virtual(self.mouse_position),
+            None => self.mouse_position,
+        }
+    }
+
+    /// In window pixels from the top left, ignoring any virtual resolution.
+    pub fn window_mouse_position(&self) -> Position<f32> {
         self.mouse_position
     }
 
@@ -7,8 +7,12 @@ pub use window::WindowConfig;
 #[allow(unused_imports)]
 pub(crate) use window::{
     abort,
+    aborted,
+    init_input,
     input,
     input_mut,
     raw,
+    reset_abort,
+    try_raw,
     Window,
 };
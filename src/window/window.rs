@@ -32,22 +32,47 @@ pub fn abort() {
     }
 }
 
+pub fn aborted() -> bool {
+    unsafe { EXIT }
+}
+
+/// Lets the next run start after `abort()` ended the last one.
+pub(crate) fn reset_abort() {
+    unsafe {
+        EXIT = false;
+    }
+}
+
 static mut INPUT: Option<Input> = None;
 
+pub fn init_input() {
+    unsafe {
+        INPUT = Some(Input::new());
+    }
+}
+
+#[allow(static_mut_refs)]
 pub fn input_mut() -> &'static mut Input {
     unsafe { INPUT.as_mut().unwrap() }
 }
 
+#[allow(static_mut_refs)]
 pub fn input() -> &'static Input {
     unsafe { INPUT.as_ref().unwrap() }
 }
 
 static mut RAW: Option<WinitWindow> = None;
 
+#[allow(static_mut_refs)]
 pub fn raw() -> &'static mut WinitWindow {
     unsafe { RAW.as_mut().unwrap() }
 }
 
+#[allow(static_mut_refs)]
+pub fn try_raw() -> Option<&'static mut WinitWindow> {
+    unsafe { RAW.as_mut() }
+}
+
 //
 
 #[derive(Debug, Clone, Copy)]
@@ -88,12 +113,14 @@ unsafe impl Send for Window {
 }
 
 impl raw_window_handle::HasDisplayHandle for Window {
+    #[allow(static_mut_refs)]
     fn display_handle(&self) -> Result<raw_window_handle::DisplayHandle<'_>, raw_window_handle::HandleError> {
         unsafe { RAW.as_ref().unwrap().display_handle() }
     }
 }
 
 impl raw_window_handle::HasWindowHandle for Window {
+    #[allow(static_mut_refs)]
     fn window_handle(&self) -> Result<raw_window_handle::WindowHandle<'_>, raw_window_handle::HandleError> {
         unsafe { RAW.as_ref().unwrap().window_handle() }
     }
@@ -101,9 +128,7 @@ impl raw_window_handle::HasWindowHandle for Window {
 
 impl Window {
     pub fn new(config: WindowConfig) -> Self {
-        unsafe {
-            INPUT = Some(Input::new());
-        }
+        init_input();
 
         let event_loop = EventLoop::new().unwrap();
 
@@ -177,8 +202,6 @@ impl Window {
                             width: self.size.width,
                             height: self.size.height,
                         });
-
-                        input_mut().reset();
                     },
                     _ => (),
                 },
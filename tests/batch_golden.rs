@@ -0,0 +1,1764 @@
+mod common;
+
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    rc::Rc,
+};
+
+use adore::{
+    raw,
+    Animation,
+    AtlasBuilder,
+    AtlasConfig,
+    Batch,
+    BatchConfig,
+    BatchShader,
+    BlendMode,
+    Camera2D,
+    Color,
+    EmitterConfig,
+    Font,
+    Layer,
+    Light,
+    LightKind,
+    Lighting,
+    LineCap,
+    LineJoin,
+    LineStyle,
+    Margins,
+    Modifier,
+    NinePatch,
+    ObjectShape,
+    Occluder,
+    Orientation,
+    ParticleEmitter,
+    PatchMode,
+    PlayMode,
+    Position,
+    PropertyValue,
+    Rectangle,
+    Shadows,
+    Size,
+    SortMode,
+    Sprite,
+    TextureAtlas,
+    TextureRegion,
+    TileMap,
+};
+use common::Scene;
+//
+
+const NUMBERED: [&[u8]; 15] = [
+    include_bytes!("../examples/dev/1.png"),
+    include_bytes!("../examples/dev/2.png"),
+    include_bytes!("../examples/dev/3.png"),
+    include_bytes!("../examples/dev/4.png"),
+    include_bytes!("../examples/dev/5.png"),
+    include_bytes!("../examples/dev/6.png"),
+    include_bytes!("../examples/dev/7.png"),
+    include_bytes!("../examples/dev/8.png"),
+    include_bytes!("../examples/dev/9.png"),
+    include_bytes!("../examples/dev/10.png"),
+    include_bytes!("../examples/dev/11.png"),
+    include_bytes!("../examples/dev/12.png"),
+    include_bytes!("../examples/dev/13.png"),
+    include_bytes!("../examples/dev/14.png"),
+    include_bytes!("../examples/dev/15.png"),
+];
+
+fn sprite(bytes: &[u8]) -> Sprite {
+    Sprite::new(adore::load_texture_from_bytes(bytes).unwrap())
+}
+
+fn grid(index: usize, size: f32) -> Rectangle<f32> {
+    let columns = (common::WIDTH as f32 / size) as usize;
+
+    Rectangle::new((index % columns) as f32 * size, (index / columns) as f32 * size, size, size)
+}
+
+/// Normal map of a dome bulging out of a `size` by `size` square, transparent around it.
+fn dome_normal_map(size: u32) -> Vec<u8> {
+    let mut bytes = vec![];
+    let half = size as f32 / 2.0;
+
+    for row in 0..size {
+        for column in 0..size {
+            // rows go down, the green channel points up
+            let (x, y) = ((column as f32 + 0.5 - half) / half, (half - row as f32 - 0.5) / half);
+            let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+            let alpha = if x * x + y * y <= 1.0 {
+                255
+            } else {
+                0
+            };
+
+            bytes.extend([x, y, z].map(|channel| ((channel * 0.5 + 0.5) * 255.0).round() as u8));
+            bytes.push(alpha);
+        }
+    }
+
+    bytes
+}
+
+/// Black and white pixels alternating in both directions, the worst case for drawing a texture shrunk.
+fn checkerboard(size: u32) -> Vec<u8> {
+    (0..size * size)
+        .flat_map(|index| match (index / size + index % size) % 2 {
+            0 => [255; 4],
+            _ => [0, 0, 0, 255],
+        })
+        .collect()
+}
+
+fn quadrants() -> TextureAtlas {
+    TextureAtlas::from_texture_packer_path(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/atlas/quadrants.json")).unwrap()
+}
+
+/// Renders `scene` with every texture bound on its own, as on devices without texture arrays, into the same reference.
+fn without_texture_arrays(name: &'static str, scene: fn() -> Scene) {
+    let config = adore::AdoreConfig {
+        texture_arrays: false,
+        ..Default::default()
+    };
+
+    common::golden_with(config, name, || {
+        let mut scene = scene();
+
+        Box::new(move |batch| {
+            assert!(!batch.uses_texture_arrays());
+            scene(batch);
+        })
+    });
+}
+
+fn tilemap_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/tilemap").join(name)
+}
+
+//
+
+#[test]
+fn single_sprite() {
+    common::golden("single_sprite", || {
+        let mut single = sprite(include_bytes!("../examples/dev/dev.png"));
+
+        Box::new(move |batch| {
+            single.set_target(Rectangle::new(16.0, 16.0, 64.0, 64.0));
+            batch.draw_sprite(&single);
+        })
+    });
+}
+
+#[test]
+fn interleaved_textures() {
+    common::golden("interleaved_textures", || {
+        let mut interleaved = [
+            sprite(include_bytes!("../examples/dev/dev.png")),
+            sprite(include_bytes!("../examples/dev/test.png")),
+        ];
+
+        Box::new(move |batch| {
+            for index in 0..30 {
+                let sprite = &mut interleaved[(index + index / 10) % 2];
+                sprite.set_target(grid(index, 16.0));
+                batch.draw_sprite(sprite);
+            }
+        })
+    });
+}
+
+// more textures than fit into a single draw call
+fn many_textures_scene() -> Scene {
+    let mut numbered = NUMBERED.iter().map(|bytes| sprite(bytes)).collect::<Vec<_>>();
+
+    Box::new(move |batch| {
+        for (index, sprite) in numbered.iter_mut().enumerate() {
+            sprite.set_target(grid(index, 32.0));
+            batch.draw_sprite(sprite);
+        }
+
+        assert_eq!(batch.draw_calls(), numbered.len().div_ceil(batch.texture_capacity() as usize));
+    })
+}
+
+#[test]
+fn many_textures() {
+    common::golden("many_textures", many_textures_scene);
+}
+
+#[test]
+fn many_textures_without_texture_arrays() {
+    without_texture_arrays("many_textures", many_textures_scene);
+}
+
+// textures reappear after the draw call already holds them
+fn repeated_textures_scene() -> Scene {
+    let mut repeated = NUMBERED.iter().map(|bytes| sprite(bytes)).collect::<Vec<_>>();
+
+    Box::new(move |batch| {
+        for index in 0..70 {
+            let sprite = &mut repeated[(index * 7 + index / 3) % 15];
+            sprite.set_target(grid(index, 16.0));
+            batch.draw_sprite(sprite);
+        }
+    })
+}
+
+#[test]
+fn repeated_textures() {
+    common::golden("repeated_textures", repeated_textures_scene);
+}
+
+#[test]
+fn repeated_textures_without_texture_arrays() {
+    without_texture_arrays("repeated_textures", repeated_textures_scene);
+}
+
+#[test]
+fn tinted() {
+    common::golden("tinted", || {
+        let mut tinted = sprite(include_bytes!("../examples/dev/dev.png"));
+
+        Box::new(move |batch| {
+            let colors = [
+                Color::new(1.0, 0.0, 0.0, 1.0),
+                Color::new(0.0, 1.0, 0.0, 1.0),
+                Color::new(0.0, 0.0, 1.0, 1.0),
+                Color::new(1.0, 1.0, 1.0, 0.5),
+            ];
+
+            for (index, color) in colors.into_iter().enumerate() {
+                tinted.set_target(Rectangle::new(8.0 + index as f32 * 36.0, 40.0, 32.0, 32.0));
+                tinted.set_color(color);
+                batch.draw_sprite(&tinted);
+            }
+        })
+    });
+}
+
+// low resolution target scaled up into the frame
+#[test]
+fn render_target() {
+    common::golden("render_target", || {
+        let mut offscreen = Batch::new();
+        let target = raw::RenderTarget::new((40, 30), false, raw::TextureConfig::default());
+        let mut target_sprite = target.sprite();
+
+        let mut pixel_art = [
+            sprite(include_bytes!("../examples/dev/dev.png")),
+            sprite(include_bytes!("../examples/dev/1.png")),
+        ];
+
+        Box::new(move |batch| {
+            target.clear(raw::Color {
+                r: 0.2,
+                g: 0.2,
+                b: 0.6,
+                a: 1.0,
+            });
+
+            offscreen
+                .begin_with(BatchConfig {
+                    target: Some(target.clone()),
+                    ..Default::default()
+                })
+                .unwrap();
+
+            pixel_art[0].set_target(Rectangle::new(2.0, 2.0, 16.0, 16.0));
+            offscreen.draw_sprite(&pixel_art[0]);
+            pixel_art[1].set_target(Rectangle::new(22.0, 10.0, 16.0, 16.0));
+            offscreen.draw_sprite(&pixel_art[1]);
+
+            offscreen.end().unwrap();
+
+            let stats = offscreen.stats();
+            assert_eq!((stats.draw_calls, stats.quads, stats.vertices), (1, 2, 8));
+            // 40 byte vertices
+            assert_eq!(stats.bytes_uploaded, 8 * 40);
+            assert_eq!(stats.reallocations, 0);
+
+            target_sprite.set_target(Rectangle::new(0.0, 0.0, 160.0, 120.0));
+            batch.draw_sprite(&target_sprite);
+        })
+    });
+}
+
+// a chain with every effect disabled copies its source over the stale destination
+#[test]
+fn post_process_disabled_chain() {
+    common::golden("post_process_disabled_chain", || {
+        let mut offscreen = Batch::new();
+        let source = raw::RenderTarget::new((40, 30), false, raw::TextureConfig::default());
+        let destination = raw::RenderTarget::new((40, 30), false, raw::TextureConfig::default());
+        let mut destination_sprite = destination.sprite();
+
+        let mut chain = raw::PostProcess::default();
+        chain.push("blur", raw::PostEffect::gaussian_blur(2.0));
+        chain.set_enabled("blur", false);
+
+        let mut pixel_art = sprite(include_bytes!("../examples/dev/dev.png"));
+
+        Box::new(move |batch| {
+            destination.clear(raw::Color {
+                r: 1.0,
+                g: 0.0,
+                b: 0.0,
+                a: 1.0,
+            });
+            source.clear(raw::Color {
+                r: 0.2,
+                g: 0.2,
+                b: 0.6,
+                a: 1.0,
+            });
+
+            offscreen
+                .begin_with(BatchConfig {
+                    target: Some(source.clone()),
+                    ..Default::default()
+                })
+                .unwrap();
+
+            pixel_art.set_target(Rectangle::new(12.0, 7.0, 16.0, 16.0));
+            offscreen.draw_sprite(&pixel_art);
+
+            offscreen.end().unwrap();
+
+            assert!(!chain.is_active());
+            chain.apply(&source, &destination);
+
+            destination_sprite.set_target(Rectangle::new(0.0, 0.0, 160.0, 120.0));
+            batch.draw_sprite(&destination_sprite);
+        })
+    });
+}
+
+// source rectangle, rotation around the origin, scale and flips
+#[test]
+fn sprite_transforms() {
+    common::golden("sprite_transforms", || {
+        let mut transformed = sprite(include_bytes!("../examples/dev/dev.png"));
+
+        Box::new(move |batch| {
+            transformed.set_target(Rectangle::new(8.0, 72.0, 32.0, 32.0));
+            batch.draw_sprite(&transformed);
+
+            // top half of the texture, stretched over the target
+            transformed.set_source(Rectangle::new(0.0, 0.0, 32.0, 16.0));
+            transformed.set_target(Rectangle::new(48.0, 72.0, 32.0, 32.0));
+            batch.draw_sprite(&transformed);
+
+            transformed.set_source(Rectangle::new(0.0, 0.0, 32.0, 32.0));
+            transformed.set_target(Rectangle::new(88.0, 72.0, 32.0, 32.0));
+            transformed.set_flip_y(true);
+            batch.draw_sprite(&transformed);
+
+            transformed.set_target(Rectangle::new(128.0, 72.0, 24.0, 32.0));
+            transformed.set_flip_x(true);
+            batch.draw_sprite(&transformed);
+
+            transformed.set_flip_x(false);
+            transformed.set_flip_y(false);
+
+            transformed.set_target(Rectangle::new(24.0, 24.0, 32.0, 32.0));
+            transformed.set_origin_center();
+            transformed.set_rotation(std::f32::consts::FRAC_PI_4);
+            batch.draw_sprite(&transformed);
+
+            // rotating around the bottom left corner
+            transformed.set_target(Rectangle::new(80.0, 8.0, 32.0, 32.0));
+            transformed.set_origin(Position {
+                x: 0.0,
+                y: 0.0,
+            });
+            transformed.set_rotation(std::f32::consts::FRAC_PI_2);
+            batch.draw_sprite(&transformed);
+
+            transformed.set_target(Rectangle::new(112.0, 24.0, 32.0, 32.0));
+            transformed.set_origin_center();
+            transformed.set_rotation(0.0);
+            transformed.set_scale(adore::glam::Vec2::new(1.5, 0.5));
+            batch.draw_sprite(&transformed);
+
+            transformed.set_scale(adore::glam::Vec2::ONE);
+        })
+    });
+}
+
+// negative scales mirror around the origin instead of turning the quad's back to the camera
+#[test]
+fn sprite_mirrored() {
+    common::golden("sprite_mirrored", || {
+        let mut mirrored = sprite(NUMBERED[6]);
+        mirrored.set_origin_center();
+
+        Box::new(move |batch| {
+            let scales = [(1.0, 1.0), (-1.0, 1.0), (1.0, -1.0), (-1.0, -1.0)];
+
+            for (index, (x, y)) in scales.into_iter().enumerate() {
+                mirrored.set_target(Rectangle::new(8.0 + index as f32 * 38.0, 72.0, 32.0, 32.0));
+                mirrored.set_scale(adore::glam::Vec2::new(x, y));
+                batch.draw_sprite(&mirrored);
+            }
+
+            // mirrored and flipped back, then mirrored while rotated
+            mirrored.set_target(Rectangle::new(8.0, 16.0, 32.0, 32.0));
+            mirrored.set_scale(adore::glam::Vec2::new(-1.5, 1.0));
+            mirrored.set_flip_x(true);
+            batch.draw_sprite(&mirrored);
+
+            mirrored.set_flip_x(false);
+            mirrored.set_target(Rectangle::new(96.0, 16.0, 32.0, 32.0));
+            mirrored.set_rotation(std::f32::consts::FRAC_PI_4);
+            batch.draw_sprite(&mirrored);
+            mirrored.set_rotation(0.0);
+        })
+    });
+}
+
+// more images than fit into a draw call, packed into a single page
+#[test]
+fn atlas() {
+    common::golden("atlas", || {
+        let mut builder = AtlasBuilder::default();
+        for (index, bytes) in NUMBERED.iter().enumerate() {
+            builder.add_bytes(format!("{}", index + 1), bytes).unwrap();
+        }
+        builder.add_bytes("dev", include_bytes!("../examples/dev/dev.png")).unwrap();
+        builder.add_bytes("test", include_bytes!("../examples/dev/test.png")).unwrap();
+        let packed = builder.build().unwrap();
+
+        // pages are converted to the configured format, single channel ones keep the alpha
+        for format in [raw::TextureFormat::R8Unorm, raw::TextureFormat::Rgba16Float] {
+            let mut builder = AtlasBuilder::new(AtlasConfig {
+                texture_config: raw::TextureConfig {
+                    format,
+                    ..Default::default()
+                },
+                ..Default::default()
+            });
+            builder.add_bytes("dev", include_bytes!("../examples/dev/dev.png")).unwrap();
+
+            let converted = builder.build().unwrap();
+            assert_eq!(converted.pages().count(), 1);
+            assert!(converted.get("dev").is_some());
+        }
+
+        let quadrants = quadrants();
+        let quadrants_array = TextureAtlas::from_texture_packer_bytes(
+            include_bytes!("atlas/quadrants_array.json"),
+            adore::load_texture_from_bytes(include_bytes!("../examples/dev/dev.png")).unwrap(),
+        )
+        .unwrap();
+
+        Box::new(move |batch| {
+            assert_eq!(packed.len(), 17);
+            assert_eq!(packed.pages().count(), 1);
+
+            for index in 0..17 {
+                let name = match index {
+                    15 => "dev".to_string(),
+                    16 => "test".to_string(),
+                    _ => format!("{}", index + 1),
+                };
+
+                let mut sprite = packed.sprite(&name).unwrap();
+                sprite.set_target(grid(index, 24.0));
+                batch.draw_sprite(&sprite);
+            }
+
+            // quadrants of the sheet drawn in reverse order, once per layout
+            for (row, atlas) in [&quadrants, &quadrants_array].into_iter().enumerate() {
+                for (index, name) in ["bottom_right", "bottom_left", "top_right", "top_left"].into_iter().enumerate() {
+                    let mut sprite = atlas.sprite(name).unwrap();
+                    sprite.set_target(Rectangle::new(
+                        index as f32 * 16.0 + (index / 2) as f32 * 8.0,
+                        104.0 - row as f32 * 24.0,
+                        16.0,
+                        16.0,
+                    ));
+                    batch.draw_sprite(&sprite);
+                }
+            }
+
+            assert_eq!(batch.draw_calls(), 1);
+        })
+    });
+}
+
+// a trimmed frame lands where it sits in the untrimmed image, next to that image
+#[test]
+fn atlas_trimmed() {
+    common::golden("atlas_trimmed", || {
+        let trimmed =
+            TextureAtlas::from_texture_packer_path(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/atlas/trimmed.json")).unwrap();
+        let uneven =
+            TextureAtlas::from_texture_packer_path(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/atlas/trimmed_uneven.json")).unwrap();
+        let mut full = sprite(include_bytes!("../examples/dev/dev.png"));
+
+        Box::new(move |batch| {
+            let region = trimmed.get("center").unwrap();
+            assert!(region.is_trimmed());
+            assert_eq!(region.original_size(), Size {
+                width: 32.0,
+                height: 32.0
+            });
+
+            let mut center = trimmed.sprite("center").unwrap();
+            assert_eq!(center.target(), Rectangle::new(8.0, 8.0, 16.0, 16.0));
+
+            full.set_target(Rectangle::new(32.0, 44.0, 32.0, 32.0));
+            batch.draw_sprite(&full);
+
+            center.target_mut().x += 96.0;
+            center.target_mut().y += 44.0;
+            batch.draw_sprite(&center);
+
+            // trimmed more at the bottom, then more at the top, offsets go down while targets go up
+            let mut top = uneven.sprite("top").unwrap();
+            assert_eq!(top.target(), Rectangle::new(4.0, 20.0, 24.0, 12.0));
+
+            let mut bottom = uneven.sprite("bottom").unwrap();
+            assert_eq!(bottom.target(), Rectangle::new(8.0, 0.0, 16.0, 8.0));
+
+            for sprite in [&mut top, &mut bottom] {
+                sprite.target_mut().x += 96.0;
+                sprite.target_mut().y += 44.0;
+                batch.draw_sprite(sprite);
+            }
+        })
+    });
+}
+
+// depth sorted overlapping sprites, texture sorted grid and immediate draws
+#[test]
+fn sort_modes() {
+    common::golden("sort_modes", || {
+        let mut sorted = [(); 4].map(|_| {
+            let mut batch = Batch::new();
+            batch.resize(Size {
+                width: common::WIDTH,
+                height: common::HEIGHT,
+            });
+            batch
+        });
+        let mut shuffled = NUMBERED.iter().map(|bytes| sprite(bytes)).collect::<Vec<_>>();
+        let mut layered = sprite(include_bytes!("../examples/dev/dev.png"));
+
+        Box::new(move |_| {
+            let [back_to_front, front_to_back, by_texture, immediate] = &mut sorted;
+
+            let colors = [
+                Color::new(1.0, 0.2, 0.2, 1.0),
+                Color::new(0.2, 1.0, 0.2, 1.0),
+                Color::new(0.2, 0.2, 1.0, 1.0),
+            ];
+
+            // submitted front to back, red at depth 0 ends up on top only when drawn back to front
+            for (column, (batch, sort_mode)) in [(back_to_front, SortMode::BackToFront), (front_to_back, SortMode::FrontToBack)]
+                .into_iter()
+                .enumerate()
+            {
+                batch
+                    .begin_with(BatchConfig {
+                        sort_mode,
+                        ..Default::default()
+                    })
+                    .unwrap();
+
+                for (index, color) in colors.into_iter().enumerate() {
+                    let offset = index as f32 * 10.0;
+
+                    layered.set_target(Rectangle::new(8.0 + column as f32 * 80.0 + offset, 72.0 + offset, 32.0, 32.0));
+                    layered.set_color(color);
+                    layered.set_layer_depth(index as f32 / 2.0);
+                    batch.draw_sprite(&layered);
+                }
+
+                batch.end().unwrap();
+            }
+
+            by_texture
+                .begin_with(BatchConfig {
+                    sort_mode: SortMode::Texture,
+                    ..Default::default()
+                })
+                .unwrap();
+
+            for index in 0..30 {
+                // every texture twice, spread out so submission order would need more draw calls
+                let sprite = &mut shuffled[index * 7 % 15];
+                sprite.set_target(grid(index, 16.0));
+                by_texture.draw_sprite(sprite);
+            }
+
+            assert_eq!(by_texture.draw_calls(), 0);
+            by_texture.end().unwrap();
+
+            let stats = by_texture.stats();
+            assert_eq!(
+                stats.draw_calls as usize,
+                shuffled.len().div_ceil(by_texture.texture_capacity() as usize)
+            );
+            assert_eq!(stats.quads, 30);
+
+            immediate
+                .begin_with(BatchConfig {
+                    sort_mode: SortMode::Immediate,
+                    ..Default::default()
+                })
+                .unwrap();
+
+            for index in 0..3 {
+                layered.set_target(Rectangle::new(index as f32 * 16.0, 48.0, 16.0, 16.0));
+                layered.set_color(Color::default());
+                immediate.draw_sprite(&layered);
+            }
+
+            immediate.end().unwrap();
+            assert_eq!(immediate.stats().draw_calls, 3);
+        })
+    });
+}
+
+// a red strip fading out from opaque to transparent multiplied over white and colored bands, the transparent end
+// leaves them untouched
+#[test]
+fn multiply_transparent() {
+    common::golden("multiply_transparent", || {
+        let mut multiplied = Batch::new();
+        multiplied.resize(Size {
+            width: common::WIDTH,
+            height: common::HEIGHT,
+        });
+        let strip = [255u8, 170, 85, 0]
+            .into_iter()
+            .flat_map(|alpha| [255, 64, 64, alpha])
+            .collect::<Vec<_>>();
+        let mut strip = Sprite::new(raw::Texture::new(&strip, (4, 1), raw::TextureConfig::default()));
+
+        Box::new(move |batch| {
+            for (index, color) in [
+                Color::new(1.0, 1.0, 1.0, 1.0),
+                Color::new(0.2, 0.8, 1.0, 1.0),
+                Color::new(1.0, 0.9, 0.2, 1.0),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                batch.fill_rectangle(Rectangle::new(0.0, 20.0 + index as f32 * 28.0, 160.0, 24.0), color);
+            }
+            batch.end().unwrap();
+
+            multiplied
+                .begin_with(BatchConfig {
+                    blend_mode: BlendMode::Multiply,
+                    ..Default::default()
+                })
+                .unwrap();
+
+            strip.set_target(Rectangle::new(16.0, 8.0, 128.0, 52.0));
+            multiplied.draw_sprite(&strip);
+
+            // the tint's alpha halves the strip's own
+            strip.set_target(Rectangle::new(16.0, 60.0, 128.0, 52.0));
+            strip.set_color(Color::new(1.0, 1.0, 1.0, 0.5));
+            multiplied.draw_sprite(&strip);
+            strip.set_color(Color::default());
+
+            multiplied.end().unwrap();
+            batch.begin().unwrap();
+        })
+    });
+}
+
+// blend modes over a gray base, a linear sampler override and a custom shader with per pass uniforms
+fn blend_and_shaders_scene() -> Scene {
+    let mut styled = Batch::new();
+    styled.resize(Size {
+        width: common::WIDTH,
+        height: common::HEIGHT,
+    });
+    let mut styled_sprite = sprite(include_bytes!("../examples/dev/dev.png"));
+    let grayscale = Rc::new(BatchShader::with_uniform(
+        include_str!("shaders/grayscale.wgsl"),
+        adore::cast(&[0.0f32; 4]),
+    ));
+
+    Box::new(move |_| {
+        let modes = [
+            BlendMode::Alpha,
+            BlendMode::Premultiplied,
+            BlendMode::Additive,
+            BlendMode::Multiply,
+            BlendMode::Opaque,
+        ];
+
+        styled.begin().unwrap();
+        for index in 0..modes.len() {
+            styled_sprite.set_target(Rectangle::new(4.0 + index as f32 * 31.0, 84.0, 24.0, 24.0));
+            styled_sprite.set_color(Color::new(0.5, 0.5, 0.5, 1.0));
+            styled.draw_sprite(&styled_sprite);
+        }
+        styled.end().unwrap();
+
+        for (index, blend_mode) in modes.into_iter().enumerate() {
+            styled
+                .begin_with(BatchConfig {
+                    blend_mode,
+                    ..Default::default()
+                })
+                .unwrap();
+
+            styled_sprite.set_target(Rectangle::new(12.0 + index as f32 * 31.0, 76.0, 24.0, 24.0));
+            styled_sprite.set_color(Color::new(1.0, 0.5, 0.2, 0.5));
+            styled.draw_sprite(&styled_sprite);
+            styled.end().unwrap();
+        }
+
+        // part of the smile magnified, nearest by default and linear through the override
+        styled_sprite.set_color(Color::default());
+        styled_sprite.set_source(Rectangle::new(4.0, 18.0, 8.0, 8.0));
+
+        for (index, sampler) in [
+            None,
+            Some(raw::TextureConfig {
+                mag_filter: raw::FilterMode::Linear,
+                min_filter: raw::FilterMode::Linear,
+                ..Default::default()
+            }),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            styled
+                .begin_with(BatchConfig {
+                    sampler,
+                    ..Default::default()
+                })
+                .unwrap();
+
+            styled_sprite.set_target(Rectangle::new(4.0 + index as f32 * 36.0, 8.0, 32.0, 32.0));
+            styled.draw_sprite(&styled_sprite);
+            styled.end().unwrap();
+        }
+
+        styled_sprite.set_source(Rectangle::new(0.0, 0.0, 32.0, 32.0));
+
+        let capacity = styled.texture_capacity();
+
+        for (index, amount) in [1.0f32, 0.5].into_iter().enumerate() {
+            grayscale.set_uniform(adore::cast(&[amount, 0.0, 0.0, 0.0]));
+
+            styled
+                .begin_with(BatchConfig {
+                    shader: Some(Rc::clone(&grayscale)),
+                    ..Default::default()
+                })
+                .unwrap();
+
+            if !styled.uses_texture_arrays() {
+                assert_eq!(styled.texture_capacity(), capacity - 1);
+            }
+
+            styled_sprite.set_target(Rectangle::new(84.0 + index as f32 * 36.0, 8.0, 32.0, 32.0));
+            styled.draw_sprite(&styled_sprite);
+            styled.end().unwrap();
+        }
+    })
+}
+
+#[test]
+fn blend_and_shaders() {
+    common::golden("blend_and_shaders", blend_and_shaders_scene);
+}
+
+#[test]
+fn blend_and_shaders_without_texture_arrays() {
+    without_texture_arrays("blend_and_shaders", blend_and_shaders_scene);
+}
+
+// zoomed and rotated view of a 3x3 grid, with a marker placed under a screen position
+#[test]
+fn camera() {
+    common::golden("camera", || {
+        let mut viewed = Batch::new();
+        let mut viewed_sprites = NUMBERED[..9].iter().map(|bytes| sprite(bytes)).collect::<Vec<_>>();
+        let mut cursor = sprite(include_bytes!("../examples/dev/dev.png"));
+
+        Box::new(move |_| {
+            let viewport = Size {
+                width: common::WIDTH,
+                height: common::HEIGHT,
+            };
+
+            let mut camera = Camera2D::new(viewport);
+            camera.set_position(Position {
+                x: 48.0,
+                y: 48.0,
+            });
+            camera.set_zoom(1.25);
+            camera.set_rotation(0.3);
+
+            let screen = Position {
+                x: 12.0,
+                y: 12.0,
+            };
+            let world = camera.screen_to_world(screen);
+            let back = camera.world_to_screen(world);
+            assert!((back.x - screen.x).abs() < 1e-3 && (back.y - screen.y).abs() < 1e-3);
+
+            // bounds keep the view inside the level, the deadzone lets the target move freely around the center
+            let mut follower = Camera2D::new(viewport);
+            follower.set_bounds(Some(Rectangle::new(0.0, 0.0, 400.0, 200.0)));
+            follower.set_deadzone(Some(Size {
+                width: 40.0,
+                height: 40.0,
+            }));
+
+            follower.follow(Position {
+                x: 95.0,
+                y: 70.0,
+            });
+            follower.advance(0.1);
+            assert_eq!(follower.position(), Position {
+                x: 80.0,
+                y: 60.0
+            });
+
+            follower.follow(Position {
+                x: 150.0,
+                y: 60.0,
+            });
+            follower.advance(0.1);
+            assert_eq!(follower.position(), Position {
+                x: 130.0,
+                y: 60.0
+            });
+
+            follower.follow(Position {
+                x: 1000.0,
+                y: -50.0,
+            });
+            follower.advance(0.1);
+            assert_eq!(follower.visible_area(), Rectangle::new(240.0, 0.0, 160.0, 120.0));
+
+            viewed
+                .begin_with(BatchConfig {
+                    camera: Some(camera.clone()),
+                    ..Default::default()
+                })
+                .unwrap();
+
+            for (index, sprite) in viewed_sprites.iter_mut().enumerate() {
+                sprite.set_target(Rectangle::new((index % 3) as f32 * 32.0, (index / 3) as f32 * 32.0, 32.0, 32.0));
+                viewed.draw_sprite(sprite);
+            }
+
+            // rotated with the view so it lines up with the screen
+            cursor.set_target(Rectangle::new(world.x, world.y, 8.0, 8.0));
+            cursor.set_origin_center();
+            cursor.set_rotation(camera.rotation());
+            cursor.set_scale(adore::glam::Vec2::splat(1.0 / camera.zoom()));
+            viewed.draw_sprite(&cursor);
+
+            viewed.end().unwrap();
+        })
+    });
+}
+
+// the frame each play mode ends on, the stepping itself is unit tested
+#[test]
+fn animation() {
+    common::golden("animation", || {
+        let mut clips = Animation::from_aseprite_path(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/atlas/walk.json")).unwrap();
+        let quadrants = quadrants();
+        let quadrant_regions = ["top_left", "top_right", "bottom_left", "bottom_right"].map(|name| quadrants.get(name).unwrap().clone());
+
+        Box::new(move |batch| {
+            let mut walk = clips.remove("walk").unwrap();
+            let mut bounce = clips.remove("bounce").unwrap();
+            let mut once = Animation::from_regions(&quadrant_regions, 0.125, PlayMode::Once).unwrap();
+            let mut reverse = Animation::from_regions(&quadrant_regions, 0.125, PlayMode::Reverse).unwrap();
+
+            walk.advance(0.625);
+            bounce.advance(0.875);
+            once.advance(10.0);
+            reverse.advance(0.25);
+
+            for (index, animation) in [&walk, &bounce, &once, &reverse].into_iter().enumerate() {
+                let mut sprite = animation.sprite();
+                sprite.set_target(Rectangle::new(8.0 + index as f32 * 40.0, 44.0, 32.0, 32.0));
+                batch.draw_sprite(&sprite);
+            }
+        })
+    });
+}
+
+// kerned pairs, line breaks and glyphs from two sizes sharing the cache
+#[test]
+fn text() {
+    common::golden("text", || {
+        // Cantarell, SIL Open Font License
+        let font = Font::from_bytes(include_bytes!("fonts/Cantarell-Regular.ttf")).unwrap();
+
+        Box::new(move |batch| {
+            let text = "AVATAR Today\nWave 42, yes!";
+            let size = font.measure_text(text, 16.0);
+
+            assert!(size.width > 0.0 && size.width < 160.0);
+            assert_eq!(size.height, font.line_height(16.0) * 2.0);
+
+            batch.draw_text(
+                &font,
+                text,
+                Position {
+                    x: 4.0,
+                    y: 116.0,
+                },
+                16.0,
+                Color::default(),
+            );
+            batch.draw_text(
+                &font,
+                "Score: 1337",
+                Position {
+                    x: 4.0,
+                    y: 60.0,
+                },
+                24.0,
+                Color::new(1.0, 0.8, 0.2, 1.0),
+            );
+        })
+    });
+}
+
+// text and binary BMFont descriptors of the same font at integer scales
+#[test]
+fn bitmap_text() {
+    common::golden("bitmap_text", || {
+        let pixel_font = Font::from_bmfont_path(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fonts/pixel.fnt")).unwrap();
+        let pixel_font_binary =
+            Font::from_bmfont_bytes(include_bytes!("fonts/pixel_binary.fnt"), &[include_bytes!("fonts/pixel_0.png")]).unwrap();
+
+        Box::new(move |batch| {
+            let text = "PIXEL FONT!\nAV WAVE 123";
+
+            assert_eq!(pixel_font.native_size(), Some(8.0));
+            assert_eq!(pixel_font.measure_text(text, 8.0), pixel_font_binary.measure_text(text, 8.0));
+
+            // kerning pulls `AV` together by one pixel
+            assert_eq!(pixel_font.measure_text("AV", 8.0).width, 7.0);
+
+            batch.draw_text(
+                &pixel_font,
+                text,
+                Position {
+                    x: 4.0,
+                    y: 116.0,
+                },
+                16.0,
+                Color::default(),
+            );
+            batch.draw_text(
+                &pixel_font_binary,
+                text,
+                Position {
+                    x: 4.0,
+                    y: 72.0,
+                },
+                24.0,
+                Color::new(0.4, 1.0, 0.4, 1.0),
+            );
+        })
+    });
+}
+
+// filled and outlined shapes, a concave polygon, per vertex colors and every join and cap
+#[test]
+fn shapes() {
+    common::golden("shapes", || {
+        Box::new(move |batch| {
+            let red = Color::new(1.0, 0.2, 0.2, 1.0);
+            let green = Color::new(0.2, 1.0, 0.2, 1.0);
+            let blue = Color::new(0.2, 0.4, 1.0, 1.0);
+            let yellow = Color::new(1.0, 0.9, 0.2, 1.0);
+
+            batch.fill_rectangle(Rectangle::new(4.0, 84.0, 32.0, 32.0), red);
+            batch.draw_rectangle(Rectangle::new(40.0, 84.0, 32.0, 32.0), 3.0, green);
+            batch.fill_circle((92.0, 100.0).into(), 16.0, 24, blue);
+            batch.draw_ellipse(
+                (136.0, 100.0).into(),
+                Size {
+                    width: 20.0,
+                    height: 12.0,
+                },
+                32,
+                2.0,
+                yellow,
+            );
+
+            // an arrow, clockwise
+            batch.fill_polygon(
+                &[(4.0, 56.0), (20.0, 80.0), (36.0, 56.0), (20.0, 64.0)].map(Into::into),
+                Color::new(1.0, 0.5, 0.0, 1.0),
+            );
+            batch.fill_triangle_colors([(40.0, 56.0).into(), (56.0, 80.0).into(), (72.0, 56.0).into()], [red, green, blue]);
+            batch.fill_rectangle_colors(Rectangle::new(76.0, 56.0, 32.0, 24.0), [red, green, blue, yellow]);
+            batch.draw_polygon(
+                &[(116.0, 56.0), (116.0, 80.0), (152.0, 80.0), (134.0, 68.0), (152.0, 56.0)].map(Into::into),
+                2.0,
+                Color::default(),
+            );
+
+            let zigzag = [(0.0, 0.0), (12.0, 24.0), (24.0, 0.0), (36.0, 24.0)];
+
+            for (index, (join, cap)) in [
+                (LineJoin::Miter, LineCap::Butt),
+                (LineJoin::Bevel, LineCap::Square),
+                (LineJoin::Round, LineCap::Round),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                let offset = 8.0 + index as f32 * 52.0;
+                let points = zigzag.map(|(x, y)| Position {
+                    x: x + offset,
+                    y: y + 16.0,
+                });
+
+                batch.draw_polyline_colors(
+                    &points,
+                    LineStyle {
+                        thickness: 6.0,
+                        join,
+                        cap,
+                    },
+                    &[red, green, blue, yellow],
+                );
+            }
+
+            batch.draw_line((4.0, 4.0).into(), (156.0, 8.0).into(), 1.0, Color::default());
+        })
+    });
+}
+
+// stretched, tiled and shrunk slices of a .9.png and margins over an atlas region
+#[test]
+fn nine_patch() {
+    common::golden("nine_patch", || {
+        let mut panel = NinePatch::from_nine_png_path(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/textures/panel.9.png")).unwrap();
+        let mut framed = NinePatch::from_region(quadrants().get("top_left").unwrap(), Margins::uniform(4.0));
+
+        Box::new(move |batch| {
+            assert_eq!(panel.margins(), Margins::uniform(3.0));
+            assert_eq!(panel.padding(), Some(Margins::uniform(2.0)));
+            assert_eq!(
+                panel.content(Rectangle::new(10.0, 20.0, 30.0, 40.0)),
+                Rectangle::new(12.0, 22.0, 26.0, 36.0)
+            );
+
+            batch.draw_nine_patch(&panel, Rectangle::new(4.0, 64.0, 72.0, 52.0));
+
+            panel.set_edge_mode(PatchMode::Tile);
+            panel.set_center_mode(PatchMode::Tile);
+            batch.draw_nine_patch(&panel, Rectangle::new(84.0, 64.0, 70.0, 50.0));
+            panel.set_edge_mode(PatchMode::Stretch);
+            panel.set_center_mode(PatchMode::Stretch);
+
+            // margins add up to more than the target
+            batch.draw_nine_patch(&panel, Rectangle::new(4.0, 40.0, 4.0, 16.0));
+
+            framed.set_color(Color::new(1.0, 0.8, 0.8, 1.0));
+            batch.draw_nine_patch(&framed, Rectangle::new(24.0, 4.0, 128.0, 52.0));
+        })
+    });
+}
+
+// groups, flips, tints, an animated tile and a rotated tile object, drawn tile by tile
+#[test]
+fn tilemap_orthogonal() {
+    common::golden("tilemap_orthogonal", || {
+        let mut orthogonal = TileMap::from_tiled_path(&tilemap_path("orthogonal.tmx")).unwrap();
+
+        Box::new(move |batch| {
+            assert_eq!(orthogonal.orientation(), Orientation::Orthogonal);
+            assert_eq!(orthogonal.pixel_size(), Size {
+                width: 128.0,
+                height: 80.0
+            });
+            assert_eq!(
+                orthogonal.background(),
+                Some(Color::new(0x20 as f32 / 255.0, 0x30 as f32 / 255.0, 0x40 as f32 / 255.0, 1.0))
+            );
+            assert_eq!(orthogonal.properties()["gravity"], PropertyValue::Float(9.81));
+            assert_eq!(
+                orthogonal.properties()["notes"],
+                PropertyValue::String("first line\nsecond line".to_string())
+            );
+            assert_eq!(
+                orthogonal.tileset_properties("dev").unwrap()["author"],
+                PropertyValue::String("adore".to_string())
+            );
+            assert_eq!(orthogonal.tile_properties(2).unwrap()["solid"], PropertyValue::Bool(true));
+
+            // the image layer is skipped, the group is flattened into its layers
+            assert_eq!(orthogonal.layers().iter().map(|layer| layer.name()).collect::<Vec<_>>(), [
+                "ground", "decor", "objects"
+            ]);
+            assert_eq!(orthogonal.layer("ground").unwrap().properties()["depth"], PropertyValue::Int(-1));
+
+            let Some(Layer::Tiles(decor)) = orthogonal.layer("decor") else {
+                panic!("decor is not a tile layer");
+            };
+            assert_eq!(decor.offset, Position {
+                x: 8.0,
+                y: 4.0
+            });
+            assert_eq!(decor.gid(5, 1), Some(2));
+            assert_eq!(decor.raw_gid(5, 1), Some(0x8000_0002));
+            assert_eq!(decor.gid(0, 0), None);
+
+            let Some(Layer::Objects(objects)) = orthogonal.layer("objects") else {
+                panic!("objects is not an object layer");
+            };
+            assert_eq!(objects.objects[0].shape, ObjectShape::Tile(4));
+            assert_eq!(objects.objects[1].class, "spawn");
+            assert_eq!(
+                objects.objects[1].properties["tint"],
+                PropertyValue::Color(Color::new(0.0, 1.0, 0.0, 1.0))
+            );
+            assert_eq!(objects.objects[1].properties["target"], PropertyValue::Object(3));
+            assert_eq!(orthogonal.to_world(objects.objects[1].position), Position {
+                x: 16.0,
+                y: 48.0
+            });
+            assert!(matches!(&objects.objects[2].shape, ObjectShape::Polyline(points) if points.len() == 3));
+
+            // edits show up right away, restoring the tile restores the map
+            orthogonal.set_tile(0, 3, 3, 0);
+            let Some(Layer::Tiles(ground)) = orthogonal.layer("ground") else {
+                panic!("ground is not a tile layer");
+            };
+            assert_eq!(ground.gid(3, 3), None);
+            orthogonal.set_tile(0, 3, 3, 3);
+
+            orthogonal.advance(0.25);
+
+            // nothing lies outside of the map
+            orthogonal.draw(batch, Rectangle::new(200.0, 0.0, 100.0, 100.0)).unwrap();
+            assert_eq!(batch.draw_calls(), 0);
+
+            orthogonal.draw(batch, Rectangle::new(0.0, 0.0, 160.0, 120.0)).unwrap();
+        })
+    });
+}
+
+// the same map from static batches, with the animated tile drawn next to them
+#[test]
+fn tilemap_cached() {
+    common::golden("tilemap_orthogonal", || {
+        let mut cached = TileMap::from_tiled_path(&tilemap_path("orthogonal.tmx")).unwrap();
+        cached.cache(&mut Batch::new()).unwrap();
+
+        Box::new(move |batch| {
+            cached.advance(0.25);
+            cached.draw(batch, Rectangle::new(0.0, 0.0, 160.0, 120.0)).unwrap();
+        })
+    });
+}
+
+// tall blocks on a 2:1 grid with an empty cell, a hidden layer and a point object
+#[test]
+fn tilemap_isometric() {
+    common::golden("tilemap_isometric", || {
+        let isometric = TileMap::from_tiled_path(&tilemap_path("isometric.tmj")).unwrap();
+        let mut isometric_batch = Batch::new();
+
+        Box::new(move |_| {
+            assert_eq!(isometric.orientation(), Orientation::Isometric);
+            assert_eq!(isometric.pixel_size(), Size {
+                width: 64.0,
+                height: 32.0
+            });
+            assert_eq!(
+                isometric.layer("blocks").unwrap().properties()["walkable"],
+                PropertyValue::Bool(true)
+            );
+
+            let Some(Layer::Objects(markers)) = isometric.layer("markers") else {
+                panic!("markers is not an object layer");
+            };
+            assert_eq!(markers.objects[0].shape, ObjectShape::Point);
+            // a tile standing on the same corner, tile objects are anchored at their bottom center on isometric maps
+            assert_eq!(markers.objects[1].position, markers.objects[0].position);
+
+            // the point sits on the corner shared by the four center tiles
+            let center = isometric.to_world(markers.objects[0].position);
+            assert_eq!(center, Position {
+                x: 32.0,
+                y: 16.0
+            });
+
+            let mut camera = Camera2D::new(Size {
+                width: common::WIDTH,
+                height: common::HEIGHT,
+            });
+            camera.set_position(center);
+            camera.set_zoom(2.0);
+
+            isometric_batch
+                .begin_with(BatchConfig {
+                    camera: Some(camera.clone()),
+                    ..Default::default()
+                })
+                .unwrap();
+            isometric.draw(&mut isometric_batch, camera.visible_area()).unwrap();
+            isometric_batch.end().unwrap();
+        })
+    });
+}
+
+// blocks over four chunks with animated ones on the chunk borders and raised props, back to front throughout
+#[test]
+fn tilemap_isometric_chunks() {
+    common::golden("tilemap_isometric_chunks", || {
+        let mut isometric = TileMap::from_tiled_path(&tilemap_path("isometric_large.tmj")).unwrap();
+        let mut isometric_batch = Batch::new();
+
+        Box::new(move |_| {
+            assert_eq!(isometric.pixel_size(), Size {
+                width: 640.0,
+                height: 320.0
+            });
+
+            // isometric maps stay uncached, so caching changes nothing
+            isometric.cache(&mut Batch::new()).unwrap();
+            isometric.advance(0.15);
+
+            let mut camera = Camera2D::new(Size {
+                width: common::WIDTH,
+                height: common::HEIGHT,
+            });
+            // the corner where the four chunks meet
+            camera.set_position(Position {
+                x: 304.0,
+                y: 64.0,
+            });
+
+            isometric_batch
+                .begin_with(BatchConfig {
+                    camera: Some(camera.clone()),
+                    ..Default::default()
+                })
+                .unwrap();
+            isometric.draw(&mut isometric_batch, camera.visible_area()).unwrap();
+            isometric_batch.end().unwrap();
+        })
+    });
+}
+
+// smoke from a textured preset over additive embers loaded from JSON and a spent explosion
+#[test]
+fn particles() {
+    common::golden("particles", || {
+        let mut particles = Batch::new();
+        particles.resize(Size {
+            width: common::WIDTH,
+            height: common::HEIGHT,
+        });
+        let puff = TextureRegion::new(
+            adore::load_texture_from_bytes(include_bytes!("../examples/dev/dev.png")).unwrap(),
+            Rectangle::new(0.0, 0.0, 16.0, 16.0),
+        );
+
+        Box::new(move |_| {
+            for preset in [
+                EmitterConfig::smoke(),
+                EmitterConfig::fire(),
+                EmitterConfig::sparks(),
+                EmitterConfig::rain(160.0),
+                EmitterConfig::explosion(),
+            ] {
+                assert_eq!(
+                    EmitterConfig::from_json_bytes(preset.to_json().unwrap().as_bytes()).unwrap(),
+                    preset
+                );
+            }
+
+            let embers_config =
+                EmitterConfig::from_json_path(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/particles/embers.json")).unwrap();
+            assert_eq!(embers_config.direction, 90.0);
+            assert_eq!(embers_config.blend_mode, BlendMode::Additive);
+            assert_eq!(embers_config.modifiers[1], Modifier::Drag(0.5));
+
+            let mut explosion = ParticleEmitter::new(EmitterConfig::explosion());
+            explosion.advance(1.0 / 60.0);
+            assert_eq!(explosion.particle_count(), 60);
+            assert!(!explosion.is_emitting());
+            explosion.advance(1.0);
+            assert!(explosion.is_finished());
+
+            let mut smoke = ParticleEmitter::from_region(&puff, EmitterConfig::smoke());
+            smoke.set_position(Position {
+                x: 50.0,
+                y: 30.0,
+            });
+            smoke.set_seed(7);
+
+            let mut embers = ParticleEmitter::new(embers_config);
+            embers.set_position(Position {
+                x: 110.0,
+                y: 30.0,
+            });
+            embers.set_seed(7);
+
+            // the same seed replays the same particles
+            let mut replay = embers.clone();
+
+            for _ in 0..60 {
+                smoke.advance(1.0 / 30.0);
+                embers.advance(1.0 / 30.0);
+                replay.advance(1.0 / 30.0);
+            }
+            assert!(smoke.particle_count() > 0);
+            assert_eq!(embers.particle_count(), replay.particle_count());
+
+            for emitter in [&smoke, &embers] {
+                particles
+                    .begin_with(BatchConfig {
+                        blend_mode: emitter.config().blend_mode,
+                        ..Default::default()
+                    })
+                    .unwrap();
+                particles.draw_particles(emitter);
+                particles.end().unwrap();
+            }
+        })
+    });
+}
+
+// normal mapped domes and a flat tile under a soft shadowed point light and a hard shadowed spot light
+#[test]
+fn lighting() {
+    common::golden("lighting", || {
+        let size = Size {
+            width: common::WIDTH,
+            height: common::HEIGHT,
+        };
+        let mut lit = Batch::new();
+        lit.resize(size);
+        let mut lighting = Lighting::new(size);
+        let dome = TextureRegion::new(
+            raw::Texture::new(&dome_normal_map(32), (32, 32), raw::TextureConfig {
+                format: raw::TextureFormat::Rgba8Unorm,
+                ..Default::default()
+            }),
+            Rectangle::new(0.0, 0.0, 32.0, 32.0),
+        );
+        let mut bumps = [1, 2, 3].map(|index| {
+            let mut bump = sprite(NUMBERED[index]);
+            bump.set_normal_map(Some(&dome));
+            bump
+        });
+        let mut flat = sprite(NUMBERED[4]);
+
+        Box::new(move |_| {
+            if lighting.lights().is_empty() {
+                lighting.set_ambient(Color::new(0.15, 0.15, 0.25, 1.0));
+
+                let mut warm = Light::point(
+                    Position {
+                        x: 40.0,
+                        y: 60.0,
+                    },
+                    90.0,
+                    Color::new(1.0, 0.8, 0.5, 1.0),
+                );
+                warm.shadows = Shadows::Soft(3.0);
+                warm.height = 16.0;
+                assert_eq!(lighting.add_light(warm), 0);
+
+                let mut spot = Light::spot(
+                    Position {
+                        x: 150.0,
+                        y: 110.0,
+                    },
+                    120.0,
+                    225.0,
+                    50.0,
+                    Color::new(0.4, 0.6, 1.0, 1.0),
+                );
+                spot.shadows = Shadows::Hard;
+                assert_eq!(lighting.add_light(spot), 1);
+
+                // switched off, so it must not show up
+                let mut off = Light::point(
+                    Position {
+                        x: 120.0,
+                        y: 20.0,
+                    },
+                    200.0,
+                    Color::new(1.0, 0.0, 0.0, 1.0),
+                );
+                off.enabled = false;
+                lighting.add_light(off);
+
+                lighting.add_occluder(Occluder::rectangle(Rectangle::new(70.0, 50.0, 12.0, 24.0)));
+                lighting.add_occluder(Occluder::new(vec![
+                    Position {
+                        x: 110.0,
+                        y: 80.0,
+                    },
+                    Position {
+                        x: 100.0,
+                        y: 64.0,
+                    },
+                    Position {
+                        x: 120.0,
+                        y: 64.0,
+                    },
+                ]));
+            }
+
+            for (bump, target) in bumps.iter_mut().zip([
+                Rectangle::new(8.0, 8.0, 32.0, 32.0),
+                Rectangle::new(90.0, 16.0, 32.0, 32.0),
+                Rectangle::new(24.0, 80.0, 32.0, 32.0),
+            ]) {
+                bump.set_target(target);
+            }
+            flat.set_target(Rectangle::new(126.0, 4.0, 32.0, 32.0));
+
+            let walls = lighting.occluders().to_vec();
+            let draw_scene = |batch: &mut Batch| {
+                batch.fill_rectangle(Rectangle::new(0.0, 0.0, 160.0, 120.0), Color::new(0.8, 0.8, 0.8, 1.0));
+
+                for bump in &bumps {
+                    batch.draw_sprite(bump);
+                }
+                batch.draw_sprite(&flat);
+
+                for occluder in &walls {
+                    batch.fill_polygon(&occluder.points, Color::new(0.3, 0.2, 0.2, 1.0));
+                }
+            };
+
+            lit.begin_with(lighting.normal_config(None)).unwrap();
+            draw_scene(&mut lit);
+            lit.end().unwrap();
+
+            lit.begin().unwrap();
+            draw_scene(&mut lit);
+            lit.end().unwrap();
+
+            lighting.draw(None).unwrap();
+        })
+    });
+}
+
+// low sun through a rotated camera, long shadows of the tiles, without a normal pass
+#[test]
+fn lighting_directional() {
+    common::golden("lighting_directional", || {
+        let mut directional = Lighting::new(Size {
+            width: common::WIDTH,
+            height: common::HEIGHT,
+        });
+
+        Box::new(move |batch| {
+            let mut camera = Camera2D::new(Size {
+                width: common::WIDTH,
+                height: common::HEIGHT,
+            });
+            camera.set_position(Position {
+                x: 0.0,
+                y: 0.0,
+            });
+            camera.set_rotation(0.2);
+
+            directional.set_ambient(Color::new(0.3, 0.3, 0.3, 1.0));
+            directional.lights_mut().clear();
+            directional.occluders_mut().clear();
+
+            let mut sun = Light::directional(-30.0, Color::new(1.0, 0.95, 0.8, 1.0));
+            sun.radius = 40.0;
+            sun.shadows = Shadows::Soft(2.0);
+            directional.add_light(sun);
+            assert!(matches!(directional.lights()[0].kind, LightKind::Directional { .. }));
+
+            batch.end().unwrap();
+            batch
+                .begin_with(BatchConfig {
+                    camera: Some(camera.clone()),
+                    ..Default::default()
+                })
+                .unwrap();
+            batch.fill_rectangle(Rectangle::new(-100.0, -100.0, 200.0, 200.0), Color::new(0.6, 0.8, 0.6, 1.0));
+
+            for index in 0..4 {
+                let tile = Rectangle::new(-60.0 + index as f32 * 30.0, -10.0 + (index % 2) as f32 * 20.0, 16.0, 16.0);
+
+                batch.fill_rectangle(tile, Color::new(0.5, 0.3, 0.2, 1.0));
+                directional.add_occluder(Occluder::rectangle(tile));
+            }
+
+            batch.end().unwrap();
+
+            directional.draw(Some(&camera)).unwrap();
+
+            batch.begin().unwrap();
+        })
+    });
+}
+
+// nested clips over stripes, the inner one partly outside the outer one, and a clip through a zoomed camera
+#[test]
+fn clip_nested() {
+    common::golden("clip_nested", || {
+        Box::new(move |batch| {
+            let stripes = |batch: &mut Batch, color: Color<f32>| {
+                for index in 0..20 {
+                    batch.fill_rectangle(Rectangle::new(index as f32 * 8.0, 0.0, 4.0, 120.0), color);
+                }
+            };
+
+            stripes(batch, Color::new(0.3, 0.3, 0.3, 1.0));
+
+            batch.push_clip(Rectangle::new(8.0, 8.0, 96.0, 64.0)).unwrap();
+            stripes(batch, Color::new(1.0, 0.3, 0.3, 1.0));
+
+            batch.push_clip(Rectangle::new(64.0, 40.0, 80.0, 60.0)).unwrap();
+            stripes(batch, Color::new(0.3, 1.0, 0.3, 1.0));
+            assert_eq!(batch.clip_depth(), (2, 0));
+
+            batch.pop_clip().unwrap();
+            batch.fill_rectangle(Rectangle::new(0.0, 0.0, 24.0, 24.0), Color::new(0.3, 0.3, 1.0, 1.0));
+            batch.pop_clip().unwrap();
+            assert_eq!(batch.clip_depth(), (0, 0));
+
+            let mut camera = Camera2D::new(Size {
+                width: common::WIDTH,
+                height: common::HEIGHT,
+            });
+            camera.set_position(Position {
+                x: 0.0,
+                y: 0.0,
+            });
+            camera.set_zoom(2.0);
+
+            batch.end().unwrap();
+            batch
+                .begin_with(BatchConfig {
+                    camera: Some(camera),
+                    ..Default::default()
+                })
+                .unwrap();
+
+            batch.push_clip(Rectangle::new(20.0, -25.0, 15.0, 10.0)).unwrap();
+            batch.fill_circle((30.0, -20.0).into(), 12.0, 24, Color::new(1.0, 0.9, 0.2, 1.0));
+            batch.pop_clip().unwrap();
+
+            batch.end().unwrap();
+            batch.begin().unwrap();
+        })
+    });
+}
+
+// a circle mask with a polygon mask nested in it, a mask inside a clip and a masked render target
+#[test]
+fn mask_nested() {
+    common::golden("mask_nested", || {
+        Box::new(move |batch| {
+            let stripes = |batch: &mut Batch, color: Color<f32>| {
+                for index in 0..20 {
+                    batch.fill_rectangle(Rectangle::new(index as f32 * 8.0, 0.0, 4.0, 120.0), color);
+                }
+            };
+
+            stripes(batch, Color::new(0.3, 0.3, 0.3, 1.0));
+
+            batch
+                .push_mask(|batch| batch.fill_circle((56.0, 60.0).into(), 44.0, 48, Color::default()))
+                .unwrap();
+            stripes(batch, Color::new(1.0, 0.3, 0.3, 1.0));
+
+            batch
+                .push_mask(|batch| {
+                    batch.fill_polygon(
+                        &[(56.0, 100.0), (84.0, 36.0), (20.0, 76.0), (92.0, 76.0), (28.0, 36.0)].map(Into::into),
+                        Color::default(),
+                    );
+                    batch.fill_rectangle(Rectangle::new(88.0, 56.0, 40.0, 8.0), Color::default());
+                })
+                .unwrap();
+            batch.fill_rectangle(Rectangle::new(0.0, 0.0, 160.0, 120.0), Color::new(0.3, 1.0, 0.3, 1.0));
+            assert_eq!(batch.clip_depth(), (0, 2));
+
+            batch.pop_mask().unwrap();
+            batch.fill_rectangle(Rectangle::new(0.0, 22.0, 160.0, 8.0), Color::new(0.3, 0.3, 1.0, 1.0));
+            batch.pop_mask().unwrap();
+
+            batch.push_clip(Rectangle::new(108.0, 4.0, 48.0, 60.0)).unwrap();
+            batch
+                .push_mask(|batch| batch.fill_circle((132.0, 34.0).into(), 26.0, 32, Color::default()))
+                .unwrap();
+            batch.fill_rectangle(Rectangle::new(0.0, 0.0, 160.0, 120.0), Color::new(1.0, 0.9, 0.2, 1.0));
+            batch.pop_mask().unwrap();
+            batch.pop_clip().unwrap();
+            assert_eq!(batch.clip_depth(), (0, 0));
+
+            // masks draw into the stencil of the target
+            let plain = raw::RenderTarget::new((32, 32), false, raw::TextureConfig::default());
+            let target = raw::RenderTarget::new((32, 32), true, raw::TextureConfig::default());
+            let mut offscreen = Batch::new();
+
+            target.clear(raw::Color {
+                a: 0.0,
+                ..Default::default()
+            });
+
+            offscreen
+                .begin_with(BatchConfig {
+                    target: Some(plain),
+                    ..Default::default()
+                })
+                .unwrap();
+            assert!(offscreen.push_mask(|_| {}).is_err());
+            offscreen.end().unwrap();
+
+            offscreen
+                .begin_with(BatchConfig {
+                    target: Some(target.clone()),
+                    ..Default::default()
+                })
+                .unwrap();
+            offscreen
+                .push_mask(|batch| batch.fill_circle((16.0, 16.0).into(), 14.0, 32, Color::default()))
+                .unwrap();
+            offscreen.fill_rectangle(Rectangle::new(0.0, 0.0, 32.0, 32.0), Color::new(0.2, 0.9, 0.9, 1.0));
+            offscreen.end().unwrap();
+
+            let mut sprite = target.sprite();
+            sprite.set_target(Rectangle::new(124.0, 76.0, 32.0, 32.0));
+            batch.draw_sprite(&sprite);
+        })
+    });
+}
+
+// a shrunk checkerboard without and with mipmaps, an R8 coverage mask, linear and sRGB gray and HDR texels
+#[test]
+fn texture_formats() {
+    common::golden("texture_formats", || {
+        let smooth = raw::TextureConfig {
+            min_filter: raw::FilterMode::Linear,
+            mipmap_filter: raw::FilterMode::Linear,
+            ..Default::default()
+        };
+        let mut checkers = [false, true].map(|mipmaps| {
+            Sprite::new(raw::Texture::new(&checkerboard(64), (64, 64), raw::TextureConfig {
+                mipmaps,
+                ..smooth
+            }))
+        });
+        let mut coverage = Sprite::new(
+            adore::load_texture_from_bytes_with(include_bytes!("fonts/pixel_0.png"), raw::TextureConfig {
+                format: raw::TextureFormat::R8Unorm,
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+        let mut grays = [raw::TextureFormat::Rgba8UnormSrgb, raw::TextureFormat::Rgba8Unorm].map(|format| {
+            Sprite::new(raw::Texture::new(&[128, 128, 128, 255], (1, 1), raw::TextureConfig {
+                format,
+                ..Default::default()
+            }))
+        });
+        // outside of 0 to 1, halved back into it by the sprite color
+        let hdr = [2.0f32, 1.0, 0.5, 1.0]
+            .map(|channel| half::f16::from_f32(channel).to_le_bytes())
+            .concat();
+        let mut bright = Sprite::new(raw::Texture::new(&hdr, (1, 1), raw::TextureConfig {
+            format: raw::TextureFormat::Rgba16Float,
+            ..Default::default()
+        }));
+
+        Box::new(move |batch| {
+            assert_eq!(checkers[0].texture().mip_level_count(), 1);
+            assert_eq!(checkers[1].texture().mip_level_count(), 7);
+
+            for (index, checker) in checkers.iter_mut().enumerate() {
+                checker.set_target(Rectangle::new(4.0 + index as f32 * 24.0, 68.0, 20.0, 20.0));
+                batch.draw_sprite(checker);
+            }
+
+            coverage.set_target(Rectangle::new(56.0, 60.0, 100.0, 56.0));
+            batch.draw_sprite(&coverage);
+
+            for (index, gray) in grays.iter_mut().enumerate() {
+                gray.set_target(Rectangle::new(4.0 + index as f32 * 32.0, 8.0, 28.0, 48.0));
+                batch.draw_sprite(gray);
+            }
+
+            bright.set_target(Rectangle::new(84.0, 8.0, 48.0, 48.0));
+            bright.set_color(Color::new(0.5, 0.5, 0.5, 1.0));
+            batch.draw_sprite(&bright);
+        })
+    });
+}
+
+// glyph cache text and an R8 sprite tinted over colored stripes, coverage keeps the stripes around the glyphs
+#[test]
+fn single_channel_text() {
+    common::golden("single_channel_text", || {
+        let font = Font::from_bytes(include_bytes!("fonts/Cantarell-Regular.ttf")).unwrap();
+        let mut coverage = Sprite::new(
+            adore::load_texture_from_bytes_with(include_bytes!("fonts/pixel_0.png"), raw::TextureConfig {
+                format: raw::TextureFormat::R8Unorm,
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+
+        Box::new(move |batch| {
+            let stripes = [
+                Color::new(0.8, 0.2, 0.2, 1.0),
+                Color::new(0.2, 0.6, 0.3, 1.0),
+                Color::new(0.2, 0.3, 0.8, 1.0),
+            ];
+
+            for (index, color) in stripes.into_iter().enumerate() {
+                batch.fill_rectangle(Rectangle::new(0.0, index as f32 * 40.0, 160.0, 40.0), color);
+            }
+
+            batch.draw_text(
+                &font,
+                "Coverage",
+                Position {
+                    x: 4.0,
+                    y: 116.0,
+                },
+                24.0,
+                Color::default(),
+            );
+            batch.draw_text(
+                &font,
+                "tinted",
+                Position {
+                    x: 4.0,
+                    y: 76.0,
+                },
+                24.0,
+                Color::new(1.0, 0.9, 0.2, 1.0),
+            );
+
+            // the glyphs sit at the top of the texture
+            coverage.set_target(Rectangle::new(4.0, -20.0, 100.0, 56.0));
+            coverage.set_color(Color::new(0.1, 0.1, 0.1, 1.0));
+            batch.draw_sprite(&coverage);
+        })
+    });
+}
@@ -0,0 +1,231 @@
+#![allow(dead_code)]
+
+use std::{
+    cell::RefCell,
+    path::PathBuf,
+    rc::Rc,
+    sync::{
+        Mutex,
+        PoisonError,
+    },
+};
+
+use adore::{
+    Batch,
+    Game,
+    GameTime,
+    Pixels,
+    Readback,
+    Size,
+};
+
+//
+
+pub const WIDTH: u32 = 160;
+pub const HEIGHT: u32 = 120;
+
+/// Per channel difference a pixel may have before it counts as a mismatch.
+pub const TOLERANCE: u8 = 2;
+
+pub type Scene = Box<dyn FnMut(&mut Batch)>;
+
+/// The context is global, tests that render take turns.
+static GPU: Mutex<()> = Mutex::new(());
+
+//
+
+/// Panics if there is no software adapter to render golden images on, a missing one fails the tests instead of
+/// skipping them.
+pub fn require_software_adapter() {
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::default(),
+        compatible_surface: None,
+        force_fallback_adapter: true,
+    }));
+
+    assert!(
+        adapter.is_some(),
+        "No software adapter available to render golden images, install one like Mesa's llvmpipe"
+    );
+}
+
+/// Holds the context for the current test, until the returned guard is dropped.
+pub fn lock_gpu() -> std::sync::MutexGuard<'static, ()> {
+    // a failed test poisons the lock, which says nothing about the next one
+    GPU.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Renders `scene` into its own headless frame and compares it against `tests/golden/<name>.png`.
+pub fn golden(name: &'static str, scene: impl FnOnce() -> Scene) {
+    golden_with(adore::AdoreConfig::default(), name, scene);
+}
+
+/// `golden` with the rest of `config`, see `render_scenes_with`.
+pub fn golden_with(config: adore::AdoreConfig, name: &'static str, scene: impl FnOnce() -> Scene) {
+    let results = render_scenes_with(config, || vec![(name, scene())]);
+
+    assert_eq!(results.len(), 1);
+
+    if let Err(err) = compare(name, &results[0].1) {
+        panic!("{err}");
+    }
+}
+
+/// Renders every scene into its own headless frame and returns the pixels in scene order. The frame is a `WIDTH` by
+/// `HEIGHT` one, the rest of `config` applies, like a virtual resolution or multisampling.
+///
+/// `scenes` is called once the context exists, so it can load textures.
+pub fn render_scenes_with(config: adore::AdoreConfig, scenes: impl FnOnce() -> Vec<(&'static str, Scene)>) -> Vec<(&'static str, Pixels)> {
+    let _gpu = lock_gpu();
+    require_software_adapter();
+
+    let output = Rc::new(RefCell::new(vec![]));
+
+    let adore = adore::Adore::new(adore::AdoreConfig {
+        headless: Some(adore::HeadlessConfig {
+            width: WIDTH,
+            height: HEIGHT,
+            force_software: true,
+            ..Default::default()
+        }),
+        ..config
+    });
+
+    adore.run(SceneRunner {
+        batch: Batch::new(),
+
+        scenes: scenes(),
+        current: 0,
+
+        pending: None,
+        output: Rc::clone(&output),
+    });
+
+    Rc::try_unwrap(output).unwrap().into_inner()
+}
+
+struct SceneRunner {
+    batch: Batch,
+
+    scenes: Vec<(&'static str, Scene)>,
+    current: usize,
+
+    pending: Option<(&'static str, Readback)>,
+    output: Rc<RefCell<Vec<(&'static str, Pixels)>>>,
+}
+
+impl Game for SceneRunner {
+    fn resize(&mut self, size: Size<u32>) {
+        self.batch.resize(size);
+    }
+
+    fn update(&mut self, _game_time: GameTime) {
+        if let Some((name, readback)) = self.pending.take() {
+            self.output.borrow_mut().push((name, readback.wait().unwrap()));
+        }
+
+        if self.current == self.scenes.len() {
+            adore::abort();
+        }
+    }
+
+    fn draw(&mut self, _game_time: GameTime) {
+        let Some((name, scene)) = self.scenes.get_mut(self.current) else {
+            return;
+        };
+
+        self.batch.begin().unwrap();
+        scene(&mut self.batch);
+        self.batch.end().unwrap();
+
+        self.pending = Some((name, adore::read_frame().unwrap()));
+        self.current += 1;
+    }
+}
+
+//
+
+fn reference_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(format!("{name}.png"))
+}
+
+fn output_path(name: &str, suffix: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("golden")
+        .join(format!("{name}.{suffix}.png"))
+}
+
+/// Compares `pixels` against `tests/golden/<name>.png`.
+///
+/// Set `ADORE_BLESS=1` to write the references instead, missing ones fail the comparison otherwise.
+/// On mismatch the actual image and a diff image are written to `$CARGO_TARGET_TMPDIR/golden`, without a reference
+/// only the actual one.
+pub fn compare(name: &str, pixels: &Pixels) -> Result<(), String> {
+    let reference_path = reference_path(name);
+
+    if std::env::var("ADORE_BLESS").is_ok_and(|bless| bless == "1") {
+        pixels.save(&reference_path).map_err(|err| err.to_string())?;
+        eprintln!("Wrote reference image {:?}", reference_path);
+        return Ok(());
+    }
+
+    if !reference_path.exists() {
+        let actual_path = output_path(name, "actual");
+
+        std::fs::create_dir_all(actual_path.parent().unwrap()).map_err(|err| err.to_string())?;
+        pixels.save(&actual_path).map_err(|err| err.to_string())?;
+
+        return Err(format!(
+            "{name}: missing reference {:?}, see {:?} and rerun with ADORE_BLESS=1 to accept it",
+            reference_path, actual_path
+        ));
+    }
+
+    let reference = image::open(&reference_path).map_err(|err| err.to_string())?.to_rgba8();
+
+    if reference.dimensions() != (pixels.width, pixels.height) {
+        return Err(format!(
+            "{name}: size {}x{} does not match reference {:?}",
+            pixels.width,
+            pixels.height,
+            reference.dimensions()
+        ));
+    }
+
+    let mut diff = image::RgbaImage::new(pixels.width, pixels.height);
+    let mut mismatches = 0;
+
+    for (x, y, expected) in reference.enumerate_pixels() {
+        let actual = pixels.pixel(x, y);
+
+        let matches = expected.0.iter().zip(actual.iter()).all(|(e, a)| e.abs_diff(*a) <= TOLERANCE);
+
+        if matches {
+            let luma = (actual[0] as u32 + actual[1] as u32 + actual[2] as u32) / 3 / 4;
+            diff.put_pixel(x, y, image::Rgba([luma as u8, luma as u8, luma as u8, 255]));
+        } else {
+            mismatches += 1;
+            diff.put_pixel(x, y, image::Rgba([255, 0, 255, 255]));
+        }
+    }
+
+    if mismatches == 0 {
+        return Ok(());
+    }
+
+    let actual_path = output_path(name, "actual");
+    let diff_path = output_path(name, "diff");
+
+    std::fs::create_dir_all(actual_path.parent().unwrap()).map_err(|err| err.to_string())?;
+    pixels.save(&actual_path).map_err(|err| err.to_string())?;
+    diff.save(&diff_path).map_err(|err| err.to_string())?;
+
+    Err(format!(
+        "{name}: {mismatches} pixels differ by more than {TOLERANCE}, see {:?} and {:?}",
+        actual_path, diff_path
+    ))
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use adore::{
+    raw,
+    Batch,
+    BatchConfig,
+    Color,
+    Rectangle,
+    Sprite,
+};
+
+//
+
+const SAMPLE_COUNT: u32 = 4;
+
+fn config() -> adore::AdoreConfig {
+    adore::AdoreConfig {
+        sample_count: SAMPLE_COUNT,
+        ..Default::default()
+    }
+}
+
+// rotated sprites, a thin line and a circle, with smoothed edges
+#[test]
+fn msaa_shapes() {
+    common::golden_with(config(), "msaa_shapes", || {
+        let mut tiles = [
+            include_bytes!("../examples/dev/1.png").as_slice(),
+            include_bytes!("../examples/dev/2.png"),
+            include_bytes!("../examples/dev/3.png"),
+        ]
+        .map(|bytes| Sprite::new(adore::load_texture_from_bytes(bytes).unwrap()));
+
+        Box::new(move |batch| {
+            assert_eq!(adore::sample_count(), SAMPLE_COUNT);
+
+            for (index, tile) in tiles.iter_mut().enumerate() {
+                tile.set_target(Rectangle::new(12.0 + index as f32 * 48.0, 68.0, 36.0, 36.0));
+                tile.set_origin_center();
+                tile.set_rotation(0.3 + index as f32 * 0.25);
+                batch.draw_sprite(tile);
+            }
+
+            batch.fill_circle((40.0, 32.0).into(), 24.0, 32, Color::new(0.2, 0.6, 1.0, 1.0));
+            batch.draw_line((76.0, 8.0).into(), (152.0, 52.0).into(), 1.5, Color::new(1.0, 0.8, 0.2, 1.0));
+        })
+    });
+}
+
+// a masked, multisampled render target resolved and drawn into the frame
+#[test]
+fn msaa_target() {
+    common::golden_with(config(), "msaa_target", || {
+        let target = raw::RenderTarget::new((48, 48), true, raw::TextureConfig::default());
+        let mut offscreen = Batch::new();
+
+        Box::new(move |batch| {
+            target.clear(raw::Color {
+                a: 0.0,
+                ..Default::default()
+            });
+
+            offscreen
+                .begin_with(BatchConfig {
+                    target: Some(target.clone()),
+                    ..Default::default()
+                })
+                .unwrap();
+            offscreen
+                .push_mask(|batch| batch.fill_circle((24.0, 24.0).into(), 22.0, 32, Color::default()))
+                .unwrap();
+
+            for index in 0..6 {
+                offscreen.fill_rectangle(Rectangle::new(index as f32 * 8.0, 0.0, 4.0, 48.0), Color::new(1.0, 0.3, 0.3, 1.0));
+            }
+
+            offscreen.pop_mask().unwrap();
+            offscreen.end().unwrap();
+
+            let mut sprite = target.sprite();
+            sprite.set_target(Rectangle::new(56.0, 36.0, 48.0, 48.0));
+            sprite.set_origin_center();
+            sprite.set_rotation(0.5);
+            batch.draw_sprite(&sprite);
+        })
+    });
+}
+
+// the same masked stripes in a single sampled target of a multisampled frame, with hard edges
+#[test]
+fn msaa_single_sampled_target() {
+    common::golden_with(config(), "msaa_single_sampled_target", || {
+        let target = raw::RenderTarget::with_config((48, 48), raw::RenderTargetConfig {
+            depth: true,
+            sample_count: Some(1),
+            ..Default::default()
+        });
+        let mut offscreen = Batch::new();
+
+        Box::new(move |batch| {
+            target.clear(raw::Color {
+                a: 0.0,
+                ..Default::default()
+            });
+
+            offscreen
+                .begin_with(BatchConfig {
+                    target: Some(target.clone()),
+                    ..Default::default()
+                })
+                .unwrap();
+            offscreen
+                .push_mask(|batch| batch.fill_circle((24.0, 24.0).into(), 22.0, 32, Color::default()))
+                .unwrap();
+
+            for index in 0..6 {
+                offscreen.fill_rectangle(Rectangle::new(index as f32 * 8.0, 0.0, 4.0, 48.0), Color::new(1.0, 0.3, 0.3, 1.0));
+            }
+
+            offscreen.pop_mask().unwrap();
+            offscreen.end().unwrap();
+
+            let mut sprite = target.sprite();
+            sprite.set_target(Rectangle::new(56.0, 36.0, 48.0, 48.0));
+            batch.draw_sprite(&sprite);
+        })
+    });
+}
@@ -0,0 +1,242 @@
+mod common;
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+
+use adore::{
+    raw,
+    Batch,
+    BatchConfig,
+    Color,
+    Game,
+    GameTime,
+    Pixels,
+    PostEffect,
+    PostProcess,
+    Rectangle,
+    RenderTarget,
+    Size,
+    Sprite,
+};
+
+//
+
+const LUT_SIZE: u32 = 16;
+
+/// Sepia LUT strip, blue picks the slice, red and green the texel inside it.
+fn sepia_lut() -> Vec<u8> {
+    let mut bytes = vec![];
+    let max = (LUT_SIZE - 1) as f32;
+
+    for green in 0..LUT_SIZE {
+        for blue in 0..LUT_SIZE {
+            for red in 0..LUT_SIZE {
+                let gray = (red as f32 * 0.3 + green as f32 * 0.59 + blue as f32 * 0.11) / max;
+
+                bytes.extend([gray * 1.07, gray * 0.74, gray * 0.43].map(|channel| (channel.min(1.0) * 255.0) as u8));
+                bytes.push(255);
+            }
+        }
+    }
+
+    bytes
+}
+
+/// Stack set up before the frame is drawn, checks on the stack, and whether the frame is drawn into a target instead.
+type Step = (&'static str, Box<dyn FnMut(&mut PostProcess)>, bool);
+
+struct Runner {
+    batch: Batch,
+    offscreen: Batch,
+    tiles: Vec<Sprite>,
+    target: RenderTarget,
+    filtered: RenderTarget,
+    chain: PostProcess,
+
+    steps: Vec<Step>,
+    current: usize,
+    screenshots: Vec<(&'static str, raw::Screenshot)>,
+    frames: Rc<RefCell<Vec<(&'static str, Pixels)>>>,
+}
+
+impl Runner {
+    fn draw_scene(batch: &mut Batch, tiles: &mut [Sprite]) {
+        batch.fill_rectangle(Rectangle::new(0.0, 0.0, 160.0, 120.0), Color::new(0.1, 0.1, 0.2, 1.0));
+
+        for (index, tile) in tiles.iter_mut().enumerate() {
+            tile.set_target(Rectangle::new(
+                8.0 + (index % 3) as f32 * 52.0,
+                8.0 + (index / 3) as f32 * 56.0,
+                40.0,
+                40.0,
+            ));
+            batch.draw_sprite(tile);
+        }
+
+        // bright enough to bloom
+        batch.fill_rectangle(Rectangle::new(72.0, 52.0, 16.0, 16.0), Color::new(1.0, 1.0, 1.0, 1.0));
+    }
+}
+
+impl Game for Runner {
+    fn resize(&mut self, size: Size<u32>) {
+        self.batch.resize(size);
+    }
+
+    fn update(&mut self, _game_time: GameTime) {
+        // recorded when the previous frame ended
+        for (name, screenshot) in self.screenshots.drain(..) {
+            self.frames.borrow_mut().push((name, screenshot.wait().unwrap()));
+        }
+
+        let Some((_, setup, _)) = self.steps.get_mut(self.current) else {
+            adore::abort();
+            return;
+        };
+
+        setup(adore::post_process_mut());
+    }
+
+    fn draw(&mut self, _game_time: GameTime) {
+        let Some((name, _, offscreen)) = self.steps.get(self.current) else {
+            return;
+        };
+
+        self.batch.begin().unwrap();
+
+        if *offscreen {
+            // a chain of its own, applied to a render target inside the frame
+            self.offscreen
+                .begin_with(BatchConfig {
+                    target: Some(self.target.clone()),
+                    ..Default::default()
+                })
+                .unwrap();
+            Self::draw_scene(&mut self.offscreen, &mut self.tiles);
+            self.offscreen.end().unwrap();
+
+            self.chain.apply(&self.target, &self.filtered);
+
+            let mut filtered = self.filtered.sprite();
+            filtered.set_target(Rectangle::new(0.0, 0.0, 160.0, 120.0));
+            self.batch.draw_sprite(&filtered);
+        } else {
+            Self::draw_scene(&mut self.batch, &mut self.tiles);
+        }
+
+        self.batch.end().unwrap();
+
+        self.screenshots.push((name, raw::screenshot()));
+
+        self.current += 1;
+    }
+}
+
+#[test]
+fn post_process_golden_images() {
+    let _gpu = common::lock_gpu();
+    common::require_software_adapter();
+
+    let adore = adore::Adore::new(adore::AdoreConfig {
+        headless: Some(adore::HeadlessConfig {
+            width: common::WIDTH,
+            height: common::HEIGHT,
+            force_software: true,
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+
+    let tiles = [
+        include_bytes!("../examples/dev/1.png").as_slice(),
+        include_bytes!("../examples/dev/2.png"),
+        include_bytes!("../examples/dev/3.png"),
+        include_bytes!("../examples/dev/4.png"),
+        include_bytes!("../examples/dev/5.png"),
+        include_bytes!("../examples/dev/6.png"),
+    ]
+    .map(|bytes| Sprite::new(adore::load_texture_from_bytes(bytes).unwrap()))
+    .into_iter()
+    .collect();
+
+    let mut chain = PostProcess::default();
+    chain.push("blur", PostEffect::gaussian_blur(2.0));
+    chain.push(
+        "invert",
+        PostEffect::with_uniform(include_str!("shaders/invert.wgsl"), adore::cast(&[1.0f32, 0.0, 0.0, 0.0])),
+    );
+    chain.effect("invert").unwrap().set_uniform(adore::cast(&[0.75f32, 0.0, 0.0, 0.0]));
+    assert_eq!(chain.effect("blur").unwrap().passes(), 2);
+
+    let steps: Vec<Step> = vec![
+        // scanlines on a curved screen, split channels and dark corners
+        (
+            "post_process_retro",
+            Box::new(|stack| {
+                assert!(!stack.is_active());
+
+                stack.push("crt", PostEffect::crt(0.4, 0.1, 0.0));
+                stack.push("chromatic_aberration", PostEffect::chromatic_aberration(3.0));
+                stack.push("vignette", PostEffect::vignette(0.8, 1.0, 0.7));
+            }),
+            false,
+        ),
+        // bloom in front, sepia grading behind, the aberration kept but skipped
+        (
+            "post_process_graded",
+            Box::new(|stack| {
+                assert!(stack.remove("crt").is_some());
+                stack.set_enabled("chromatic_aberration", false);
+                stack.insert(0, "bloom", PostEffect::bloom(0.8, 1.5, 4.0));
+                stack.push("grade", PostEffect::color_grading(&sepia_lut(), LUT_SIZE, 1.0));
+
+                assert_eq!(stack.names().collect::<Vec<_>>(), [
+                    "bloom",
+                    "chromatic_aberration",
+                    "vignette",
+                    "grade"
+                ]);
+                assert!(!stack.is_enabled("chromatic_aberration"));
+                assert_eq!(stack.effect("bloom").unwrap().passes(), 4);
+            }),
+            false,
+        ),
+        // no effects on the frame, a blurred and mostly inverted render target drawn into it
+        (
+            "post_process_target",
+            Box::new(|stack| {
+                stack.clear();
+                assert!(!stack.is_active());
+            }),
+            true,
+        ),
+    ];
+
+    let frames = Rc::new(RefCell::new(vec![]));
+
+    adore.run(Runner {
+        batch: Batch::new(),
+        offscreen: Batch::new(),
+        tiles,
+        target: RenderTarget::new((common::WIDTH, common::HEIGHT), false, raw::TextureConfig::default()),
+        filtered: RenderTarget::new((common::WIDTH, common::HEIGHT), false, raw::TextureConfig::default()),
+        chain,
+
+        steps,
+        current: 0,
+        screenshots: vec![],
+        frames: Rc::clone(&frames),
+    });
+
+    let frames = frames.take();
+    assert_eq!(frames.len(), 3);
+
+    let failures = frames
+        .iter()
+        .filter_map(|(name, pixels)| common::compare(name, pixels).err())
+        .collect::<Vec<_>>();
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use std::{
+    cell::Cell,
+    path::PathBuf,
+    rc::Rc,
+};
+
+use adore::{
+    raw,
+    Batch,
+    Color,
+    Game,
+    GameTime,
+    Readback,
+    Rectangle,
+    Size,
+};
+
+//
+
+struct Runner {
+    batch: Batch,
+    path: PathBuf,
+    readback: Option<Readback>,
+    screenshot: Option<raw::Screenshot>,
+    checked: Rc<Cell<bool>>,
+}
+
+impl Game for Runner {
+    fn resize(&mut self, size: Size<u32>) {
+        self.batch.resize(size);
+    }
+
+    fn update(&mut self, _game_time: GameTime) {
+        let (Some(readback), Some(screenshot)) = (self.readback.take(), self.screenshot.take()) else {
+            return;
+        };
+
+        assert!(readback.is_submitted());
+        assert!(screenshot.is_submitted());
+
+        let frame = readback.wait().unwrap();
+        let screenshot = screenshot.wait().unwrap();
+
+        assert_eq!(frame.pixel(0, 0), [255, 0, 0, 255]);
+        assert_eq!(frame.pixel(common::WIDTH - 1, common::HEIGHT - 1), [255, 0, 0, 255]);
+        assert_eq!(screenshot.data, frame.data);
+
+        self.checked.set(true);
+        adore::abort();
+    }
+
+    fn draw(&mut self, _game_time: GameTime) {
+        if self.checked.get() || self.readback.is_some() {
+            return;
+        }
+
+        self.batch.begin().unwrap();
+        self.batch.fill_rectangle(
+            Rectangle::new(0.0, 0.0, common::WIDTH as f32, common::HEIGHT as f32),
+            Color::new(1.0, 0.0, 0.0, 1.0),
+        );
+        self.batch.end().unwrap();
+
+        let mut readback = adore::read_frame().unwrap();
+        let mut screenshot = raw::screenshot();
+        adore::screenshot(&self.path);
+
+        // generating the mip levels submits before the frame does
+        let texture = raw::Texture::new(&[255; 8 * 8 * 4], (8, 8), raw::TextureConfig {
+            mipmaps: true,
+            ..Default::default()
+        });
+        assert!(texture.mip_level_count() > 1);
+
+        assert!(!readback.is_submitted());
+        assert!(readback.try_read().is_none());
+        assert!(!screenshot.is_submitted());
+        assert!(screenshot.try_read().is_none());
+
+        self.readback = Some(readback);
+        self.screenshot = Some(screenshot);
+    }
+}
+
+#[test]
+fn readback_waits_for_the_frame() {
+    let _gpu = common::lock_gpu();
+    common::require_software_adapter();
+
+    let adore = adore::Adore::new(adore::AdoreConfig {
+        headless: Some(adore::HeadlessConfig {
+            width: common::WIDTH,
+            height: common::HEIGHT,
+            force_software: true,
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+
+    let checked = Rc::new(Cell::new(false));
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("readback_waits_for_the_frame.png");
+    _ = std::fs::remove_file(&path);
+
+    adore.run(Runner {
+        batch: Batch::new(),
+        path: path.clone(),
+        readback: None,
+        screenshot: None,
+        checked: Rc::clone(&checked),
+    });
+
+    assert!(checked.get());
+
+    // saved by the time the run returns
+    let saved = image::open(&path).unwrap().to_rgba8();
+    assert_eq!((saved.width(), saved.height()), (common::WIDTH, common::HEIGHT));
+    assert_eq!(saved.get_pixel(0, 0).0, [255, 0, 0, 255]);
+}
@@ -0,0 +1,11 @@
+struct Params {
+    amount: vec4<f32>,
+};
+@group(#uniform_group) @binding(0) var<uniform> params: Params;
+
+fn fragment(texel: vec4<f32>, color: vec4<f32>, texcoord: vec2<f32>) -> vec4<f32> {
+    let tinted = texel * color;
+    let gray = dot(tinted.rgb, vec3<f32>(0.299, 0.587, 0.114));
+
+    return vec4<f32>(mix(tinted.rgb, vec3<f32>(gray), params.amount.x), tinted.a);
+}
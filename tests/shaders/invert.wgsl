@@ -0,0 +1,10 @@
+struct Params {
+    amount: vec4<f32>,
+};
+@group(1) @binding(0) var<uniform> params: Params;
+
+fn effect(texcoord: vec2<f32>) -> vec4<f32> {
+    let color = source(texcoord);
+
+    return vec4<f32>(mix(color.rgb, vec3<f32>(1.0) - color.rgb, params.amount.x), color.a);
+}
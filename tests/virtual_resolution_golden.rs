@@ -0,0 +1,111 @@
+mod common;
+
+use adore::{
+    Position,
+    Rectangle,
+    ScalingPolicy,
+    Size,
+    Sprite,
+    Viewport,
+    VirtualResolutionConfig,
+};
+
+//
+
+const VIRTUAL: VirtualResolutionConfig = VirtualResolutionConfig {
+    width: 72,
+    height: 48,
+    policy: ScalingPolicy::Fit,
+};
+
+const WINDOW: Size<u32> = Size {
+    width: common::WIDTH,
+    height: common::HEIGHT,
+};
+
+#[test]
+fn viewport_placement() {
+    let viewport = |policy| {
+        Viewport::new(WINDOW, VirtualResolutionConfig {
+            policy,
+            ..VIRTUAL
+        })
+    };
+
+    let stretch = viewport(ScalingPolicy::Stretch);
+    assert_eq!(stretch.area, Rectangle::new(0.0, 0.0, 160.0, 120.0));
+    assert_eq!(stretch.scale, (160.0 / 72.0, 2.5));
+
+    // letterboxed above and below
+    let fit = viewport(ScalingPolicy::Fit);
+    assert_eq!(fit.area.width, 160.0);
+    assert!((fit.area.y - (120.0 - 48.0 * 160.0 / 72.0) / 2.0).abs() < 1e-4);
+    assert_eq!(fit.visible_area(), fit.area);
+
+    // cropped left and right
+    let fill = viewport(ScalingPolicy::Fill);
+    assert_eq!(fill.area, Rectangle::new(-10.0, 0.0, 180.0, 120.0));
+    assert_eq!(fill.visible_area(), Rectangle::new(0.0, 0.0, 160.0, 120.0));
+
+    let pixel_perfect = viewport(ScalingPolicy::PixelPerfect);
+    assert_eq!(pixel_perfect.area, Rectangle::new(8.0, 12.0, 144.0, 96.0));
+
+    // window pixels map back into virtual pixels, both from the top left
+    let mouse = pixel_perfect.to_virtual(Position {
+        x: 80.0,
+        y: 60.0,
+    });
+    assert_eq!(mouse, Position {
+        x: 36.0,
+        y: 24.0
+    });
+    assert_eq!(pixel_perfect.to_window(mouse), Position {
+        x: 80.0,
+        y: 60.0
+    });
+
+    // windows smaller than the resolution crop it at 1x
+    let small = Viewport::new(
+        Size {
+            width: 40,
+            height: 40,
+        },
+        VirtualResolutionConfig {
+            policy: ScalingPolicy::PixelPerfect,
+            ..VIRTUAL
+        },
+    );
+    assert_eq!(small.scale, (1.0, 1.0));
+    assert_eq!(small.visible_area(), Rectangle::new(0.0, 0.0, 40.0, 40.0));
+}
+
+// 3x2 tiles covering the virtual resolution exactly, fill covers both the viewport and the cropping
+#[test]
+fn virtual_resolution() {
+    let config = adore::AdoreConfig {
+        virtual_resolution: Some(VirtualResolutionConfig {
+            policy: ScalingPolicy::Fill,
+            ..VIRTUAL
+        }),
+        ..Default::default()
+    };
+
+    common::golden_with(config, "virtual_resolution", || {
+        let mut tiles = [
+            include_bytes!("../examples/dev/1.png").as_slice(),
+            include_bytes!("../examples/dev/2.png"),
+            include_bytes!("../examples/dev/3.png"),
+            include_bytes!("../examples/dev/4.png"),
+            include_bytes!("../examples/dev/5.png"),
+            include_bytes!("../examples/dev/6.png"),
+        ]
+        .map(|bytes| Sprite::new(adore::load_texture_from_bytes(bytes).unwrap()));
+
+        Box::new(move |batch| {
+            for (index, tile) in tiles.iter_mut().enumerate() {
+                tile.set_target(Rectangle::new((index % 3) as f32 * 24.0, (index / 3) as f32 * 24.0, 24.0, 24.0));
+                batch.draw_sprite(tile);
+            }
+        })
+    });
+}